@@ -0,0 +1,25 @@
+package ru.khlevnov;
+
+import java.util.List;
+import java.util.function.Supplier;
+
+public class Attributes {
+    sealed interface Shape permits Circle, Square {
+    }
+
+    record Circle(int radius) implements Shape {
+    }
+
+    record Square(int side) implements Shape {
+    }
+
+    static Supplier<String> first(List<String> names) {
+        class Local {
+            String get() {
+                return names.get(0);
+            }
+        }
+        Local local = new Local();
+        return () -> local.get();
+    }
+}
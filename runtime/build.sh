@@ -0,0 +1,9 @@
+#!/bin/sh
+# Compiles the bootstrap runtime into rt.jar, which is embedded into smerdvm.
+set -e
+cd "$(dirname "$0")"
+
+rm -rf build
+javac --patch-module java.base=src -d build $(find src -name '*.java')
+jar --create --file rt.jar --no-manifest --date 2000-01-01T00:00:00Z -C build .
+rm -rf build
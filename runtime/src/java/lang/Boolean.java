@@ -0,0 +1,24 @@
+package java.lang;
+
+public final class Boolean {
+    @SuppressWarnings("unchecked")
+    public static final Class<Boolean> TYPE = (Class<Boolean>) Class.getPrimitiveClass("boolean");
+
+    private final boolean value;
+
+    public Boolean(boolean value) {
+        this.value = value;
+    }
+
+    public static Boolean valueOf(boolean value) {
+        return new Boolean(value);
+    }
+
+    public boolean booleanValue() {
+        return value;
+    }
+
+    public String toString() {
+        return "" + value;
+    }
+}
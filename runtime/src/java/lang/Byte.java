@@ -0,0 +1,24 @@
+package java.lang;
+
+public final class Byte {
+    @SuppressWarnings("unchecked")
+    public static final Class<Byte> TYPE = (Class<Byte>) Class.getPrimitiveClass("byte");
+
+    private final byte value;
+
+    public Byte(byte value) {
+        this.value = value;
+    }
+
+    public static Byte valueOf(byte value) {
+        return new Byte(value);
+    }
+
+    public byte byteValue() {
+        return value;
+    }
+
+    public String toString() {
+        return "" + value;
+    }
+}
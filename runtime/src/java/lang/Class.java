@@ -0,0 +1,76 @@
+package java.lang;
+
+import java.lang.invoke.MethodHandle;
+import java.lang.invoke.MethodHandles;
+import java.lang.invoke.MethodType;
+
+public final class Class<T> {
+    // the descriptor of the type, such as I, V, [I or Ljava/lang/String;, as
+    // the VM creates the instances
+    private final String descriptor;
+
+    private Class(String descriptor) {
+        this.descriptor = descriptor;
+    }
+
+    public native String getName();
+
+    public native String getSimpleName();
+
+    public native String getCanonicalName();
+
+    public native Class<? super T> getSuperclass();
+
+    public native boolean isInterface();
+
+    public native boolean isInstance(Object obj);
+
+    public native boolean isEnum();
+
+    public native Class<?> arrayType();
+
+    public native boolean desiredAssertionStatus();
+
+    public boolean isArray() {
+        return descriptor.charAt(0) == '[';
+    }
+
+    public boolean isPrimitive() {
+        char c = descriptor.charAt(0);
+        return c != 'L' && c != '[';
+    }
+
+    @SuppressWarnings("unchecked")
+    public T cast(Object obj) {
+        if (obj != null && !isInstance(obj)) {
+            throw new ClassCastException("Cannot cast " + obj.getClass().getName() + " to " + getName());
+        }
+        return (T) obj;
+    }
+
+    public T[] getEnumConstants() {
+        T[] values = getEnumConstantsShared();
+        return values != null ? values.clone() : null;
+    }
+
+    // the constants values() returns, or null if this is not an enum class
+    @SuppressWarnings("unchecked")
+    T[] getEnumConstantsShared() {
+        if (!isEnum()) {
+            return null;
+        }
+        try {
+            MethodType type = MethodType.methodType(arrayType());
+            MethodHandle values = MethodHandles.lookup().findStatic(this, "values", type);
+            return (T[]) values.invoke();
+        } catch (Throwable e) {
+            return null;
+        }
+    }
+
+    public String toString() {
+        return (isInterface() ? "interface " : (isPrimitive() ? "" : "class ")) + getName();
+    }
+
+    static native Class<?> getPrimitiveClass(String name);
+}
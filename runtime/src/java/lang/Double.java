@@ -0,0 +1,24 @@
+package java.lang;
+
+public final class Double {
+    @SuppressWarnings("unchecked")
+    public static final Class<Double> TYPE = (Class<Double>) Class.getPrimitiveClass("double");
+
+    private final double value;
+
+    public Double(double value) {
+        this.value = value;
+    }
+
+    public static Double valueOf(double value) {
+        return new Double(value);
+    }
+
+    public double doubleValue() {
+        return value;
+    }
+
+    public String toString() {
+        return "" + value;
+    }
+}
@@ -0,0 +1,24 @@
+package java.lang;
+
+public final class Float {
+    @SuppressWarnings("unchecked")
+    public static final Class<Float> TYPE = (Class<Float>) Class.getPrimitiveClass("float");
+
+    private final float value;
+
+    public Float(float value) {
+        this.value = value;
+    }
+
+    public static Float valueOf(float value) {
+        return new Float(value);
+    }
+
+    public float floatValue() {
+        return value;
+    }
+
+    public String toString() {
+        return "" + value;
+    }
+}
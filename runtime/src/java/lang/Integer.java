@@ -0,0 +1,24 @@
+package java.lang;
+
+public final class Integer {
+    @SuppressWarnings("unchecked")
+    public static final Class<Integer> TYPE = (Class<Integer>) Class.getPrimitiveClass("int");
+
+    private final int value;
+
+    public Integer(int value) {
+        this.value = value;
+    }
+
+    public static Integer valueOf(int value) {
+        return new Integer(value);
+    }
+
+    public int intValue() {
+        return value;
+    }
+
+    public String toString() {
+        return "" + value;
+    }
+}
@@ -0,0 +1,24 @@
+package java.lang;
+
+public final class Long {
+    @SuppressWarnings("unchecked")
+    public static final Class<Long> TYPE = (Class<Long>) Class.getPrimitiveClass("long");
+
+    private final long value;
+
+    public Long(long value) {
+        this.value = value;
+    }
+
+    public static Long valueOf(long value) {
+        return new Long(value);
+    }
+
+    public long longValue() {
+        return value;
+    }
+
+    public String toString() {
+        return "" + value;
+    }
+}
@@ -0,0 +1,24 @@
+package java.lang;
+
+public final class Short {
+    @SuppressWarnings("unchecked")
+    public static final Class<Short> TYPE = (Class<Short>) Class.getPrimitiveClass("short");
+
+    private final short value;
+
+    public Short(short value) {
+        this.value = value;
+    }
+
+    public static Short valueOf(short value) {
+        return new Short(value);
+    }
+
+    public short shortValue() {
+        return value;
+    }
+
+    public String toString() {
+        return "" + value;
+    }
+}
@@ -0,0 +1,106 @@
+package java.lang;
+
+public final class String implements java.io.Serializable {
+    static final byte LATIN1 = 0;
+    static final byte UTF16 = 1;
+
+    private final byte[] value;
+    private final byte coder;
+    private int hash;
+
+    public String() {
+        value = new byte[0];
+        coder = LATIN1;
+    }
+
+    public String(char[] value) {
+        boolean latin1 = true;
+        for (char c : value) {
+            if (c > 0xff) {
+                latin1 = false;
+                break;
+            }
+        }
+
+        if (latin1) {
+            this.value = new byte[value.length];
+            for (int i = 0; i < value.length; i++) {
+                this.value[i] = (byte) value[i];
+            }
+            coder = LATIN1;
+        } else {
+            // two bytes per character, low byte first
+            this.value = new byte[value.length << 1];
+            for (int i = 0; i < value.length; i++) {
+                this.value[i << 1] = (byte) value[i];
+                this.value[(i << 1) + 1] = (byte) (value[i] >> 8);
+            }
+            coder = UTF16;
+        }
+    }
+
+    String(byte[] value, byte coder) {
+        this.value = value;
+        this.coder = coder;
+    }
+
+    public int length() {
+        return value.length >> coder;
+    }
+
+    public boolean isEmpty() {
+        return value.length == 0;
+    }
+
+    public char charAt(int index) {
+        if (index < 0 || index >= length()) {
+            throw new StringIndexOutOfBoundsException();
+        }
+        if (coder == LATIN1) {
+            return (char) (value[index] & 0xff);
+        }
+        return (char) ((value[index << 1] & 0xff) | (value[(index << 1) + 1] & 0xff) << 8);
+    }
+
+    public boolean equals(Object anObject) {
+        if (this == anObject) {
+            return true;
+        }
+        if (!(anObject instanceof String)) {
+            return false;
+        }
+
+        String aString = (String) anObject;
+        if (coder != aString.coder || value.length != aString.value.length) {
+            return false;
+        }
+        for (int i = 0; i < value.length; i++) {
+            if (value[i] != aString.value[i]) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    public int hashCode() {
+        int h = hash;
+        if (h == 0) {
+            int length = length();
+            for (int i = 0; i < length; i++) {
+                h = 31 * h + charAt(i);
+            }
+            hash = h;
+        }
+        return h;
+    }
+
+    public String toString() {
+        return this;
+    }
+
+    public static String valueOf(Object obj) {
+        return obj == null ? "null" : obj.toString();
+    }
+
+    public native String intern();
+}
@@ -0,0 +1,180 @@
+package java.lang;
+
+public final class StringBuilder implements java.io.Serializable {
+    private char[] value;
+    private int count;
+
+    public StringBuilder() {
+        this(16);
+    }
+
+    public StringBuilder(int capacity) {
+        value = new char[capacity];
+    }
+
+    public StringBuilder(String str) {
+        this(str.length() + 16);
+        append(str);
+    }
+
+    public int length() {
+        return count;
+    }
+
+    public char charAt(int index) {
+        checkIndex(index, count);
+        return value[index];
+    }
+
+    public void setCharAt(int index, char ch) {
+        checkIndex(index, count);
+        value[index] = ch;
+    }
+
+    public void setLength(int newLength) {
+        if (newLength < 0) {
+            throw new StringIndexOutOfBoundsException();
+        }
+        ensureCapacity(newLength);
+        for (int i = count; i < newLength; i++) {
+            value[i] = '\0';
+        }
+        count = newLength;
+    }
+
+    public StringBuilder append(Object obj) {
+        return append(String.valueOf(obj));
+    }
+
+    public StringBuilder append(String str) {
+        if (str == null) {
+            str = "null";
+        }
+        int length = str.length();
+        ensureCapacity(count + length);
+        for (int i = 0; i < length; i++) {
+            value[count++] = str.charAt(i);
+        }
+        return this;
+    }
+
+    public StringBuilder append(char[] str) {
+        ensureCapacity(count + str.length);
+        for (char c : str) {
+            value[count++] = c;
+        }
+        return this;
+    }
+
+    public StringBuilder append(boolean b) {
+        return append(b ? "true" : "false");
+    }
+
+    public StringBuilder append(char c) {
+        ensureCapacity(count + 1);
+        value[count++] = c;
+        return this;
+    }
+
+    public StringBuilder append(int i) {
+        return append("" + i);
+    }
+
+    public StringBuilder append(long l) {
+        return append("" + l);
+    }
+
+    public StringBuilder append(float f) {
+        return append("" + f);
+    }
+
+    public StringBuilder append(double d) {
+        return append("" + d);
+    }
+
+    public StringBuilder insert(int offset, String str) {
+        if (offset < 0 || offset > count) {
+            throw new StringIndexOutOfBoundsException();
+        }
+        if (str == null) {
+            str = "null";
+        }
+        int length = str.length();
+        ensureCapacity(count + length);
+        for (int i = count - 1; i >= offset; i--) {
+            value[i + length] = value[i];
+        }
+        for (int i = 0; i < length; i++) {
+            value[offset + i] = str.charAt(i);
+        }
+        count += length;
+        return this;
+    }
+
+    public StringBuilder insert(int offset, char c) {
+        return insert(offset, new String(new char[] {c}));
+    }
+
+    public StringBuilder delete(int start, int end) {
+        if (end > count) {
+            end = count;
+        }
+        if (start < 0 || start > end) {
+            throw new StringIndexOutOfBoundsException();
+        }
+        int length = end - start;
+        for (int i = end; i < count; i++) {
+            value[i - length] = value[i];
+        }
+        count -= length;
+        return this;
+    }
+
+    public StringBuilder deleteCharAt(int index) {
+        checkIndex(index, count);
+        return delete(index, index + 1);
+    }
+
+    public StringBuilder reverse() {
+        for (int i = 0, j = count - 1; i < j; i++, j--) {
+            char c = value[i];
+            value[i] = value[j];
+            value[j] = c;
+        }
+        // put the surrogate pairs, now low surrogate first, back in order
+        for (int i = 0; i < count - 1; i++) {
+            if (Character.isLowSurrogate(value[i]) && Character.isHighSurrogate(value[i + 1])) {
+                char c = value[i];
+                value[i] = value[i + 1];
+                value[i + 1] = c;
+                i++;
+            }
+        }
+        return this;
+    }
+
+    public String toString() {
+        char[] chars = new char[count];
+        for (int i = 0; i < count; i++) {
+            chars[i] = value[i];
+        }
+        return new String(chars);
+    }
+
+    private void ensureCapacity(int minimumCapacity) {
+        if (minimumCapacity > value.length) {
+            int capacity = value.length * 2 + 2;
+            char[] chars = new char[capacity < minimumCapacity ? minimumCapacity : capacity];
+            for (int i = 0; i < count; i++) {
+                chars[i] = value[i];
+            }
+            value = chars;
+        }
+    }
+
+    private static void checkIndex(int index, int length) {
+        if (index < 0 || index >= length) {
+            throw new StringIndexOutOfBoundsException();
+        }
+    }
+}
@@ -0,0 +1,29 @@
+package java.lang;
+
+public final class System {
+    private System() {
+    }
+
+    public static native void exit(int status);
+
+    public static String getProperty(String key) {
+        checkKey(key);
+        return getProperty0(key);
+    }
+
+    public static String getProperty(String key, String def) {
+        String value = getProperty(key);
+        return value == null ? def : value;
+    }
+
+    private static void checkKey(String key) {
+        if (key == null) {
+            throw new NullPointerException("key can't be null");
+        }
+        if (key.isEmpty()) {
+            throw new IllegalArgumentException("key can't be empty");
+        }
+    }
+
+    private static native String getProperty0(String key);
+}
@@ -0,0 +1,12 @@
+package java.lang.invoke;
+
+// converts the arguments to the types of the target and its return value
+// back, the way asType does
+final class AdaptedMethodHandle extends MethodHandle {
+    private final MethodHandle target;
+
+    AdaptedMethodHandle(MethodHandle target, MethodType type) {
+        super(type);
+        this.target = target;
+    }
+}
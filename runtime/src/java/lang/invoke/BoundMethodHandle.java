@@ -0,0 +1,13 @@
+package java.lang.invoke;
+
+// passes the argument as the leading one of the target
+final class BoundMethodHandle extends MethodHandle {
+    private final MethodHandle target;
+    private final Object argument;
+
+    BoundMethodHandle(MethodHandle target, Object argument) {
+        super(target.type().dropParameterTypes(0, 1));
+        this.target = target;
+        this.argument = argument;
+    }
+}
@@ -0,0 +1,34 @@
+package java.lang.invoke;
+
+// invokes a method or accesses a field, the way the bytecode for the kind of
+// reference does
+final class DirectMethodHandle extends MethodHandle {
+    static final int REF_getField = 1;
+    static final int REF_getStatic = 2;
+    static final int REF_putField = 3;
+    static final int REF_putStatic = 4;
+    static final int REF_invokeVirtual = 5;
+    static final int REF_invokeStatic = 6;
+    static final int REF_invokeSpecial = 7;
+    static final int REF_newInvokeSpecial = 8;
+    static final int REF_invokeInterface = 9;
+
+    private final int kind;
+    private final Class<?> clazz;
+    private final String name;
+    private final String descriptor;
+
+    private DirectMethodHandle(MethodType type, int kind, Class<?> clazz, String name, String descriptor) {
+        super(type);
+        this.kind = kind;
+        this.clazz = clazz;
+        this.name = name;
+        this.descriptor = descriptor;
+    }
+
+    static native MethodHandle make(int kind, Class<?> refc, String name, MethodType type)
+            throws NoSuchMethodException, IllegalAccessException;
+
+    static native MethodHandle makeField(int kind, Class<?> refc, String name, Class<?> type)
+            throws NoSuchFieldException, IllegalAccessException;
+}
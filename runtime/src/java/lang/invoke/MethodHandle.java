@@ -0,0 +1,55 @@
+package java.lang.invoke;
+
+import java.lang.annotation.ElementType;
+import java.lang.annotation.Retention;
+import java.lang.annotation.RetentionPolicy;
+import java.lang.annotation.Target;
+
+public abstract class MethodHandle {
+    // marks the methods javac compiles calls to with the descriptor of the
+    // call site, which the VM invokes the handle with
+    @Target({ElementType.METHOD})
+    @Retention(RetentionPolicy.RUNTIME)
+    @interface PolymorphicSignature {
+    }
+
+    private final MethodType type;
+
+    MethodHandle(MethodType type) {
+        this.type = type;
+    }
+
+    public MethodType type() {
+        return type;
+    }
+
+    @PolymorphicSignature
+    public final native Object invokeExact(Object... args) throws Throwable;
+
+    @PolymorphicSignature
+    public final native Object invoke(Object... args) throws Throwable;
+
+    public MethodHandle asType(MethodType newType) {
+        if (newType.equals(type)) {
+            return this;
+        }
+        if (!canConvert(type, newType)) {
+            throw new WrongMethodTypeException("cannot convert " + this + " to " + newType);
+        }
+        return new AdaptedMethodHandle(this, newType);
+    }
+
+    public MethodHandle bindTo(Object x) {
+        if (type.parameterCount() == 0 || type.parameterType(0).isPrimitive()) {
+            throw new IllegalArgumentException("no leading reference parameter: " + x);
+        }
+        return new BoundMethodHandle(this, type.parameterType(0).cast(x));
+    }
+
+    public String toString() {
+        return "MethodHandle" + type;
+    }
+
+    // whether asType can adapt a handle of the type to the new one
+    private static native boolean canConvert(MethodType type, MethodType newType);
+}
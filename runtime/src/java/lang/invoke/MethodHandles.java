@@ -0,0 +1,63 @@
+package java.lang.invoke;
+
+import static java.lang.invoke.DirectMethodHandle.*;
+
+public class MethodHandles {
+    private MethodHandles() {
+    }
+
+    // there are no access checks, so every lookup is a full-privilege one
+    public static Lookup lookup() {
+        return new Lookup();
+    }
+
+    public static Lookup publicLookup() {
+        return new Lookup();
+    }
+
+    public static final class Lookup {
+        Lookup() {
+        }
+
+        public MethodHandle findVirtual(Class<?> refc, String name, MethodType type)
+                throws NoSuchMethodException, IllegalAccessException {
+            int kind = refc.isInterface() ? REF_invokeInterface : REF_invokeVirtual;
+            return DirectMethodHandle.make(kind, refc, name, type);
+        }
+
+        public MethodHandle findStatic(Class<?> refc, String name, MethodType type)
+                throws NoSuchMethodException, IllegalAccessException {
+            return DirectMethodHandle.make(REF_invokeStatic, refc, name, type);
+        }
+
+        public MethodHandle findConstructor(Class<?> refc, MethodType type)
+                throws NoSuchMethodException, IllegalAccessException {
+            return DirectMethodHandle.make(REF_newInvokeSpecial, refc, "<init>", type);
+        }
+
+        public MethodHandle findGetter(Class<?> refc, String name, Class<?> type)
+                throws NoSuchFieldException, IllegalAccessException {
+            return DirectMethodHandle.makeField(REF_getField, refc, name, type);
+        }
+
+        public MethodHandle findSetter(Class<?> refc, String name, Class<?> type)
+                throws NoSuchFieldException, IllegalAccessException {
+            return DirectMethodHandle.makeField(REF_putField, refc, name, type);
+        }
+
+        public MethodHandle findStaticGetter(Class<?> refc, String name, Class<?> type)
+                throws NoSuchFieldException, IllegalAccessException {
+            return DirectMethodHandle.makeField(REF_getStatic, refc, name, type);
+        }
+
+        public MethodHandle findStaticSetter(Class<?> refc, String name, Class<?> type)
+                throws NoSuchFieldException, IllegalAccessException {
+            return DirectMethodHandle.makeField(REF_putStatic, refc, name, type);
+        }
+
+        public MethodHandle bind(Object receiver, String name, MethodType type)
+                throws NoSuchMethodException, IllegalAccessException {
+            return findVirtual(receiver.getClass(), name, type).bindTo(receiver);
+        }
+    }
+}
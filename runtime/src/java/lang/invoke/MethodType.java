@@ -0,0 +1,127 @@
+package java.lang.invoke;
+
+public final class MethodType {
+    private final Class<?> rtype;
+    private final Class<?>[] ptypes;
+
+    private MethodType(Class<?> rtype, Class<?>[] ptypes) {
+        if (rtype == null) {
+            throw new NullPointerException();
+        }
+        for (Class<?> ptype : ptypes) {
+            if (ptype == null) {
+                throw new NullPointerException();
+            }
+            if (ptype == void.class) {
+                throw new IllegalArgumentException("parameter type cannot be void");
+            }
+        }
+        this.rtype = rtype;
+        this.ptypes = ptypes;
+    }
+
+    public static MethodType methodType(Class<?> rtype) {
+        return new MethodType(rtype, new Class<?>[0]);
+    }
+
+    public static MethodType methodType(Class<?> rtype, Class<?> ptype0) {
+        return new MethodType(rtype, new Class<?>[] {ptype0});
+    }
+
+    public static MethodType methodType(Class<?> rtype, Class<?>[] ptypes) {
+        return new MethodType(rtype, copy(ptypes, 0, ptypes.length));
+    }
+
+    public static MethodType methodType(Class<?> rtype, Class<?> ptype0, Class<?>... ptypes) {
+        Class<?>[] all = new Class<?>[ptypes.length + 1];
+        all[0] = ptype0;
+        for (int i = 0; i < ptypes.length; i++) {
+            all[i + 1] = ptypes[i];
+        }
+        return new MethodType(rtype, all);
+    }
+
+    public static MethodType methodType(Class<?> rtype, MethodType ptypes) {
+        return new MethodType(rtype, ptypes.ptypes);
+    }
+
+    public Class<?> returnType() {
+        return rtype;
+    }
+
+    public Class<?> parameterType(int num) {
+        return ptypes[num];
+    }
+
+    public int parameterCount() {
+        return ptypes.length;
+    }
+
+    public Class<?>[] parameterArray() {
+        return copy(ptypes, 0, ptypes.length);
+    }
+
+    public MethodType changeReturnType(Class<?> nrtype) {
+        return new MethodType(nrtype, ptypes);
+    }
+
+    public MethodType changeParameterType(int num, Class<?> nptype) {
+        Class<?>[] nptypes = parameterArray();
+        nptypes[num] = nptype;
+        return new MethodType(rtype, nptypes);
+    }
+
+    public MethodType dropParameterTypes(int start, int end) {
+        if (start < 0 || start > end || end > ptypes.length) {
+            throw new IndexOutOfBoundsException();
+        }
+        Class<?>[] nptypes = new Class<?>[ptypes.length - (end - start)];
+        for (int i = 0; i < start; i++) {
+            nptypes[i] = ptypes[i];
+        }
+        for (int i = end; i < ptypes.length; i++) {
+            nptypes[i - (end - start)] = ptypes[i];
+        }
+        return new MethodType(rtype, nptypes);
+    }
+
+    public boolean equals(Object x) {
+        if (this == x) {
+            return true;
+        }
+        if (!(x instanceof MethodType)) {
+            return false;
+        }
+
+        MethodType that = (MethodType) x;
+        if (rtype != that.rtype || ptypes.length != that.ptypes.length) {
+            return false;
+        }
+        for (int i = 0; i < ptypes.length; i++) {
+            if (ptypes[i] != that.ptypes[i]) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    public int hashCode() {
+        int hashCode = 31 + rtype.hashCode();
+        for (Class<?> ptype : ptypes) {
+            hashCode = 31 * hashCode + ptype.hashCode();
+        }
+        return hashCode;
+    }
+
+    public native String toString();
+
+    public native String toMethodDescriptorString();
+
+    private static Class<?>[] copy(Class<?>[] ptypes, int start, int end) {
+        Class<?>[] copy = new Class<?>[end - start];
+        for (int i = start; i < end; i++) {
+            copy[i - start] = ptypes[i];
+        }
+        return copy;
+    }
+}
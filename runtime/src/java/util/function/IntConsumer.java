@@ -0,0 +1,16 @@
+package java.util.function;
+
+import java.util.Objects;
+
+@FunctionalInterface
+public interface IntConsumer {
+    void accept(int value);
+
+    default IntConsumer andThen(IntConsumer after) {
+        Objects.requireNonNull(after);
+        return (int t) -> {
+            accept(t);
+            after.accept(t);
+        };
+    }
+}
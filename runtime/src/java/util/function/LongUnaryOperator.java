@@ -0,0 +1,6 @@
+package java.util.function;
+
+@FunctionalInterface
+public interface LongUnaryOperator {
+    long applyAsLong(long operand);
+}
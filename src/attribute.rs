@@ -0,0 +1,784 @@
+use crate::class_file::{
+    check_constant, ClassFormatError, ClassFormatErrorKind, ClassReader, ConstantInfo, FromReader,
+    CONSTANT_CLASS, CONSTANT_DOUBLE, CONSTANT_FLOAT, CONSTANT_INTEGER, CONSTANT_LONG,
+    CONSTANT_METHOD_HANDLE, CONSTANT_STRING, LOADABLE_CONSTANTS,
+};
+use std::io::Cursor;
+
+#[derive(Debug)]
+pub(crate) struct AttributeInfo {
+    pub(crate) attribute_name_index: u16,
+    pub(crate) attribute: Attribute,
+}
+
+#[derive(Debug)]
+pub enum Attribute {
+    ConstantValue(u16),
+    Code(CodeAttribute),
+    StackMapTable(Vec<StackMapFrame>),
+    Exceptions(Vec<u16>),
+    InnerClasses(Vec<InnerClass>),
+    EnclosingMethod(EnclosingMethod),
+    Synthetic,
+    Signature(u16),
+    SourceFile(u16),
+    SourceDebugExtension(Box<[u8]>),
+    LineNumberTable(Vec<LineNumber>),
+    LocalVariableTable(Vec<LocalVariable>),
+    LocalVariableTypeTable(Vec<LocalVariable>),
+    Deprecated,
+    RuntimeVisibleAnnotations(Vec<Annotation>),
+    RuntimeInvisibleAnnotations(Vec<Annotation>),
+    RuntimeVisibleParameterAnnotations(Vec<Vec<Annotation>>),
+    RuntimeInvisibleParameterAnnotations(Vec<Vec<Annotation>>),
+    RuntimeVisibleTypeAnnotations(Vec<TypeAnnotation>),
+    RuntimeInvisibleTypeAnnotations(Vec<TypeAnnotation>),
+    AnnotationDefault(ElementValue),
+    BootstrapMethods(Vec<BootstrapMethod>),
+    MethodParameters(Vec<MethodParameter>),
+    NestHost(u16),
+    NestMembers(Vec<u16>),
+    Record(Vec<RecordComponent>),
+    PermittedSubclasses(Vec<u16>),
+    Unknown(Box<[u8]>),
+}
+
+#[derive(Debug, Default)]
+pub struct CodeAttribute {
+    /// The offset of the code in the class file.
+    pub(crate) offset: usize,
+    pub(crate) max_stack: usize,
+    pub(crate) max_locals: usize,
+    pub(crate) code: Vec<u8>,
+    pub(crate) exception_table: Vec<ExceptionTableEntry>,
+    pub(crate) attributes: Vec<AttributeInfo>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ExceptionTableEntry {
+    pub(crate) start_pc: usize,
+    pub(crate) end_pc: usize,
+    pub(crate) handler_pc: usize,
+    pub(crate) catch_type: u16,
+}
+
+#[derive(Debug)]
+pub enum StackMapFrame {
+    Same {
+        frame_type: u8,
+    },
+    SameLocals1StackItem {
+        frame_type: u8,
+        stack: VerificationTypeInfo,
+    },
+    SameLocals1StackItemExtended {
+        offset_delta: u16,
+        stack: VerificationTypeInfo,
+    },
+    Chop {
+        frame_type: u8,
+        offset_delta: u16,
+    },
+    SameExtended {
+        offset_delta: u16,
+    },
+    Append {
+        frame_type: u8,
+        offset_delta: u16,
+        locals: Vec<VerificationTypeInfo>,
+    },
+    Full {
+        offset_delta: u16,
+        locals: Vec<VerificationTypeInfo>,
+        stack: Vec<VerificationTypeInfo>,
+    },
+}
+
+#[derive(Debug)]
+pub enum VerificationTypeInfo {
+    Top,
+    Integer,
+    Float,
+    Double,
+    Long,
+    Null,
+    UninitializedThis,
+    Object { cpool_index: u16 },
+    Uninitialized { offset: u16 },
+}
+
+#[derive(Debug)]
+pub struct InnerClass {
+    pub(crate) inner_class_info_index: u16,
+    pub(crate) outer_class_info_index: u16,
+    pub(crate) inner_name_index: u16,
+    pub(crate) inner_class_access_flags: u16,
+}
+
+#[derive(Debug)]
+pub struct EnclosingMethod {
+    pub(crate) class_index: u16,
+    pub(crate) method_index: u16,
+}
+
+#[derive(Debug)]
+pub struct LineNumber {
+    pub(crate) start_pc: u16,
+    pub(crate) line_number: u16,
+}
+
+#[derive(Debug)]
+pub struct LocalVariable {
+    pub(crate) start_pc: u16,
+    pub(crate) length: u16,
+    pub(crate) name_index: u16,
+    pub(crate) descriptor_index: u16,
+    pub(crate) index: u16,
+}
+
+#[derive(Debug)]
+pub struct Annotation {
+    pub(crate) type_index: u16,
+    pub(crate) element_value_pairs: Vec<ElementValuePair>,
+}
+
+#[derive(Debug)]
+pub struct ElementValuePair {
+    pub(crate) element_name_index: u16,
+    pub(crate) value: ElementValue,
+}
+
+#[derive(Debug)]
+pub enum ElementValue {
+    Const {
+        tag: u8,
+        const_value_index: u16,
+    },
+    Enum {
+        type_name_index: u16,
+        const_name_index: u16,
+    },
+    Class {
+        class_info_index: u16,
+    },
+    Annotation(Annotation),
+    Array(Vec<ElementValue>),
+}
+
+#[derive(Debug)]
+pub struct TypeAnnotation {
+    pub(crate) target_type: u8,
+    pub(crate) target_info: TargetInfo,
+    pub(crate) target_path: Vec<TypePathEntry>,
+    pub(crate) annotation: Annotation,
+}
+
+#[derive(Debug)]
+pub enum TargetInfo {
+    TypeParameter {
+        type_parameter_index: u8,
+    },
+    Supertype {
+        supertype_index: u16,
+    },
+    TypeParameterBound {
+        type_parameter_index: u8,
+        bound_index: u8,
+    },
+    Empty,
+    FormalParameter {
+        formal_parameter_index: u8,
+    },
+    Throws {
+        throws_type_index: u16,
+    },
+    LocalVar(Vec<LocalVarTarget>),
+    Catch {
+        exception_table_index: u16,
+    },
+    Offset {
+        offset: u16,
+    },
+    TypeArgument {
+        offset: u16,
+        type_argument_index: u8,
+    },
+}
+
+#[derive(Debug)]
+pub struct LocalVarTarget {
+    pub(crate) start_pc: u16,
+    pub(crate) length: u16,
+    pub(crate) index: u16,
+}
+
+#[derive(Debug)]
+pub struct TypePathEntry {
+    pub(crate) type_path_kind: u8,
+    pub(crate) type_argument_index: u8,
+}
+
+#[derive(Clone, Debug)]
+pub struct BootstrapMethod {
+    pub(crate) bootstrap_method_ref: u16,
+    pub(crate) bootstrap_arguments: Vec<u16>,
+}
+
+#[derive(Debug)]
+pub struct MethodParameter {
+    pub(crate) name_index: u16,
+    pub(crate) access_flags: u16,
+}
+
+#[derive(Debug)]
+pub struct RecordComponent {
+    pub(crate) name_index: u16,
+    pub(crate) descriptor_index: u16,
+    pub(crate) attributes: Vec<AttributeInfo>,
+}
+
+impl AttributeInfo {
+    pub(crate) fn read<T>(
+        reader: &mut T,
+        constant_pool: &[ConstantInfo],
+    ) -> Result<AttributeInfo, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        let offset = reader.offset();
+        let attribute_name_index = reader.read_u2()?;
+        let attribute_length = reader.read_u4()?;
+        let info_offset = reader.offset();
+        let info = reader.read_vec(attribute_length as usize)?;
+
+        let name = match constant_pool.get((attribute_name_index as usize).wrapping_sub(1)) {
+            Some(ConstantInfo::Utf8(utf8)) => utf8.value.as_str(),
+            _ => {
+                let kind = ClassFormatErrorKind::ConstantIndex {
+                    actual: attribute_name_index,
+                };
+                return Err(ClassFormatError::new(offset, kind));
+            }
+        };
+
+        let mut attribute = Attribute::decode(name, info, constant_pool).map_err(|mut e| {
+            e.offset += info_offset;
+            e
+        })?;
+        if let Attribute::Code(code) = &mut attribute {
+            code.offset += info_offset;
+        }
+
+        Ok(AttributeInfo {
+            attribute_name_index,
+            attribute,
+        })
+    }
+}
+
+impl Attribute {
+    fn decode(
+        name: &str,
+        info: Vec<u8>,
+        constant_pool: &[ConstantInfo],
+    ) -> Result<Attribute, ClassFormatError> {
+        let mut reader = Cursor::new(&info[..]);
+        let reader = &mut reader;
+
+        let attribute = match name {
+            "ConstantValue" => {
+                let index = reader.read_u2()?;
+                let tags = [
+                    CONSTANT_INTEGER,
+                    CONSTANT_FLOAT,
+                    CONSTANT_LONG,
+                    CONSTANT_DOUBLE,
+                    CONSTANT_STRING,
+                ];
+                check_constant(constant_pool, 0, index, &tags)?;
+                Attribute::ConstantValue(index)
+            }
+            "Code" => Attribute::Code(CodeAttribute::read(reader, constant_pool)?),
+            "StackMapTable" => Attribute::StackMapTable(reader.parse_table()?),
+            "Exceptions" => Attribute::Exceptions(reader.parse_table()?),
+            "InnerClasses" => Attribute::InnerClasses(reader.parse_table()?),
+            "EnclosingMethod" => Attribute::EnclosingMethod(reader.parse()?),
+            "Synthetic" => Attribute::Synthetic,
+            "Signature" => Attribute::Signature(reader.read_u2()?),
+            "SourceFile" => Attribute::SourceFile(reader.read_u2()?),
+            "SourceDebugExtension" => {
+                let bytes = reader.read_vec(info.len())?;
+                Attribute::SourceDebugExtension(bytes.into_boxed_slice())
+            }
+            "LineNumberTable" => Attribute::LineNumberTable(reader.parse_table()?),
+            "LocalVariableTable" => Attribute::LocalVariableTable(reader.parse_table()?),
+            "LocalVariableTypeTable" => Attribute::LocalVariableTypeTable(reader.parse_table()?),
+            "Deprecated" => Attribute::Deprecated,
+            "RuntimeVisibleAnnotations" => {
+                Attribute::RuntimeVisibleAnnotations(reader.parse_table()?)
+            }
+            "RuntimeInvisibleAnnotations" => {
+                Attribute::RuntimeInvisibleAnnotations(reader.parse_table()?)
+            }
+            "RuntimeVisibleParameterAnnotations" => {
+                Attribute::RuntimeVisibleParameterAnnotations(parameter_annotations(reader)?)
+            }
+            "RuntimeInvisibleParameterAnnotations" => {
+                Attribute::RuntimeInvisibleParameterAnnotations(parameter_annotations(reader)?)
+            }
+            "RuntimeVisibleTypeAnnotations" => {
+                Attribute::RuntimeVisibleTypeAnnotations(reader.parse_table()?)
+            }
+            "RuntimeInvisibleTypeAnnotations" => {
+                Attribute::RuntimeInvisibleTypeAnnotations(reader.parse_table()?)
+            }
+            "AnnotationDefault" => Attribute::AnnotationDefault(reader.parse()?),
+            "BootstrapMethods" => {
+                let methods: Vec<BootstrapMethod> = reader.parse_table()?;
+                for method in &methods {
+                    let index = method.bootstrap_method_ref;
+                    check_constant(constant_pool, 0, index, &[CONSTANT_METHOD_HANDLE])?;
+                    for &index in &method.bootstrap_arguments {
+                        check_constant(constant_pool, 0, index, LOADABLE_CONSTANTS)?;
+                    }
+                }
+                Attribute::BootstrapMethods(methods)
+            }
+            "MethodParameters" => {
+                let parameters_count = reader.read_u1()?;
+                let parameters = (0..parameters_count)
+                    .map(|_| reader.parse())
+                    .collect::<Result<_, _>>()?;
+                Attribute::MethodParameters(parameters)
+            }
+            "NestHost" => Attribute::NestHost(reader.read_u2()?),
+            "NestMembers" => Attribute::NestMembers(reader.parse_table()?),
+            "Record" => {
+                let components_count = reader.read_u2()?;
+                let components = (0..components_count)
+                    .map(|_| RecordComponent::read(reader, constant_pool))
+                    .collect::<Result<_, _>>()?;
+                Attribute::Record(components)
+            }
+            "PermittedSubclasses" => Attribute::PermittedSubclasses(reader.parse_table()?),
+            _ => return Ok(Attribute::Unknown(info.into_boxed_slice())),
+        };
+
+        if reader.remaining() > 0 {
+            let kind = ClassFormatErrorKind::AttributeLength {
+                expected: info.len(),
+                actual: reader.offset(),
+            };
+            return Err(ClassFormatError::new(0, kind));
+        }
+
+        Ok(attribute)
+    }
+}
+
+pub(crate) fn read_attributes<T>(
+    reader: &mut T,
+    constant_pool: &[ConstantInfo],
+) -> Result<Vec<AttributeInfo>, ClassFormatError>
+where
+    T: ClassReader,
+{
+    let attributes_count = reader.read_u2()?;
+    (0..attributes_count)
+        .map(|_| AttributeInfo::read(reader, constant_pool))
+        .collect()
+}
+
+fn parameter_annotations<T>(reader: &mut T) -> Result<Vec<Vec<Annotation>>, ClassFormatError>
+where
+    T: ClassReader,
+{
+    let num_parameters = reader.read_u1()?;
+    (0..num_parameters).map(|_| reader.parse_table()).collect()
+}
+
+fn unknown_tag(offset: usize, attribute: &'static str, actual: u8) -> ClassFormatError {
+    ClassFormatError::new(
+        offset,
+        ClassFormatErrorKind::AttributeTag { attribute, actual },
+    )
+}
+
+impl CodeAttribute {
+    fn read<T>(reader: &mut T, constant_pool: &[ConstantInfo]) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        let max_stack = reader.read_u2()? as usize;
+        let max_locals = reader.read_u2()? as usize;
+        let code_length = reader.read_u4()?;
+        let code_offset = reader.offset();
+        let code = reader.read_vec(code_length as usize)?;
+        let offset = reader.offset();
+        let exception_table: Vec<ExceptionTableEntry> = reader.parse_table()?;
+        for entry in &exception_table {
+            if entry.catch_type != 0 {
+                check_constant(constant_pool, offset, entry.catch_type, &[CONSTANT_CLASS])?;
+            }
+        }
+        let attributes = read_attributes(reader, constant_pool)?;
+
+        Ok(CodeAttribute {
+            offset: code_offset,
+            max_stack,
+            max_locals,
+            code,
+            exception_table,
+            attributes,
+        })
+    }
+
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub(crate) fn line_number_table(&self) -> Option<&[LineNumber]> {
+        self.attributes
+            .iter()
+            .find_map(|info| match &info.attribute {
+                Attribute::LineNumberTable(table) => Some(table.as_slice()),
+                _ => None,
+            })
+    }
+
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub(crate) fn local_variable_table(&self) -> Option<&[LocalVariable]> {
+        self.attributes
+            .iter()
+            .find_map(|info| match &info.attribute {
+                Attribute::LocalVariableTable(table) => Some(table.as_slice()),
+                _ => None,
+            })
+    }
+
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub(crate) fn local_variable_type_table(&self) -> Option<&[LocalVariable]> {
+        self.attributes
+            .iter()
+            .find_map(|info| match &info.attribute {
+                Attribute::LocalVariableTypeTable(table) => Some(table.as_slice()),
+                _ => None,
+            })
+    }
+
+    pub(crate) fn stack_map_table(&self) -> Option<&[StackMapFrame]> {
+        self.attributes
+            .iter()
+            .find_map(|info| match &info.attribute {
+                Attribute::StackMapTable(table) => Some(table.as_slice()),
+                _ => None,
+            })
+    }
+}
+
+impl RecordComponent {
+    fn read<T>(reader: &mut T, constant_pool: &[ConstantInfo]) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(RecordComponent {
+            name_index: reader.read_u2()?,
+            descriptor_index: reader.read_u2()?,
+            attributes: read_attributes(reader, constant_pool)?,
+        })
+    }
+}
+
+impl FromReader for u16 {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        reader.read_u2()
+    }
+}
+
+impl FromReader for ExceptionTableEntry {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(ExceptionTableEntry {
+            start_pc: reader.read_u2()? as usize,
+            end_pc: reader.read_u2()? as usize,
+            handler_pc: reader.read_u2()? as usize,
+            catch_type: reader.read_u2()?,
+        })
+    }
+}
+
+impl FromReader for StackMapFrame {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        let offset = reader.offset();
+        let frame = match reader.read_u1()? {
+            frame_type @ 0..=63 => StackMapFrame::Same { frame_type },
+            frame_type @ 64..=127 => StackMapFrame::SameLocals1StackItem {
+                frame_type,
+                stack: reader.parse()?,
+            },
+            247 => StackMapFrame::SameLocals1StackItemExtended {
+                offset_delta: reader.read_u2()?,
+                stack: reader.parse()?,
+            },
+            frame_type @ 248..=250 => StackMapFrame::Chop {
+                frame_type,
+                offset_delta: reader.read_u2()?,
+            },
+            251 => StackMapFrame::SameExtended {
+                offset_delta: reader.read_u2()?,
+            },
+            frame_type @ 252..=254 => StackMapFrame::Append {
+                frame_type,
+                offset_delta: reader.read_u2()?,
+                locals: (0..frame_type - 251)
+                    .map(|_| reader.parse())
+                    .collect::<Result<_, _>>()?,
+            },
+            255 => StackMapFrame::Full {
+                offset_delta: reader.read_u2()?,
+                locals: reader.parse_table()?,
+                stack: reader.parse_table()?,
+            },
+            frame_type => return Err(unknown_tag(offset, "stack map frame", frame_type)),
+        };
+        Ok(frame)
+    }
+}
+
+impl FromReader for VerificationTypeInfo {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        let offset = reader.offset();
+        let info = match reader.read_u1()? {
+            0 => VerificationTypeInfo::Top,
+            1 => VerificationTypeInfo::Integer,
+            2 => VerificationTypeInfo::Float,
+            3 => VerificationTypeInfo::Double,
+            4 => VerificationTypeInfo::Long,
+            5 => VerificationTypeInfo::Null,
+            6 => VerificationTypeInfo::UninitializedThis,
+            7 => VerificationTypeInfo::Object {
+                cpool_index: reader.read_u2()?,
+            },
+            8 => VerificationTypeInfo::Uninitialized {
+                offset: reader.read_u2()?,
+            },
+            tag => return Err(unknown_tag(offset, "verification type", tag)),
+        };
+        Ok(info)
+    }
+}
+
+impl FromReader for InnerClass {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(InnerClass {
+            inner_class_info_index: reader.read_u2()?,
+            outer_class_info_index: reader.read_u2()?,
+            inner_name_index: reader.read_u2()?,
+            inner_class_access_flags: reader.read_u2()?,
+        })
+    }
+}
+
+impl FromReader for EnclosingMethod {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(EnclosingMethod {
+            class_index: reader.read_u2()?,
+            method_index: reader.read_u2()?,
+        })
+    }
+}
+
+impl FromReader for LineNumber {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(LineNumber {
+            start_pc: reader.read_u2()?,
+            line_number: reader.read_u2()?,
+        })
+    }
+}
+
+impl FromReader for LocalVariable {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(LocalVariable {
+            start_pc: reader.read_u2()?,
+            length: reader.read_u2()?,
+            name_index: reader.read_u2()?,
+            descriptor_index: reader.read_u2()?,
+            index: reader.read_u2()?,
+        })
+    }
+}
+
+impl FromReader for Annotation {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(Annotation {
+            type_index: reader.read_u2()?,
+            element_value_pairs: reader.parse_table()?,
+        })
+    }
+}
+
+impl FromReader for ElementValuePair {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(ElementValuePair {
+            element_name_index: reader.read_u2()?,
+            value: reader.parse()?,
+        })
+    }
+}
+
+impl FromReader for ElementValue {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        let offset = reader.offset();
+        let value = match reader.read_u1()? {
+            tag @ (b'B' | b'C' | b'D' | b'F' | b'I' | b'J' | b'S' | b'Z' | b's') => {
+                ElementValue::Const {
+                    tag,
+                    const_value_index: reader.read_u2()?,
+                }
+            }
+            b'e' => ElementValue::Enum {
+                type_name_index: reader.read_u2()?,
+                const_name_index: reader.read_u2()?,
+            },
+            b'c' => ElementValue::Class {
+                class_info_index: reader.read_u2()?,
+            },
+            b'@' => ElementValue::Annotation(reader.parse()?),
+            b'[' => ElementValue::Array(reader.parse_table()?),
+            tag => return Err(unknown_tag(offset, "element value", tag)),
+        };
+        Ok(value)
+    }
+}
+
+impl FromReader for TypeAnnotation {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        let offset = reader.offset();
+        let target_type = reader.read_u1()?;
+        let target_info = match target_type {
+            0x00 | 0x01 => TargetInfo::TypeParameter {
+                type_parameter_index: reader.read_u1()?,
+            },
+            0x10 => TargetInfo::Supertype {
+                supertype_index: reader.read_u2()?,
+            },
+            0x11 | 0x12 => TargetInfo::TypeParameterBound {
+                type_parameter_index: reader.read_u1()?,
+                bound_index: reader.read_u1()?,
+            },
+            0x13..=0x15 => TargetInfo::Empty,
+            0x16 => TargetInfo::FormalParameter {
+                formal_parameter_index: reader.read_u1()?,
+            },
+            0x17 => TargetInfo::Throws {
+                throws_type_index: reader.read_u2()?,
+            },
+            0x40 | 0x41 => TargetInfo::LocalVar(reader.parse_table()?),
+            0x42 => TargetInfo::Catch {
+                exception_table_index: reader.read_u2()?,
+            },
+            0x43..=0x46 => TargetInfo::Offset {
+                offset: reader.read_u2()?,
+            },
+            0x47..=0x4b => TargetInfo::TypeArgument {
+                offset: reader.read_u2()?,
+                type_argument_index: reader.read_u1()?,
+            },
+            tag => return Err(unknown_tag(offset, "type annotation target", tag)),
+        };
+
+        let path_length = reader.read_u1()?;
+        let target_path = (0..path_length)
+            .map(|_| reader.parse())
+            .collect::<Result<_, _>>()?;
+
+        Ok(TypeAnnotation {
+            target_type,
+            target_info,
+            target_path,
+            annotation: reader.parse()?,
+        })
+    }
+}
+
+impl FromReader for LocalVarTarget {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(LocalVarTarget {
+            start_pc: reader.read_u2()?,
+            length: reader.read_u2()?,
+            index: reader.read_u2()?,
+        })
+    }
+}
+
+impl FromReader for TypePathEntry {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(TypePathEntry {
+            type_path_kind: reader.read_u1()?,
+            type_argument_index: reader.read_u1()?,
+        })
+    }
+}
+
+impl FromReader for BootstrapMethod {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(BootstrapMethod {
+            bootstrap_method_ref: reader.read_u2()?,
+            bootstrap_arguments: reader.parse_table()?,
+        })
+    }
+}
+
+impl FromReader for MethodParameter {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(MethodParameter {
+            name_index: reader.read_u2()?,
+            access_flags: reader.read_u2()?,
+        })
+    }
+}
@@ -0,0 +1,263 @@
+use crate::class::Class;
+use crate::class_file::ReferenceKind;
+use crate::descriptor::{FieldDescriptor, MethodDescriptor};
+use crate::heap::Reference;
+use crate::lambda::{self, Implementation, Lambda};
+use crate::throwable::{Throwable, BOOTSTRAP_METHOD_ERROR};
+use crate::vm::VirtualMachine;
+use std::rc::Rc;
+
+const LAMBDA_METAFACTORY: &str = "java/lang/invoke/LambdaMetafactory";
+const STRING_CONCAT_FACTORY: &str = "java/lang/invoke/StringConcatFactory";
+const SERIALIZABLE: &str = "java/io/Serializable";
+
+const FLAG_SERIALIZABLE: i32 = 1 << 0;
+const FLAG_MARKERS: i32 = 1 << 1;
+const FLAG_BRIDGES: i32 = 1 << 2;
+
+const TAG_ARGUMENT: char = '\u{1}';
+const TAG_CONSTANT: char = '\u{2}';
+
+/// What an `invokedynamic` call site is linked to. Only the bootstrap
+/// methods javac emits for lambdas and string concatenation are supported,
+/// and rather than invoking them they are recognized and linked natively.
+#[derive(Debug)]
+pub enum CallSite {
+    /// Creates an instance of the lambda class, storing the arguments into
+    /// the fields with the slots.
+    Lambda { class: Rc<Class>, slots: Vec<usize> },
+    /// Concatenates the constants and the arguments into a string.
+    StringConcat(Vec<Recipe>),
+}
+
+#[derive(Debug)]
+pub enum Recipe {
+    Constant(String),
+    Argument(FieldDescriptor),
+}
+
+/// Links the `invokedynamic` call site with the index of its
+/// `CONSTANT_InvokeDynamic` entry and the descriptor (JVMS 5.4.3.6).
+pub(crate) fn link(
+    vm: &mut VirtualMachine,
+    caller: &Class,
+    index: u16,
+    descriptor: &MethodDescriptor,
+) -> Result<CallSite, Throwable> {
+    let constant_pool = &caller.constant_pool;
+    let (bootstrap_method_index, name_and_type) = constant_pool.invoke_dynamic(index);
+    let bootstrap_method = &caller.bootstrap_methods[bootstrap_method_index as usize];
+    let (_, class_name, bootstrap_name_and_type) =
+        constant_pool.method_handle(bootstrap_method.bootstrap_method_ref);
+    let (bootstrap_name, _) = bootstrap_name_and_type.split_once(':').unwrap();
+    let arguments = &bootstrap_method.bootstrap_arguments;
+
+    match (class_name.as_str(), bootstrap_name) {
+        (LAMBDA_METAFACTORY, "metafactory") => {
+            let (name, _) = name_and_type.split_once(':').unwrap();
+            link_lambda(vm, caller, name, descriptor, arguments, false)
+        }
+        (LAMBDA_METAFACTORY, "altMetafactory") => {
+            let (name, _) = name_and_type.split_once(':').unwrap();
+            link_lambda(vm, caller, name, descriptor, arguments, true)
+        }
+        (STRING_CONCAT_FACTORY, "makeConcatWithConstants") => {
+            let recipe = match arguments.first() {
+                Some(&index) if constant_pool.is_string(index as usize) => {
+                    constant_pool.string(index)
+                }
+                _ => return Err(bootstrap_method_error("Missing recipe")),
+            };
+            let mut constants = arguments[1..].iter();
+            let mut parameters = descriptor.parameters.iter();
+
+            let mut recipes = vec![];
+            let mut literal = String::new();
+            for c in recipe.chars() {
+                let next = match c {
+                    TAG_ARGUMENT => parameters.next().cloned().map(Recipe::Argument),
+                    TAG_CONSTANT => match constants.next() {
+                        Some(&index) if constant_pool.is_string(index as usize) => {
+                            Some(Recipe::Constant(constant_pool.string(index)))
+                        }
+                        _ => return Err(bootstrap_method_error("Missing string constant")),
+                    },
+                    c => {
+                        literal.push(c);
+                        continue;
+                    }
+                };
+                if !literal.is_empty() {
+                    recipes.push(Recipe::Constant(std::mem::take(&mut literal)));
+                }
+                match next {
+                    Some(next) => recipes.push(next),
+                    None => return Err(bootstrap_method_error("Mismatched number of arguments")),
+                }
+            }
+            if !literal.is_empty() {
+                recipes.push(Recipe::Constant(literal));
+            }
+            Ok(CallSite::StringConcat(recipes))
+        }
+        (STRING_CONCAT_FACTORY, "makeConcat") => {
+            let recipes = descriptor
+                .parameters
+                .iter()
+                .cloned()
+                .map(Recipe::Argument)
+                .collect();
+            Ok(CallSite::StringConcat(recipes))
+        }
+        _ => {
+            let message = format!(
+                "Unsupported bootstrap method {}.{}",
+                class_name.replace('/', "."),
+                bootstrap_name
+            );
+            Err(bootstrap_method_error(&message))
+        }
+    }
+}
+
+/// Resolves the `CONSTANT_Dynamic` entry with the index (JVMS 5.4.3.6). None
+/// of the bootstrap methods for dynamically computed constants is supported.
+pub(crate) fn resolve_dynamic(caller: &Class, index: u16) -> Result<Reference, Throwable> {
+    let constant_pool = &caller.constant_pool;
+    let (bootstrap_method_index, _) = constant_pool.dynamic(index);
+    let bootstrap_method = &caller.bootstrap_methods[bootstrap_method_index as usize];
+    let (_, class_name, bootstrap_name_and_type) =
+        constant_pool.method_handle(bootstrap_method.bootstrap_method_ref);
+    let (bootstrap_name, _) = bootstrap_name_and_type.split_once(':').unwrap();
+    let message = format!(
+        "Unsupported bootstrap method {}.{}",
+        class_name.replace('/', "."),
+        bootstrap_name
+    );
+    Err(bootstrap_method_error(&message))
+}
+
+/// Links a call site of `LambdaMetafactory.metafactory` or `altMetafactory`
+/// by spinning the lambda class.
+fn link_lambda(
+    vm: &mut VirtualMachine,
+    caller: &Class,
+    method_name: &str,
+    descriptor: &MethodDescriptor,
+    arguments: &[u16],
+    alternate: bool,
+) -> Result<CallSite, Throwable> {
+    let constant_pool = &caller.constant_pool;
+    let argument = |position: usize| {
+        arguments
+            .get(position)
+            .copied()
+            .ok_or_else(|| bootstrap_method_error("Missing bootstrap method argument"))
+    };
+    let method_descriptor = |index: u16| {
+        if !constant_pool.is_method_type(index as usize) {
+            return Err(bootstrap_method_error("Method type expected"));
+        }
+        let descriptor = constant_pool.method_type(index);
+        MethodDescriptor::parse(&descriptor)
+            .ok_or_else(|| bootstrap_method_error(&format!("Bad method type {}", descriptor)))
+    };
+
+    let interface = match &descriptor.return_type {
+        Some(FieldDescriptor::Object(interface)) => interface.clone(),
+        _ => return Err(bootstrap_method_error("Lambda must return an interface")),
+    };
+
+    let handle = argument(1)?;
+    if !constant_pool.is_method_handle(handle as usize) {
+        return Err(bootstrap_method_error("Method handle expected"));
+    }
+    let (kind, class_name, name_and_type) = constant_pool.method_handle(handle);
+    if let ReferenceKind::GetField
+    | ReferenceKind::GetStatic
+    | ReferenceKind::PutField
+    | ReferenceKind::PutStatic = kind
+    {
+        let message = format!("Unsupported implementation method kind {:?}", kind);
+        return Err(bootstrap_method_error(&message));
+    }
+    let (name, implementation_descriptor) = name_and_type.split_once(':').unwrap();
+    let implementation_descriptor = MethodDescriptor::parse(implementation_descriptor)
+        .ok_or_else(|| bootstrap_method_error("Bad implementation method descriptor"))?;
+    let implementation = Implementation {
+        kind,
+        interface: vm.resolve_class(&class_name)?.is_interface(),
+        class_name,
+        name: name.to_string(),
+        descriptor: implementation_descriptor,
+    };
+
+    let method_type = method_descriptor(argument(0)?)?;
+    let mut interfaces = vec![interface];
+    let mut method_descriptors = vec![method_type];
+    if alternate {
+        // flags, then the counts of the markers and bridges that follow them
+        // when the flags say so
+        let int = |position: usize| {
+            let index = argument(position)? as usize;
+            if !constant_pool.is_integer(index) {
+                return Err(bootstrap_method_error("Integer expected"));
+            }
+            Ok(constant_pool.get(index).int())
+        };
+        let flags = int(3)?;
+        let mut next = 4;
+        if flags & FLAG_SERIALIZABLE != 0 {
+            interfaces.push(SERIALIZABLE.to_string());
+        }
+        if flags & FLAG_MARKERS != 0 {
+            let count = int(next)? as usize;
+            for position in next + 1..next + 1 + count {
+                let index = argument(position)?;
+                if !constant_pool.is_class(index as usize) {
+                    return Err(bootstrap_method_error("Marker interface expected"));
+                }
+                interfaces.push(constant_pool.class(index));
+            }
+            next += 1 + count;
+        }
+        if flags & FLAG_BRIDGES != 0 {
+            let count = int(next)? as usize;
+            for position in next + 1..next + 1 + count {
+                method_descriptors.push(method_descriptor(argument(position)?)?);
+            }
+        }
+    }
+
+    let captured = descriptor.parameters.len();
+    if captured + method_descriptors[0].parameters.len() != implementation.parameters().len() {
+        let message = "Type mismatch for lambda expected arguments";
+        return Err(bootstrap_method_error(message));
+    }
+
+    vm.lambda_classes += 1;
+    let lambda = Lambda {
+        class_name: format!("{}$$Lambda${}", caller.this_class(), vm.lambda_classes),
+        interfaces,
+        captured: descriptor.parameters.clone(),
+        method_name: method_name.to_string(),
+        method_descriptors,
+        implementation,
+    };
+    let mut class = Class::try_from(lambda::spin(&lambda))
+        .map_err(|error| bootstrap_method_error(&error.to_string()))?;
+    class.bootstrap = caller.bootstrap;
+    let source = caller.this_class().replace('/', ".");
+    let class = vm.define_class(class, Some(source))?;
+
+    let slots = class
+        .fields
+        .iter()
+        .map(|field| class.instance_fields[&field.name_and_type()])
+        .collect();
+    Ok(CallSite::Lambda { class, slots })
+}
+
+fn bootstrap_method_error(message: &str) -> Throwable {
+    Throwable::new(BOOTSTRAP_METHOD_ERROR, message)
+}
@@ -1,54 +1,271 @@
-use crate::class_file::ClassFile;
+use crate::attribute::BootstrapMethod;
+use crate::class_file::{ClassFile, ClassFormatError, Section, WithinSection};
 use crate::constant_pool::ConstantPool;
+use crate::dispatch::{self, ItableEntry, VirtualMethod};
+use crate::field::Field;
+use crate::heap::Reference;
 use crate::method::Method;
-use crate::thread::Thread;
+use crate::r#type::Type;
+use crate::throwable::Throwable;
+use crate::vm::VirtualMachine;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+
+pub(crate) const OBJECT: &str = "java/lang/Object";
+pub(crate) const CLASS: &str = "java/lang/Class";
+pub(crate) const CLONEABLE: &str = "java/lang/Cloneable";
+pub(crate) const SERIALIZABLE: &str = "java/io/Serializable";
+pub(crate) const ENUM: &str = "java/lang/Enum";
+
+const ACC_INTERFACE: u16 = 0x0200;
+const ACC_ABSTRACT: u16 = 0x0400;
+const ACC_ENUM: u16 = 0x4000;
+
+/// Initialization state of a class, as described in JVMS 5.5.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InitializationState {
+    Linked,
+    BeingInitialized,
+    Initialized,
+    Erroneous,
+}
 
 #[derive(Debug)]
 pub struct Class {
     access_flags: u16,
     pub(crate) constant_pool: ConstantPool,
-    pub(crate) methods: HashMap<String, Method>,
+    pub(crate) fields: Vec<Field>,
+    pub(crate) methods: HashMap<String, Rc<Method>>,
     this_class: u16,
     super_class: u16,
+    interfaces: Box<[u16]>,
+    /// The BootstrapMethods attribute, which `invokedynamic` refers to.
+    pub(crate) bootstrap_methods: Vec<BootstrapMethod>,
+    pub(crate) initialization_state: Cell<InitializationState>,
+    /// Whether the bootstrap class loader defined the class rather than the
+    /// application one.
+    pub(crate) bootstrap: bool,
+    /// Slots of the instance fields, including inherited ones, by name and type.
+    pub(crate) instance_fields: HashMap<String, usize>,
+    /// Default values of an instance, one per instance field slot.
+    pub(crate) instance_layout: Vec<Type>,
+    /// Slots of the static fields declared by the class, by name and type.
+    pub(crate) static_fields: HashMap<String, usize>,
+    pub(crate) static_values: RefCell<Vec<Type>>,
+    /// Objects the `CONSTANT_String`, `CONSTANT_Class`, `CONSTANT_MethodType`
+    /// and `CONSTANT_MethodHandle` entries resolved to so far, by index.
+    pub(crate) resolved_constants: RefCell<HashMap<u16, Reference>>,
+    /// Superinterfaces, direct and indirect, in declaration order.
+    pub(crate) superinterfaces: Vec<Rc<Class>>,
+    /// Methods invoked through `invokevirtual`, by the index of the method
+    /// they override or implement.
+    pub(crate) vtable: Vec<VirtualMethod>,
+    pub(crate) vtable_indices: HashMap<String, usize>,
+    /// Methods invoked through `invokeinterface`, by name and type.
+    pub(crate) itable: HashMap<String, ItableEntry>,
 }
 
 impl Class {
-    pub fn method(&self, name_and_type: &str) -> Option<&Method> {
+    pub fn method(&self, name_and_type: &str) -> Option<&Rc<Method>> {
         self.methods.get(name_and_type)
     }
 
-    pub fn super_class(&self) -> String {
-        self.constant_pool.class(self.super_class)
+    pub fn super_class(&self) -> Option<String> {
+        (self.super_class != 0).then(|| self.constant_pool.class(self.super_class))
     }
 
     pub fn this_class(&self) -> String {
         self.constant_pool.class(self.this_class)
     }
+
+    pub fn interfaces(&self) -> Vec<String> {
+        self.interfaces
+            .iter()
+            .map(|&index| self.constant_pool.class(index))
+            .collect()
+    }
+
+    pub fn field(&self, name_and_type: &str) -> Option<&Field> {
+        self.fields
+            .iter()
+            .find(|field| field.name_and_type() == name_and_type)
+    }
+
+    /// Lays out the instance fields after those of the superclass, prepares
+    /// the static fields with their default values (JVMS 5.4.2) and builds
+    /// the method tables.
+    pub(crate) fn link(
+        &mut self,
+        this: &Weak<Class>,
+        super_class: Option<&Rc<Class>>,
+        interfaces: &[Rc<Class>],
+    ) {
+        if let Some(super_class) = super_class {
+            self.instance_fields = super_class.instance_fields.clone();
+            self.instance_layout = super_class.instance_layout.clone();
+            self.superinterfaces = super_class.superinterfaces.clone();
+        }
+        for interface in interfaces {
+            let inherited = interface.superinterfaces.iter();
+            for interface in std::iter::once(interface).chain(inherited) {
+                if !self
+                    .superinterfaces
+                    .iter()
+                    .any(|known| Rc::ptr_eq(known, interface))
+                {
+                    self.superinterfaces.push(interface.clone());
+                }
+            }
+        }
+
+        let static_values = self.static_values.get_mut();
+        for field in &self.fields {
+            let (slots, values) = if field.is_static() {
+                (&mut self.static_fields, &mut *static_values)
+            } else {
+                (&mut self.instance_fields, &mut self.instance_layout)
+            };
+            slots.insert(field.name_and_type(), values.len());
+            values.push(Type::zero(&field.descriptor));
+        }
+
+        if !self.is_interface() {
+            let (vtable, vtable_indices) =
+                dispatch::vtable(this, self, super_class.map(Rc::as_ref));
+            self.vtable = vtable;
+            self.vtable_indices = vtable_indices;
+            self.itable = dispatch::itable(self);
+        }
+    }
+
+    /// Assigns the `static final` fields their ConstantValue attributes
+    /// (JVMS 5.5, step 6).
+    pub(crate) fn initialize_constant_fields(
+        &self,
+        vm: &mut VirtualMachine,
+    ) -> Result<(), Throwable> {
+        for field in &self.fields {
+            let index = match field.constant_value {
+                Some(index) if field.is_static() && field.is_final() => index,
+                _ => continue,
+            };
+
+            let value = if self.constant_pool.is_string(index as usize) {
+                Type::Reference(vm.resolve_constant(self, index)?)
+            } else {
+                self.constant_pool.get(index as usize)
+            };
+            let slot = self.static_fields[&field.name_and_type()];
+            self.static_values.borrow_mut()[slot] = value;
+        }
+        Ok(())
+    }
+
+    /// The descriptor of the components of an array class, e.g. `I` for `[I`.
+    pub(crate) fn component_type(&self) -> Option<String> {
+        let name = self.this_class();
+        name.strip_prefix('[').map(str::to_string)
+    }
+
+    pub(crate) fn is_array(&self) -> bool {
+        self.this_class().starts_with('[')
+    }
+
+    pub(crate) fn is_abstract(&self) -> bool {
+        self.access_flags & ACC_ABSTRACT != 0
+    }
+
+    pub(crate) fn is_interface(&self) -> bool {
+        self.access_flags & ACC_INTERFACE != 0
+    }
+
+    /// Whether the class is an enum class rather than the class of a
+    /// constant with a body.
+    pub(crate) fn is_enum(&self) -> bool {
+        self.access_flags & ACC_ENUM != 0 && self.super_class().as_deref() == Some(ENUM)
+    }
+
+    /// Whether the interface has to be initialized along with the classes
+    /// implementing it (JVMS 5.5, step 7).
+    pub(crate) fn declares_default_methods(&self) -> bool {
+        self.methods
+            .values()
+            .any(|method| !method.is_abstract() && !method.is_static())
+    }
 }
 
-impl From<ClassFile> for Class {
-    fn from(class_file: ClassFile) -> Self {
+impl TryFrom<ClassFile> for Class {
+    type Error = ClassFormatError;
+
+    fn try_from(class_file: ClassFile) -> Result<Self, Self::Error> {
+        let bootstrap_methods = class_file
+            .bootstrap_methods()
+            .map(<[_]>::to_vec)
+            .unwrap_or_default();
         let ClassFile {
             access_flags,
             constant_pool,
             this_class,
             super_class,
+            interfaces,
+            fields,
             methods,
             ..
         } = class_file;
 
         let constant_pool = ConstantPool::from(constant_pool);
-        Class {
+        let fields = fields
+            .into_iter()
+            .map(|info| Field::from(info, &constant_pool))
+            .collect();
+        let methods = methods
+            .into_iter()
+            .zip(0..)
+            .map(|(info, index)| Method::from(info, &constant_pool).within(Section::Method(index)))
+            .map(|method| method.map(|method| (method.name_and_type(), Rc::new(method))))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Class {
             access_flags,
-            methods: methods
-                .into_iter()
-                .map(|info| Method::from(info, &constant_pool))
-                .map(|method| (format!("{}:{}", method.name, method.descriptor), method))
-                .collect(),
+            fields,
+            methods,
             constant_pool,
             this_class,
             super_class,
-        }
+            interfaces,
+            bootstrap_methods,
+            initialization_state: Cell::new(InitializationState::Linked),
+            bootstrap: false,
+            instance_fields: HashMap::new(),
+            instance_layout: vec![],
+            static_fields: HashMap::new(),
+            static_values: RefCell::new(vec![]),
+            resolved_constants: RefCell::new(HashMap::new()),
+            superinterfaces: vec![],
+            vtable: vec![],
+            vtable_indices: HashMap::new(),
+            itable: HashMap::new(),
+        })
+    }
+}
+
+/// The name of the class or array class a field descriptor refers to, or
+/// `None` for a primitive type.
+pub(crate) fn descriptor_class_name(descriptor: &str) -> Option<&str> {
+    match descriptor.as_bytes().first()? {
+        b'L' => descriptor.strip_prefix('L')?.strip_suffix(';'),
+        b'[' => Some(descriptor),
+        _ => None,
+    }
+}
+
+/// The name of the class of arrays with components of the class, e.g.
+/// `[Ljava/lang/String;` for `java/lang/String` and `[[I` for `[I`.
+pub(crate) fn array_class_name(class_name: &str) -> String {
+    if class_name.starts_with('[') {
+        format!("[{}", class_name)
+    } else {
+        format!("[L{};", class_name)
     }
 }
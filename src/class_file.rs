@@ -1,13 +1,54 @@
-use std::fs;
-use std::fs::File;
-use std::io::{BufReader, Cursor, Read};
-use std::str::from_utf8;
+use crate::attribute::{
+    read_attributes, Attribute, AttributeInfo, BootstrapMethod, CodeAttribute, EnclosingMethod,
+    InnerClass, MethodParameter, RecordComponent,
+};
+use crate::mutf8;
+use crate::version::ClassVersion;
+use std::fmt;
+use std::io::{Cursor, Read};
+
+pub(crate) const MAGIC: u32 = 0xcafebabe;
+
+pub(crate) const CONSTANT_UTF8: u8 = 1;
+pub(crate) const CONSTANT_INTEGER: u8 = 3;
+pub(crate) const CONSTANT_FLOAT: u8 = 4;
+pub(crate) const CONSTANT_LONG: u8 = 5;
+pub(crate) const CONSTANT_DOUBLE: u8 = 6;
+pub(crate) const CONSTANT_CLASS: u8 = 7;
+pub(crate) const CONSTANT_STRING: u8 = 8;
+pub(crate) const CONSTANT_FIELD_REF: u8 = 9;
+pub(crate) const CONSTANT_METHOD_REF: u8 = 10;
+pub(crate) const CONSTANT_INTERFACE_METHOD_REF: u8 = 11;
+pub(crate) const CONSTANT_NAME_AND_TYPE: u8 = 12;
+pub(crate) const CONSTANT_METHOD_HANDLE: u8 = 15;
+pub(crate) const CONSTANT_METHOD_TYPE: u8 = 16;
+pub(crate) const CONSTANT_DYNAMIC: u8 = 17;
+pub(crate) const CONSTANT_INVOKE_DYNAMIC: u8 = 18;
+pub(crate) const CONSTANT_MODULE: u8 = 19;
+pub(crate) const CONSTANT_PACKAGE: u8 = 20;
+
+/// The constants `ldc` and the bootstrap method arguments may refer to.
+pub(crate) const LOADABLE_CONSTANTS: &[u8] = &[
+    CONSTANT_INTEGER,
+    CONSTANT_FLOAT,
+    CONSTANT_LONG,
+    CONSTANT_DOUBLE,
+    CONSTANT_CLASS,
+    CONSTANT_STRING,
+    CONSTANT_METHOD_HANDLE,
+    CONSTANT_METHOD_TYPE,
+    CONSTANT_DYNAMIC,
+];
+
+const ACC_STATIC: u16 = 0x0008;
 
 #[derive(Debug)]
 pub struct ClassFile {
-    magic: u32,
-    minor_version: u16,
-    major_version: u16,
+    // the class writer reads these back, and only the tests build it
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub(crate) magic: u32,
+    pub(crate) minor_version: u16,
+    pub(crate) major_version: u16,
     pub(crate) constant_pool: Vec<ConstantInfo>,
     pub(crate) access_flags: u16,
     pub(crate) this_class: u16,
@@ -18,52 +59,274 @@ pub struct ClassFile {
     pub(crate) attributes: Vec<AttributeInfo>,
 }
 
+#[derive(Debug)]
+pub struct ClassFormatError {
+    pub(crate) offset: usize,
+    pub(crate) section: Option<Section>,
+    pub(crate) kind: ClassFormatErrorKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Section {
+    Header,
+    ConstantPool(u16),
+    ClassInfo,
+    Interfaces,
+    Field(u16),
+    Method(u16),
+    Attributes,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ClassFormatErrorKind {
+    UnexpectedEof { expected: usize, actual: usize },
+    Magic { expected: u32, actual: u32 },
+    ConstantTag { actual: u8 },
+    ConstantTagVersion { actual: u8, version: ClassVersion },
+    ReferenceKind { actual: u8 },
+    ConstantIndex { actual: u16 },
+    ConstantType { index: u16, actual: u8 },
+    BootstrapMethodIndex { actual: u16 },
+    Utf8,
+    AttributeLength { expected: usize, actual: usize },
+    AttributeTag { attribute: &'static str, actual: u8 },
+    MissingAttribute { expected: &'static str },
+    Descriptor { actual: String },
+    Instruction { opcode: u8 },
+    TableSwitchRange { low: i32, high: i32 },
+    LookupSwitchCount { actual: i32 },
+    ExceptionTable { pc: usize },
+    TrailingBytes { actual: usize },
+}
+
+impl ClassFormatError {
+    pub(crate) fn new(offset: usize, kind: ClassFormatErrorKind) -> ClassFormatError {
+        ClassFormatError {
+            offset,
+            section: None,
+            kind,
+        }
+    }
+
+    pub(crate) fn within(mut self, section: Section) -> ClassFormatError {
+        self.section.get_or_insert(section);
+        self
+    }
+}
+
+pub(crate) trait WithinSection {
+    fn within(self, section: Section) -> Self;
+}
+
+impl<T> WithinSection for Result<T, ClassFormatError> {
+    fn within(self, section: Section) -> Self {
+        self.map_err(|e| e.within(section))
+    }
+}
+
+impl fmt::Display for ClassFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.kind, self.offset)?;
+        match self.section {
+            Some(section) => write!(f, " while parsing {}", section),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Section::Header => write!(f, "header"),
+            Section::ConstantPool(index) => write!(f, "constant pool entry #{}", index),
+            Section::ClassInfo => write!(f, "class info"),
+            Section::Interfaces => write!(f, "interfaces"),
+            Section::Field(index) => write!(f, "field #{}", index),
+            Section::Method(index) => write!(f, "method #{}", index),
+            Section::Attributes => write!(f, "class attributes"),
+        }
+    }
+}
+
+impl fmt::Display for ClassFormatErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClassFormatErrorKind::UnexpectedEof { expected, actual } => write!(
+                f,
+                "Truncated class file: expected {} bytes, found {}",
+                expected, actual
+            ),
+            ClassFormatErrorKind::Magic { expected, actual } => write!(
+                f,
+                "Incompatible magic value {:#x}, expected {:#x}",
+                actual, expected
+            ),
+            ClassFormatErrorKind::ConstantTag { actual } => {
+                write!(f, "Unknown constant tag {}", actual)
+            }
+            ClassFormatErrorKind::ConstantTagVersion { actual, version } => write!(
+                f,
+                "Class file version {} does not support constant tag {}",
+                version, actual
+            ),
+            ClassFormatErrorKind::ReferenceKind { actual } => {
+                write!(f, "Bad method handle kind {}", actual)
+            }
+            ClassFormatErrorKind::ConstantIndex { actual } => {
+                write!(f, "Invalid constant pool index {}", actual)
+            }
+            ClassFormatErrorKind::ConstantType { index, actual } => write!(
+                f,
+                "Unexpected constant tag {} at constant pool index {}",
+                actual, index
+            ),
+            ClassFormatErrorKind::BootstrapMethodIndex { actual } => {
+                write!(f, "Invalid bootstrap method index {}", actual)
+            }
+            ClassFormatErrorKind::Utf8 => write!(f, "Illegal UTF8 string in constant pool"),
+            ClassFormatErrorKind::AttributeLength { expected, actual } => write!(
+                f,
+                "Attribute length {} does not match its contents of {} bytes",
+                expected, actual
+            ),
+            ClassFormatErrorKind::AttributeTag { attribute, actual } => {
+                write!(f, "Unknown {} tag {}", attribute, actual)
+            }
+            ClassFormatErrorKind::MissingAttribute { expected } => {
+                write!(f, "Absent {} attribute", expected)
+            }
+            ClassFormatErrorKind::Descriptor { actual } => {
+                write!(f, "Illegal method descriptor \"{}\"", actual)
+            }
+            ClassFormatErrorKind::Instruction { opcode } => {
+                write!(f, "Malformed instruction {:#04x}", opcode)
+            }
+            ClassFormatErrorKind::TableSwitchRange { low, high } => write!(
+                f,
+                "low {} must be less than or equal to high {} in tableswitch",
+                low, high
+            ),
+            ClassFormatErrorKind::LookupSwitchCount { actual } => {
+                write!(f, "number of keys {} in lookupswitch less than 0", actual)
+            }
+            ClassFormatErrorKind::ExceptionTable { pc } => {
+                write!(f, "Exception table entry refers to illegal offset {}", pc)
+            }
+            ClassFormatErrorKind::TrailingBytes { actual } => {
+                write!(f, "Extra {} bytes at the end of class file", actual)
+            }
+        }
+    }
+}
+
+pub(crate) trait FromReader: Sized {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader;
+}
+
 pub trait ClassReader: Read {
-    fn read_u1(&mut self) -> u8;
-    fn read_u2(&mut self) -> u16;
-    fn read_u4(&mut self) -> u32;
-    fn cp_info(&mut self) -> ConstantInfo;
+    fn offset(&self) -> usize;
+    fn remaining(&self) -> usize;
+    fn read_bytes<const N: usize>(&mut self) -> Result<[u8; N], ClassFormatError>;
+    fn read_vec(&mut self, length: usize) -> Result<Vec<u8>, ClassFormatError>;
+    fn cp_info(&mut self) -> Result<ConstantInfo, ClassFormatError>;
+
+    fn read_u1(&mut self) -> Result<u8, ClassFormatError> {
+        Ok(u8::from_be_bytes(self.read_bytes()?))
+    }
+
+    fn read_u2(&mut self) -> Result<u16, ClassFormatError> {
+        Ok(u16::from_be_bytes(self.read_bytes()?))
+    }
+
+    fn read_u4(&mut self) -> Result<u32, ClassFormatError> {
+        Ok(u32::from_be_bytes(self.read_bytes()?))
+    }
+
+    fn parse<R>(&mut self) -> Result<R, ClassFormatError>
+    where
+        R: FromReader,
+        Self: Sized,
+    {
+        R::from_reader(self)
+    }
+
+    fn parse_table<R>(&mut self) -> Result<Vec<R>, ClassFormatError>
+    where
+        R: FromReader,
+        Self: Sized,
+    {
+        let length = self.read_u2()?;
+        (0..length).map(|_| R::from_reader(self)).collect()
+    }
+
+    fn unexpected_eof(&self, expected: usize) -> ClassFormatError {
+        let kind = ClassFormatErrorKind::UnexpectedEof {
+            expected,
+            actual: self.remaining(),
+        };
+        ClassFormatError::new(self.offset(), kind)
+    }
 }
 
-impl<T> ClassReader for BufReader<T>
+impl<T> ClassReader for Cursor<T>
 where
-    T: Read,
+    T: AsRef<[u8]>,
 {
-    fn read_u1(&mut self) -> u8 {
-        let mut buf = [0u8; 1];
-        self.read_exact(&mut buf).unwrap();
-        u8::from_be_bytes(buf)
-    }
-
-    fn read_u2(&mut self) -> u16 {
-        let mut buf = [0u8; 2];
-        self.read_exact(&mut buf).unwrap();
-        u16::from_be_bytes(buf)
-    }
-
-    fn read_u4(&mut self) -> u32 {
-        let mut buf = [0u8; 4];
-        self.read_exact(&mut buf).unwrap();
-        u32::from_be_bytes(buf)
-    }
-
-    fn cp_info(&mut self) -> ConstantInfo {
-        match self.read_u1() {
-            7 => ConstantInfo::Class(self.into()),
-            9 => ConstantInfo::FieldRef(self.into()),
-            10 => ConstantInfo::MethodRef(self.into()),
-            8 => ConstantInfo::String(self.into()),
-            3 => ConstantInfo::Integer(self.into()),
-            4 => ConstantInfo::Float(self.into()),
-            5 => ConstantInfo::Long(self.into()),
-            6 => ConstantInfo::Double(self.into()),
-            12 => ConstantInfo::NameAndType(self.into()),
-            1 => ConstantInfo::Utf8(self.into()),
+    fn offset(&self) -> usize {
+        self.position() as usize
+    }
+
+    fn remaining(&self) -> usize {
+        self.get_ref().as_ref().len().saturating_sub(self.offset())
+    }
+
+    fn read_bytes<const N: usize>(&mut self) -> Result<[u8; N], ClassFormatError> {
+        let mut buf = [0u8; N];
+        if self.remaining() < N || self.read_exact(&mut buf).is_err() {
+            return Err(self.unexpected_eof(N));
+        }
+        Ok(buf)
+    }
+
+    fn read_vec(&mut self, length: usize) -> Result<Vec<u8>, ClassFormatError> {
+        if self.remaining() < length {
+            return Err(self.unexpected_eof(length));
+        }
+
+        let mut buf = vec![0u8; length];
+        self.read_exact(&mut buf)
+            .map_err(|_| self.unexpected_eof(length))?;
+        Ok(buf)
+    }
+
+    fn cp_info(&mut self) -> Result<ConstantInfo, ClassFormatError> {
+        let offset = self.offset();
+        let constant_info = match self.read_u1()? {
+            CONSTANT_CLASS => ConstantInfo::Class(self.parse()?),
+            CONSTANT_FIELD_REF => ConstantInfo::FieldRef(self.parse()?),
+            CONSTANT_METHOD_REF => ConstantInfo::MethodRef(self.parse()?),
+            CONSTANT_INTERFACE_METHOD_REF => ConstantInfo::InterfaceMethodRef(self.parse()?),
+            CONSTANT_STRING => ConstantInfo::String(self.parse()?),
+            CONSTANT_INTEGER => ConstantInfo::Integer(self.parse()?),
+            CONSTANT_FLOAT => ConstantInfo::Float(self.parse()?),
+            CONSTANT_LONG => ConstantInfo::Long(self.parse()?),
+            CONSTANT_DOUBLE => ConstantInfo::Double(self.parse()?),
+            CONSTANT_NAME_AND_TYPE => ConstantInfo::NameAndType(self.parse()?),
+            CONSTANT_UTF8 => ConstantInfo::Utf8(self.parse()?),
+            CONSTANT_METHOD_HANDLE => ConstantInfo::MethodHandle(self.parse()?),
+            CONSTANT_METHOD_TYPE => ConstantInfo::MethodType(self.parse()?),
+            CONSTANT_DYNAMIC => ConstantInfo::Dynamic(self.parse()?),
+            CONSTANT_INVOKE_DYNAMIC => ConstantInfo::InvokeDynamic(self.parse()?),
+            CONSTANT_MODULE => ConstantInfo::Module(self.parse()?),
+            CONSTANT_PACKAGE => ConstantInfo::Package(self.parse()?),
             tag => {
-                println!("tag {}", tag);
-                unreachable!()
+                let kind = ClassFormatErrorKind::ConstantTag { actual: tag };
+                return Err(ClassFormatError::new(offset, kind));
             }
-        }
+        };
+        Ok(constant_info)
     }
 }
 
@@ -72,14 +335,14 @@ pub struct ConstantClassInfo {
     pub(crate) name_index: u16,
 }
 
-impl<T> From<&mut T> for ConstantClassInfo
-where
-    T: ClassReader,
-{
-    fn from(reader: &mut T) -> Self {
-        ConstantClassInfo {
-            name_index: reader.read_u2(),
-        }
+impl FromReader for ConstantClassInfo {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(ConstantClassInfo {
+            name_index: reader.read_u2()?,
+        })
     }
 }
 
@@ -89,15 +352,15 @@ pub struct ConstantFieldRefInfo {
     pub(crate) name_and_type_index: u16,
 }
 
-impl<T> From<&mut T> for ConstantFieldRefInfo
-where
-    T: ClassReader,
-{
-    fn from(reader: &mut T) -> Self {
-        ConstantFieldRefInfo {
-            class_index: reader.read_u2(),
-            name_and_type_index: reader.read_u2(),
-        }
+impl FromReader for ConstantFieldRefInfo {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(ConstantFieldRefInfo {
+            class_index: reader.read_u2()?,
+            name_and_type_index: reader.read_u2()?,
+        })
     }
 }
 
@@ -107,15 +370,33 @@ pub struct ConstantMethodRefInfo {
     pub(crate) name_and_type_index: u16,
 }
 
-impl<T> From<&mut T> for ConstantMethodRefInfo
-where
-    T: ClassReader,
-{
-    fn from(reader: &mut T) -> Self {
-        ConstantMethodRefInfo {
-            class_index: reader.read_u2(),
-            name_and_type_index: reader.read_u2(),
-        }
+impl FromReader for ConstantMethodRefInfo {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(ConstantMethodRefInfo {
+            class_index: reader.read_u2()?,
+            name_and_type_index: reader.read_u2()?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct ConstantInterfaceMethodRefInfo {
+    pub(crate) class_index: u16,
+    pub(crate) name_and_type_index: u16,
+}
+
+impl FromReader for ConstantInterfaceMethodRefInfo {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(ConstantInterfaceMethodRefInfo {
+            class_index: reader.read_u2()?,
+            name_and_type_index: reader.read_u2()?,
+        })
     }
 }
 
@@ -125,15 +406,15 @@ pub struct ConstantNameAndTypeInfo {
     pub(crate) descriptor_index: u16,
 }
 
-impl<T> From<&mut T> for ConstantNameAndTypeInfo
-where
-    T: ClassReader,
-{
-    fn from(reader: &mut T) -> Self {
-        ConstantNameAndTypeInfo {
-            name_index: reader.read_u2(),
-            descriptor_index: reader.read_u2(),
-        }
+impl FromReader for ConstantNameAndTypeInfo {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(ConstantNameAndTypeInfo {
+            name_index: reader.read_u2()?,
+            descriptor_index: reader.read_u2()?,
+        })
     }
 }
 
@@ -142,14 +423,14 @@ pub struct ConstantStringInfo {
     pub(crate) string_index: u16,
 }
 
-impl<T> From<&mut T> for ConstantStringInfo
-where
-    T: ClassReader,
-{
-    fn from(reader: &mut T) -> Self {
-        ConstantStringInfo {
-            string_index: reader.read_u2(),
-        }
+impl FromReader for ConstantStringInfo {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(ConstantStringInfo {
+            string_index: reader.read_u2()?,
+        })
     }
 }
 
@@ -158,17 +439,14 @@ pub struct ConstantIntegerInfo {
     pub(crate) value: i32,
 }
 
-impl<T> From<&mut T> for ConstantIntegerInfo
-where
-    T: ClassReader,
-{
-    fn from(reader: &mut T) -> Self {
-        let mut buf = [0u8; 4];
-        reader.read_exact(&mut buf).unwrap();
-
-        ConstantIntegerInfo {
-            value: i32::from_be_bytes(buf),
-        }
+impl FromReader for ConstantIntegerInfo {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(ConstantIntegerInfo {
+            value: i32::from_be_bytes(reader.read_bytes()?),
+        })
     }
 }
 
@@ -177,17 +455,14 @@ pub struct ConstantFloatInfo {
     pub(crate) value: f32,
 }
 
-impl<T> From<&mut T> for ConstantFloatInfo
-where
-    T: ClassReader,
-{
-    fn from(reader: &mut T) -> Self {
-        let mut buf = [0u8; 4];
-        reader.read_exact(&mut buf).unwrap();
-
-        ConstantFloatInfo {
-            value: f32::from_be_bytes(buf),
-        }
+impl FromReader for ConstantFloatInfo {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(ConstantFloatInfo {
+            value: f32::from_be_bytes(reader.read_bytes()?),
+        })
     }
 }
 
@@ -196,17 +471,14 @@ pub struct ConstantLongInfo {
     pub(crate) value: i64,
 }
 
-impl<T> From<&mut T> for ConstantLongInfo
-where
-    T: ClassReader,
-{
-    fn from(reader: &mut T) -> Self {
-        let mut buf = [0u8; 8];
-        reader.read_exact(&mut buf).unwrap();
-
-        ConstantLongInfo {
-            value: i64::from_be_bytes(buf),
-        }
+impl FromReader for ConstantLongInfo {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(ConstantLongInfo {
+            value: i64::from_be_bytes(reader.read_bytes()?),
+        })
     }
 }
 
@@ -215,46 +487,171 @@ pub struct ConstantDoubleInfo {
     pub(crate) value: f64,
 }
 
-impl<T> From<&mut T> for ConstantDoubleInfo
-where
-    T: ClassReader,
-{
-    fn from(reader: &mut T) -> Self {
-        let mut buf = [0u8; 8];
-        reader.read_exact(&mut buf).unwrap();
-
-        ConstantDoubleInfo {
-            value: f64::from_be_bytes(buf),
-        }
+impl FromReader for ConstantDoubleInfo {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(ConstantDoubleInfo {
+            value: f64::from_be_bytes(reader.read_bytes()?),
+        })
     }
 }
 
 #[derive(Debug)]
 pub struct ConstantUtf8Info {
     pub(crate) value: String,
+    /// The original bytes when re-encoding the value would not reproduce them,
+    /// for the class writer.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub(crate) bytes: Option<Box<[u8]>>,
 }
 
-impl<T> From<&mut T> for ConstantUtf8Info
-where
-    T: ClassReader,
-{
-    fn from(reader: &mut T) -> Self {
-        let length = reader.read_u2();
+impl FromReader for ConstantUtf8Info {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        let length = reader.read_u2()?;
+
+        let offset = reader.offset();
+        let buf = reader.read_vec(length as usize)?;
+        let value = mutf8::decode(&buf).map_err(|position| {
+            ClassFormatError::new(offset + position, ClassFormatErrorKind::Utf8)
+        })?;
+
+        let bytes = (mutf8::encode(&value) != buf).then(|| buf.into_boxed_slice());
+        Ok(ConstantUtf8Info { value, bytes })
+    }
+}
 
-        let mut buf = vec![0u8; length as usize];
-        reader.read_exact(&mut buf).unwrap();
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReferenceKind {
+    GetField = 1,
+    GetStatic = 2,
+    PutField = 3,
+    PutStatic = 4,
+    InvokeVirtual = 5,
+    InvokeStatic = 6,
+    InvokeSpecial = 7,
+    NewInvokeSpecial = 8,
+    InvokeInterface = 9,
+}
+
+impl TryFrom<u8> for ReferenceKind {
+    type Error = u8;
 
-        ConstantUtf8Info {
-            value: from_utf8(&buf).unwrap().to_string(),
+    fn try_from(kind: u8) -> Result<Self, Self::Error> {
+        match kind {
+            1 => Ok(ReferenceKind::GetField),
+            2 => Ok(ReferenceKind::GetStatic),
+            3 => Ok(ReferenceKind::PutField),
+            4 => Ok(ReferenceKind::PutStatic),
+            5 => Ok(ReferenceKind::InvokeVirtual),
+            6 => Ok(ReferenceKind::InvokeStatic),
+            7 => Ok(ReferenceKind::InvokeSpecial),
+            8 => Ok(ReferenceKind::NewInvokeSpecial),
+            9 => Ok(ReferenceKind::InvokeInterface),
+            kind => Err(kind),
         }
     }
 }
 
+#[derive(Debug)]
+pub struct ConstantMethodHandleInfo {
+    pub(crate) reference_kind: ReferenceKind,
+    pub(crate) reference_index: u16,
+}
+
+impl FromReader for ConstantMethodHandleInfo {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        let offset = reader.offset();
+        let reference_kind = ReferenceKind::try_from(reader.read_u1()?).map_err(|actual| {
+            ClassFormatError::new(offset, ClassFormatErrorKind::ReferenceKind { actual })
+        })?;
+
+        Ok(ConstantMethodHandleInfo {
+            reference_kind,
+            reference_index: reader.read_u2()?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct ConstantMethodTypeInfo {
+    pub(crate) descriptor_index: u16,
+}
+
+impl FromReader for ConstantMethodTypeInfo {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(ConstantMethodTypeInfo {
+            descriptor_index: reader.read_u2()?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct ConstantDynamicInfo {
+    pub(crate) bootstrap_method_attr_index: u16,
+    pub(crate) name_and_type_index: u16,
+}
+
+impl FromReader for ConstantDynamicInfo {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(ConstantDynamicInfo {
+            bootstrap_method_attr_index: reader.read_u2()?,
+            name_and_type_index: reader.read_u2()?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct ConstantModuleInfo {
+    pub(crate) name_index: u16,
+}
+
+impl FromReader for ConstantModuleInfo {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(ConstantModuleInfo {
+            name_index: reader.read_u2()?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct ConstantPackageInfo {
+    pub(crate) name_index: u16,
+}
+
+impl FromReader for ConstantPackageInfo {
+    fn from_reader<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        Ok(ConstantPackageInfo {
+            name_index: reader.read_u2()?,
+        })
+    }
+}
+
 #[derive(Debug)]
 pub enum ConstantInfo {
     Class(ConstantClassInfo),
     FieldRef(ConstantFieldRefInfo),
     MethodRef(ConstantMethodRefInfo),
+    InterfaceMethodRef(ConstantInterfaceMethodRefInfo),
     String(ConstantStringInfo),
     Integer(ConstantIntegerInfo),
     Float(ConstantFloatInfo),
@@ -262,118 +659,303 @@ pub enum ConstantInfo {
     Double(ConstantDoubleInfo),
     NameAndType(ConstantNameAndTypeInfo),
     Utf8(ConstantUtf8Info),
+    MethodHandle(ConstantMethodHandleInfo),
+    MethodType(ConstantMethodTypeInfo),
+    Dynamic(ConstantDynamicInfo),
+    InvokeDynamic(ConstantDynamicInfo),
+    Module(ConstantModuleInfo),
+    Package(ConstantPackageInfo),
     Unusable,
 }
 
-#[derive(Debug)]
-pub(crate) struct AttributeInfo {
-    pub(crate) attribute_name_index: u16,
-    pub(crate) info: Box<[u8]>,
-}
-
-impl<T> From<&mut T> for AttributeInfo
-where
-    T: ClassReader,
-{
-    fn from(reader: &mut T) -> Self {
-        let attribute_name_index = reader.read_u2();
-        let attribute_length = reader.read_u4();
+impl ConstantInfo {
+    pub(crate) fn tag(&self) -> u8 {
+        match self {
+            ConstantInfo::Utf8(_) => CONSTANT_UTF8,
+            ConstantInfo::Integer(_) => CONSTANT_INTEGER,
+            ConstantInfo::Float(_) => CONSTANT_FLOAT,
+            ConstantInfo::Long(_) => CONSTANT_LONG,
+            ConstantInfo::Double(_) => CONSTANT_DOUBLE,
+            ConstantInfo::Class(_) => CONSTANT_CLASS,
+            ConstantInfo::String(_) => CONSTANT_STRING,
+            ConstantInfo::FieldRef(_) => CONSTANT_FIELD_REF,
+            ConstantInfo::MethodRef(_) => CONSTANT_METHOD_REF,
+            ConstantInfo::InterfaceMethodRef(_) => CONSTANT_INTERFACE_METHOD_REF,
+            ConstantInfo::NameAndType(_) => CONSTANT_NAME_AND_TYPE,
+            ConstantInfo::MethodHandle(_) => CONSTANT_METHOD_HANDLE,
+            ConstantInfo::MethodType(_) => CONSTANT_METHOD_TYPE,
+            ConstantInfo::Dynamic(_) => CONSTANT_DYNAMIC,
+            ConstantInfo::InvokeDynamic(_) => CONSTANT_INVOKE_DYNAMIC,
+            ConstantInfo::Module(_) => CONSTANT_MODULE,
+            ConstantInfo::Package(_) => CONSTANT_PACKAGE,
+            ConstantInfo::Unusable => 0,
+        }
+    }
 
-        let mut buf = vec![0u8; attribute_length as usize];
-        reader.read_exact(&mut buf).unwrap();
+    /// Checks that the entries the constant refers to have the tags the
+    /// accessors of the constant pool rely on.
+    fn check_references(
+        &self,
+        constant_pool: &[ConstantInfo],
+        offset: usize,
+    ) -> Result<(), ClassFormatError> {
+        let check = |index, tags: &[u8]| check_constant(constant_pool, offset, index, tags);
+        let member_ref = |class_index, name_and_type_index| {
+            check(class_index, &[CONSTANT_CLASS])?;
+            check(name_and_type_index, &[CONSTANT_NAME_AND_TYPE])
+        };
+        match self {
+            ConstantInfo::Class(info) => check(info.name_index, &[CONSTANT_UTF8]),
+            ConstantInfo::FieldRef(info) => member_ref(info.class_index, info.name_and_type_index),
+            ConstantInfo::MethodRef(info) => member_ref(info.class_index, info.name_and_type_index),
+            ConstantInfo::InterfaceMethodRef(info) => {
+                member_ref(info.class_index, info.name_and_type_index)
+            }
+            ConstantInfo::String(info) => check(info.string_index, &[CONSTANT_UTF8]),
+            ConstantInfo::NameAndType(info) => {
+                check(info.name_index, &[CONSTANT_UTF8])?;
+                check(info.descriptor_index, &[CONSTANT_UTF8])
+            }
+            ConstantInfo::MethodHandle(info) => {
+                let tags: &[u8] = match info.reference_kind {
+                    ReferenceKind::GetField
+                    | ReferenceKind::GetStatic
+                    | ReferenceKind::PutField
+                    | ReferenceKind::PutStatic => &[CONSTANT_FIELD_REF],
+                    ReferenceKind::InvokeVirtual | ReferenceKind::NewInvokeSpecial => {
+                        &[CONSTANT_METHOD_REF]
+                    }
+                    ReferenceKind::InvokeStatic | ReferenceKind::InvokeSpecial => {
+                        &[CONSTANT_METHOD_REF, CONSTANT_INTERFACE_METHOD_REF]
+                    }
+                    ReferenceKind::InvokeInterface => &[CONSTANT_INTERFACE_METHOD_REF],
+                };
+                check(info.reference_index, tags)
+            }
+            ConstantInfo::MethodType(info) => check(info.descriptor_index, &[CONSTANT_UTF8]),
+            ConstantInfo::Dynamic(info) | ConstantInfo::InvokeDynamic(info) => {
+                check(info.name_and_type_index, &[CONSTANT_NAME_AND_TYPE])
+            }
+            ConstantInfo::Module(info) => check(info.name_index, &[CONSTANT_UTF8]),
+            ConstantInfo::Package(info) => check(info.name_index, &[CONSTANT_UTF8]),
+            _ => Ok(()),
+        }
+    }
+}
 
-        AttributeInfo {
-            attribute_name_index,
-            info: buf.into_boxed_slice(),
+/// Checks that the index refers to an entry of the constant pool with one of
+/// the tags.
+pub(crate) fn check_constant(
+    constant_pool: &[ConstantInfo],
+    offset: usize,
+    index: u16,
+    tags: &[u8],
+) -> Result<(), ClassFormatError> {
+    let kind = match constant_pool.get((index as usize).wrapping_sub(1)) {
+        None | Some(ConstantInfo::Unusable) => {
+            ClassFormatErrorKind::ConstantIndex { actual: index }
         }
+        Some(info) if tags.contains(&info.tag()) => return Ok(()),
+        Some(info) => ClassFormatErrorKind::ConstantType {
+            index,
+            actual: info.tag(),
+        },
+    };
+    Err(ClassFormatError::new(offset, kind))
+}
+
+/// The tags the ConstantValue attribute of a field with the descriptor may
+/// refer to, none for the types that cannot have one.
+fn constant_value_tags(descriptor: &str) -> &'static [u8] {
+    match descriptor {
+        "B" | "C" | "I" | "S" | "Z" => &[CONSTANT_INTEGER],
+        "F" => &[CONSTANT_FLOAT],
+        "J" => &[CONSTANT_LONG],
+        "D" => &[CONSTANT_DOUBLE],
+        "Ljava/lang/String;" => &[CONSTANT_STRING],
+        _ => &[],
     }
 }
 
 #[derive(Debug)]
 pub(crate) struct FieldInfo {
-    access_flags: u16,
-    name_index: u16,
-    descriptor_index: u16,
-    attributes_count: u16,
-    attributes: Box<[AttributeInfo]>,
+    pub(crate) access_flags: u16,
+    pub(crate) name_index: u16,
+    pub(crate) descriptor_index: u16,
+    pub(crate) attributes: Vec<AttributeInfo>,
 }
 
-impl<T> From<&mut T> for FieldInfo
-where
-    T: ClassReader,
-{
-    fn from(reader: &mut T) -> Self {
-        let access_flags = reader.read_u2();
-        let name_index = reader.read_u2();
-        let descriptor_index = reader.read_u2();
-        let attributes_count = reader.read_u2();
+impl FieldInfo {
+    fn read<T>(reader: &mut T, constant_pool: &[ConstantInfo]) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        let offset = reader.offset();
+        let field_info = FieldInfo {
+            access_flags: reader.read_u2()?,
+            name_index: reader.read_u2()?,
+            descriptor_index: reader.read_u2()?,
+            attributes: read_attributes(reader, constant_pool)?,
+        };
+        check_constant(
+            constant_pool,
+            offset,
+            field_info.name_index,
+            &[CONSTANT_UTF8],
+        )?;
+        check_constant(
+            constant_pool,
+            offset,
+            field_info.descriptor_index,
+            &[CONSTANT_UTF8],
+        )?;
 
-        FieldInfo {
-            access_flags,
-            name_index,
-            descriptor_index,
-            attributes_count,
-            attributes: (0..attributes_count)
-                .into_iter()
-                .map(|_| AttributeInfo::from(&mut *reader))
-                .collect::<Box<[AttributeInfo]>>(),
+        // the attribute is ignored for instance fields (JVMS 4.7.2)
+        let descriptor = &constant_pool[field_info.descriptor_index as usize - 1];
+        if let (Some(index), ConstantInfo::Utf8(descriptor)) =
+            (field_info.constant_value(), descriptor)
+        {
+            if field_info.access_flags & ACC_STATIC != 0 {
+                let tags = constant_value_tags(&descriptor.value);
+                check_constant(constant_pool, offset, index, tags)?;
+            }
         }
+        Ok(field_info)
+    }
+
+    pub(crate) fn constant_value(&self) -> Option<u16> {
+        self.attributes
+            .iter()
+            .find_map(|info| match info.attribute {
+                Attribute::ConstantValue(index) => Some(index),
+                _ => None,
+            })
+    }
+
+    pub(crate) fn signature(&self) -> Option<u16> {
+        signature(&self.attributes)
     }
 }
 
 #[derive(Debug)]
 pub(crate) struct MethodInfo {
+    /// The offset of the method in the class file.
+    pub(crate) offset: usize,
     pub(crate) access_flags: u16,
     pub(crate) name_index: u16,
     pub(crate) descriptor_index: u16,
     pub(crate) attributes: Vec<AttributeInfo>,
 }
 
-impl<T> From<&mut T> for MethodInfo
-where
-    T: ClassReader,
-{
-    fn from(reader: &mut T) -> Self {
-        let access_flags = reader.read_u2();
-        let name_index = reader.read_u2();
-        let descriptor_index = reader.read_u2();
-        let attributes_count = reader.read_u2();
+impl MethodInfo {
+    fn read<T>(reader: &mut T, constant_pool: &[ConstantInfo]) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        let offset = reader.offset();
+        let method_info = MethodInfo {
+            offset,
+            access_flags: reader.read_u2()?,
+            name_index: reader.read_u2()?,
+            descriptor_index: reader.read_u2()?,
+            attributes: read_attributes(reader, constant_pool)?,
+        };
+        check_constant(
+            constant_pool,
+            offset,
+            method_info.name_index,
+            &[CONSTANT_UTF8],
+        )?;
+        check_constant(
+            constant_pool,
+            offset,
+            method_info.descriptor_index,
+            &[CONSTANT_UTF8],
+        )?;
+        Ok(method_info)
+    }
 
-        MethodInfo {
-            access_flags,
-            name_index,
-            descriptor_index,
-            attributes: (0..attributes_count)
-                .into_iter()
-                .map(|_| AttributeInfo::from(&mut *reader))
-                .collect(),
-        }
+    pub(crate) fn code(&self) -> Option<&CodeAttribute> {
+        self.attributes
+            .iter()
+            .find_map(|info| match &info.attribute {
+                Attribute::Code(code) => Some(code),
+                _ => None,
+            })
+    }
+
+    pub(crate) fn exceptions(&self) -> Option<&[u16]> {
+        self.attributes
+            .iter()
+            .find_map(|info| match &info.attribute {
+                Attribute::Exceptions(exceptions) => Some(exceptions.as_slice()),
+                _ => None,
+            })
+    }
+
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub(crate) fn method_parameters(&self) -> Option<&[MethodParameter]> {
+        self.attributes
+            .iter()
+            .find_map(|info| match &info.attribute {
+                Attribute::MethodParameters(parameters) => Some(parameters.as_slice()),
+                _ => None,
+            })
+    }
+
+    pub(crate) fn signature(&self) -> Option<u16> {
+        signature(&self.attributes)
     }
 }
 
-impl<T> From<T> for ClassFile
-where
-    T: ClassReader,
-{
-    fn from(mut reader: T) -> Self {
-        let magic = reader.read_u4();
-        assert_eq!(
-            magic, 0xcafebabe,
-            "Incompatible magic value {:#x} in class file Foo",
-            magic
-        );
+fn signature(attributes: &[AttributeInfo]) -> Option<u16> {
+    attributes.iter().find_map(|info| match info.attribute {
+        Attribute::Signature(index) => Some(index),
+        _ => None,
+    })
+}
+
+impl ClassFile {
+    fn read<T>(reader: &mut T) -> Result<Self, ClassFormatError>
+    where
+        T: ClassReader,
+    {
+        let magic = reader.read_u4().within(Section::Header)?;
+        if magic != MAGIC {
+            let kind = ClassFormatErrorKind::Magic {
+                expected: MAGIC,
+                actual: magic,
+            };
+            return Err(ClassFormatError::new(0, kind)).within(Section::Header);
+        }
 
-        let minor_version = reader.read_u2();
-        let major_version = reader.read_u2();
-        let constant_pool_count = reader.read_u2();
+        let minor_version = reader.read_u2().within(Section::Header)?;
+        let major_version = reader.read_u2().within(Section::Header)?;
+        let constant_pool_count = reader.read_u2().within(Section::Header)?;
+        let version = ClassVersion {
+            major: major_version,
+            minor: minor_version,
+        };
 
         let mut constant_pool = vec![];
+        let mut offsets = vec![];
         let mut index = 1;
         while index < constant_pool_count {
-            let cp_info = reader.cp_info();
+            let offset = reader.offset();
+            offsets.push(offset);
+            let cp_info = reader.cp_info().within(Section::ConstantPool(index))?;
+            if !version.supports_constant_tag(cp_info.tag()) {
+                let kind = ClassFormatErrorKind::ConstantTagVersion {
+                    actual: cp_info.tag(),
+                    version,
+                };
+                return Err(ClassFormatError::new(offset, kind))
+                    .within(Section::ConstantPool(index));
+            }
             if matches!(cp_info, ConstantInfo::Long(_) | ConstantInfo::Double(_)) {
                 constant_pool.push(cp_info);
                 constant_pool.push(ConstantInfo::Unusable);
+                offsets.push(offset);
                 index += 2;
             } else {
                 constant_pool.push(cp_info);
@@ -381,35 +963,71 @@ where
             }
         }
 
-        let access_flags = reader.read_u2();
-        let this_class = reader.read_u2();
-        let super_class = reader.read_u2();
+        for ((info, &offset), index) in constant_pool.iter().zip(&offsets).zip(1..) {
+            info.check_references(&constant_pool, offset)
+                .within(Section::ConstantPool(index))?;
+        }
 
-        let interfaces_count = reader.read_u2();
+        let offset = reader.offset();
+        let access_flags = reader.read_u2().within(Section::ClassInfo)?;
+        let this_class = reader.read_u2().within(Section::ClassInfo)?;
+        let super_class = reader.read_u2().within(Section::ClassInfo)?;
+        check_constant(&constant_pool, offset, this_class, &[CONSTANT_CLASS])
+            .within(Section::ClassInfo)?;
+        if super_class != 0 {
+            check_constant(&constant_pool, offset, super_class, &[CONSTANT_CLASS])
+                .within(Section::ClassInfo)?;
+        }
+
+        let interfaces_count = reader.read_u2().within(Section::Interfaces)?;
         let interfaces = (0..interfaces_count)
-            .into_iter()
-            .map(|_| reader.read_u2())
-            .collect();
+            .map(|_| {
+                let offset = reader.offset();
+                let index = reader.read_u2()?;
+                check_constant(&constant_pool, offset, index, &[CONSTANT_CLASS])?;
+                Ok(index)
+            })
+            .collect::<Result<_, _>>()
+            .within(Section::Interfaces)?;
 
-        let fields_count = reader.read_u2();
+        let fields_count = reader.read_u2().within(Section::Field(0))?;
         let fields = (0..fields_count)
-            .into_iter()
-            .map(|_| FieldInfo::from(&mut reader))
-            .collect();
+            .map(|index| FieldInfo::read(reader, &constant_pool).within(Section::Field(index)))
+            .collect::<Result<_, _>>()?;
 
-        let methods_count = reader.read_u2();
+        let methods_count = reader.read_u2().within(Section::Method(0))?;
         let methods = (0..methods_count)
-            .into_iter()
-            .map(|_| MethodInfo::from(&mut reader))
-            .collect();
+            .map(|index| MethodInfo::read(reader, &constant_pool).within(Section::Method(index)))
+            .collect::<Result<_, _>>()?;
 
-        let attributes_count = reader.read_u2();
-        let attributes = (0..attributes_count)
-            .into_iter()
-            .map(|_| AttributeInfo::from(&mut reader))
-            .collect();
+        let attributes = read_attributes(reader, &constant_pool).within(Section::Attributes)?;
 
-        ClassFile {
+        let bootstrap_methods = attributes
+            .iter()
+            .find_map(|info| match &info.attribute {
+                Attribute::BootstrapMethods(methods) => Some(methods.len()),
+                _ => None,
+            })
+            .unwrap_or(0);
+        for ((info, &offset), index) in constant_pool.iter().zip(&offsets).zip(1..) {
+            if let ConstantInfo::Dynamic(info) | ConstantInfo::InvokeDynamic(info) = info {
+                let actual = info.bootstrap_method_attr_index;
+                if actual as usize >= bootstrap_methods {
+                    let kind = ClassFormatErrorKind::BootstrapMethodIndex { actual };
+                    return Err(ClassFormatError::new(offset, kind))
+                        .within(Section::ConstantPool(index));
+                }
+            }
+        }
+
+        if reader.remaining() > 0 {
+            let kind = ClassFormatErrorKind::TrailingBytes {
+                actual: reader.remaining(),
+            };
+            return Err(ClassFormatError::new(reader.offset(), kind));
+        }
+
+        Ok(ClassFile {
             magic,
             minor_version,
             major_version,
@@ -421,18 +1039,573 @@ where
             fields,
             methods,
             attributes,
+        })
+    }
+}
+
+impl ClassFile {
+    pub(crate) fn version(&self) -> ClassVersion {
+        ClassVersion {
+            major: self.major_version,
+            minor: self.minor_version,
         }
     }
+
+    pub(crate) fn utf8(&self, index: u16) -> Option<&str> {
+        match self.constant_pool.get((index as usize).wrapping_sub(1))? {
+            ConstantInfo::Utf8(info) => Some(&info.value),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn source_file(&self) -> Option<u16> {
+        self.attributes
+            .iter()
+            .find_map(|info| match info.attribute {
+                Attribute::SourceFile(index) => Some(index),
+                _ => None,
+            })
+    }
+
+    pub(crate) fn signature(&self) -> Option<u16> {
+        signature(&self.attributes)
+    }
+
+    pub(crate) fn bootstrap_methods(&self) -> Option<&[BootstrapMethod]> {
+        self.attributes
+            .iter()
+            .find_map(|info| match &info.attribute {
+                Attribute::BootstrapMethods(methods) => Some(methods.as_slice()),
+                _ => None,
+            })
+    }
 }
 
-impl From<File> for ClassFile {
-    fn from(f: File) -> Self {
-        BufReader::new(f).into()
+// the attributes the VM does not act on, read only by the tests so far
+#[cfg_attr(not(test), allow(dead_code))]
+impl ClassFile {
+    pub(crate) fn inner_classes(&self) -> Option<&[InnerClass]> {
+        self.attributes
+            .iter()
+            .find_map(|info| match &info.attribute {
+                Attribute::InnerClasses(classes) => Some(classes.as_slice()),
+                _ => None,
+            })
+    }
+
+    pub(crate) fn enclosing_method(&self) -> Option<&EnclosingMethod> {
+        self.attributes
+            .iter()
+            .find_map(|info| match &info.attribute {
+                Attribute::EnclosingMethod(method) => Some(method),
+                _ => None,
+            })
+    }
+
+    pub(crate) fn nest_host(&self) -> Option<u16> {
+        self.attributes
+            .iter()
+            .find_map(|info| match info.attribute {
+                Attribute::NestHost(index) => Some(index),
+                _ => None,
+            })
+    }
+
+    pub(crate) fn nest_members(&self) -> Option<&[u16]> {
+        self.attributes
+            .iter()
+            .find_map(|info| match &info.attribute {
+                Attribute::NestMembers(classes) => Some(classes.as_slice()),
+                _ => None,
+            })
+    }
+
+    pub(crate) fn permitted_subclasses(&self) -> Option<&[u16]> {
+        self.attributes
+            .iter()
+            .find_map(|info| match &info.attribute {
+                Attribute::PermittedSubclasses(classes) => Some(classes.as_slice()),
+                _ => None,
+            })
+    }
+
+    pub(crate) fn record(&self) -> Option<&[RecordComponent]> {
+        self.attributes
+            .iter()
+            .find_map(|info| match &info.attribute {
+                Attribute::Record(components) => Some(components.as_slice()),
+                _ => None,
+            })
+    }
+}
+
+impl TryFrom<&[u8]> for ClassFile {
+    type Error = ClassFormatError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        ClassFile::read(&mut Cursor::new(bytes))
     }
 }
 
-impl From<&[u8]> for ClassFile {
-    fn from(bytes: &[u8]) -> Self {
-        BufReader::new(bytes).into()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attribute::ExceptionTableEntry;
+    use crate::class::Class;
+    use std::fs;
+    use std::path::Path;
+
+    fn class_bytes(name: &str) -> Vec<u8> {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("playground/classes/ru/khlevnov")
+            .join(name);
+        fs::read(path).unwrap()
+    }
+
+    fn parse(name: &str) -> ClassFile {
+        ClassFile::try_from(&class_bytes(name)[..]).unwrap()
+    }
+
+    fn class_name(class_file: &ClassFile, index: u16) -> &str {
+        match &class_file.constant_pool[index as usize - 1] {
+            ConstantInfo::Class(info) => class_file.utf8(info.name_index).unwrap(),
+            _ => panic!("not a class constant"),
+        }
+    }
+
+    fn method<'a>(class_file: &'a ClassFile, name: &str) -> &'a MethodInfo {
+        class_file
+            .methods
+            .iter()
+            .find(|method| class_file.utf8(method.name_index) == Some(name))
+            .unwrap()
+    }
+
+    fn utf8(class_file: &mut ClassFile, value: &str) -> u16 {
+        let info = ConstantUtf8Info {
+            value: value.to_string(),
+            bytes: None,
+        };
+        class_file.constant_pool.push(ConstantInfo::Utf8(info));
+        class_file.constant_pool.len() as u16
+    }
+
+    fn reparse(class_file: &ClassFile) -> Result<ClassFile, ClassFormatError> {
+        ClassFile::try_from(&class_file.to_bytes()[..])
+    }
+
+    /// The class with a `static final` field of the descriptor holding the
+    /// integer constant 42.
+    fn with_constant_field(descriptor: &str) -> ClassFile {
+        let mut class_file = ClassFile::try_from(&class_bytes("Main.class")[..]).unwrap();
+        let name_index = utf8(&mut class_file, "CONSTANT");
+        let descriptor_index = utf8(&mut class_file, descriptor);
+        let attribute_name_index = utf8(&mut class_file, "ConstantValue");
+        let value = ConstantInfo::Integer(ConstantIntegerInfo { value: 42 });
+        class_file.constant_pool.push(value);
+        let value_index = class_file.constant_pool.len() as u16;
+        class_file.fields.push(FieldInfo {
+            access_flags: ACC_STATIC | 0x0010,
+            name_index,
+            descriptor_index,
+            attributes: vec![AttributeInfo {
+                attribute_name_index,
+                attribute: Attribute::ConstantValue(value_index),
+            }],
+        });
+        class_file
+    }
+
+    #[test]
+    fn rejects_truncated_class_files() {
+        let bytes = class_bytes("Main.class");
+        for length in 0..bytes.len() {
+            let error = ClassFile::try_from(&bytes[..length]).unwrap_err();
+            assert!(
+                matches!(error.kind, ClassFormatErrorKind::UnexpectedEof { .. }),
+                "{}: {}",
+                length,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let mut bytes = class_bytes("Main.class");
+        bytes[0] = 0xca;
+        bytes[3] = 0xfe;
+        let error = ClassFile::try_from(&bytes[..]).unwrap_err();
+        assert_eq!(
+            error.kind,
+            ClassFormatErrorKind::Magic {
+                expected: MAGIC,
+                actual: 0xcafebafe
+            }
+        );
+        assert_eq!(error.section, Some(Section::Header));
+    }
+
+    #[test]
+    fn rejects_out_of_range_constant_index() {
+        let mut class_file = ClassFile::try_from(&class_bytes("Main.class")[..]).unwrap();
+        class_file.this_class = 999;
+        let error = reparse(&class_file).unwrap_err();
+        assert_eq!(
+            error.kind,
+            ClassFormatErrorKind::ConstantIndex { actual: 999 }
+        );
+        assert_eq!(error.section, Some(Section::ClassInfo));
+
+        let mut class_file = ClassFile::try_from(&class_bytes("Main.class")[..]).unwrap();
+        class_file.methods[0].name_index = 0;
+        let error = reparse(&class_file).unwrap_err();
+        assert_eq!(
+            error.kind,
+            ClassFormatErrorKind::ConstantIndex { actual: 0 }
+        );
+        assert_eq!(error.section, Some(Section::Method(0)));
+    }
+
+    #[test]
+    fn rejects_reference_to_constant_of_wrong_tag() {
+        let mut class_file = ClassFile::try_from(&class_bytes("Main.class")[..]).unwrap();
+        let utf8_index = utf8(&mut class_file, "ru/khlevnov/Main");
+        class_file.super_class = utf8_index;
+        let error = reparse(&class_file).unwrap_err();
+        assert_eq!(
+            error.kind,
+            ClassFormatErrorKind::ConstantType {
+                index: utf8_index,
+                actual: CONSTANT_UTF8
+            }
+        );
+
+        // a class constant naming another class constant
+        let mut class_file = ClassFile::try_from(&class_bytes("Main.class")[..]).unwrap();
+        let class_index = class_file.this_class;
+        let info = ConstantClassInfo {
+            name_index: class_index,
+        };
+        class_file.constant_pool.push(ConstantInfo::Class(info));
+        let index = class_file.constant_pool.len() as u16;
+        let error = reparse(&class_file).unwrap_err();
+        assert_eq!(
+            error.kind,
+            ClassFormatErrorKind::ConstantType {
+                index: class_index,
+                actual: CONSTANT_CLASS
+            }
+        );
+        assert_eq!(error.section, Some(Section::ConstantPool(index)));
+    }
+
+    #[test]
+    fn checks_constant_value_against_field_type() {
+        assert!(reparse(&with_constant_field("I")).is_ok());
+
+        let error = reparse(&with_constant_field("J")).unwrap_err();
+        assert!(matches!(
+            error.kind,
+            ClassFormatErrorKind::ConstantType {
+                actual: CONSTANT_INTEGER,
+                ..
+            }
+        ));
+        assert!(matches!(error.section, Some(Section::Field(_))));
+    }
+
+    #[test]
+    fn reads_nest_and_inner_class_attributes() {
+        let outer = parse("Bar.class");
+        let members = outer.nest_members().unwrap();
+        let names: Vec<_> = members
+            .iter()
+            .map(|&index| class_name(&outer, index))
+            .collect();
+        assert_eq!(names, ["ru/khlevnov/Bar$BarInner"]);
+        assert!(outer.nest_host().is_none());
+
+        let inner = parse("Bar$BarInner.class");
+        assert_eq!(
+            class_name(&inner, inner.nest_host().unwrap()),
+            "ru/khlevnov/Bar"
+        );
+        assert!(inner.nest_members().is_none());
+        let inner_classes = inner.inner_classes().unwrap();
+        assert_eq!(inner_classes.len(), 1);
+        let inner_class = &inner_classes[0];
+        assert_eq!(
+            class_name(&inner, inner_class.inner_class_info_index),
+            "ru/khlevnov/Bar$BarInner"
+        );
+        assert_eq!(
+            class_name(&inner, inner_class.outer_class_info_index),
+            "ru/khlevnov/Bar"
+        );
+        assert_eq!(inner.utf8(inner_class.inner_name_index), Some("BarInner"));
+        assert_eq!(inner_class.inner_class_access_flags, ACC_STATIC);
+
+        assert!(parse("Main.class").inner_classes().is_none());
+    }
+
+    #[test]
+    fn reads_enclosing_method() {
+        let local = parse("Attributes$1Local.class");
+        let enclosing_method = local.enclosing_method().unwrap();
+        assert_eq!(
+            class_name(&local, enclosing_method.class_index),
+            "ru/khlevnov/Attributes"
+        );
+        match &local.constant_pool[enclosing_method.method_index as usize - 1] {
+            ConstantInfo::NameAndType(info) => {
+                assert_eq!(local.utf8(info.name_index), Some("first"));
+                assert_eq!(
+                    local.utf8(info.descriptor_index),
+                    Some("(Ljava/util/List;)Ljava/util/function/Supplier;")
+                );
+            }
+            constant => panic!("{:?}", constant),
+        }
+
+        assert!(parse("Attributes$Circle.class")
+            .enclosing_method()
+            .is_none());
+    }
+
+    #[test]
+    fn reads_bootstrap_methods() {
+        let class_file = parse("Attributes.class");
+        let bootstrap_methods = class_file.bootstrap_methods().unwrap();
+        assert_eq!(bootstrap_methods.len(), 1);
+        let bootstrap_method = &bootstrap_methods[0];
+        assert!(matches!(
+            class_file.constant_pool[bootstrap_method.bootstrap_method_ref as usize - 1],
+            ConstantInfo::MethodHandle(_)
+        ));
+        let tags: Vec<_> = bootstrap_method
+            .bootstrap_arguments
+            .iter()
+            .map(|&index| class_file.constant_pool[index as usize - 1].tag())
+            .collect();
+        assert_eq!(
+            tags,
+            [
+                CONSTANT_METHOD_TYPE,
+                CONSTANT_METHOD_HANDLE,
+                CONSTANT_METHOD_TYPE
+            ]
+        );
+
+        assert!(parse("Main.class").bootstrap_methods().is_none());
+    }
+
+    #[test]
+    fn reads_permitted_subclasses() {
+        let class_file = parse("Attributes$Shape.class");
+        let subclasses = class_file.permitted_subclasses().unwrap();
+        let names: Vec<_> = subclasses
+            .iter()
+            .map(|&index| class_name(&class_file, index))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "ru/khlevnov/Attributes$Circle",
+                "ru/khlevnov/Attributes$Square"
+            ]
+        );
+
+        assert!(parse("Attributes$Circle.class")
+            .permitted_subclasses()
+            .is_none());
+    }
+
+    #[test]
+    fn reads_record_components() {
+        let class_file = parse("Attributes$Circle.class");
+        let components = class_file.record().unwrap();
+        assert_eq!(components.len(), 1);
+        assert_eq!(class_file.utf8(components[0].name_index), Some("radius"));
+        assert_eq!(class_file.utf8(components[0].descriptor_index), Some("I"));
+        assert!(components[0].attributes.is_empty());
+
+        assert!(parse("Attributes.class").record().is_none());
+    }
+
+    #[test]
+    fn reads_method_parameters() {
+        let class_file = parse("Attributes.class");
+        let parameters = method(&class_file, "first").method_parameters().unwrap();
+        assert_eq!(parameters.len(), 1);
+        assert_eq!(class_file.utf8(parameters[0].name_index), Some("names"));
+        assert_eq!(parameters[0].access_flags, 0);
+
+        // compiled without -parameters
+        let class_file = parse("Main.class");
+        assert!(method(&class_file, "main").method_parameters().is_none());
+    }
+
+    #[test]
+    fn reads_line_number_and_local_variable_tables() {
+        let class_file = parse("Attributes.class");
+        let code = method(&class_file, "first").code().unwrap();
+
+        let lines: Vec<_> = code
+            .line_number_table()
+            .unwrap()
+            .iter()
+            .map(|line| (line.start_pc, line.line_number))
+            .collect();
+        assert_eq!(lines, [(0, 22), (9, 23)]);
+
+        let variables: Vec<_> = code
+            .local_variable_table()
+            .unwrap()
+            .iter()
+            .map(|variable| {
+                let name = class_file.utf8(variable.name_index).unwrap();
+                let descriptor = class_file.utf8(variable.descriptor_index).unwrap();
+                (
+                    variable.start_pc,
+                    variable.length,
+                    variable.index,
+                    name,
+                    descriptor,
+                )
+            })
+            .collect();
+        assert_eq!(
+            variables,
+            [
+                (0, 16, 0, "names", "Ljava/util/List;"),
+                (9, 7, 1, "local", "Lru/khlevnov/Attributes$1Local;"),
+            ]
+        );
+
+        let types = code.local_variable_type_table().unwrap();
+        assert_eq!(types.len(), 1);
+        assert_eq!(class_file.utf8(types[0].name_index), Some("names"));
+        assert_eq!(
+            class_file.utf8(types[0].descriptor_index),
+            Some("Ljava/util/List<Ljava/lang/String;>;")
+        );
+
+        // only generic variables have a signature
+        let class_file = parse("Main.class");
+        let code = method(&class_file, "main").code().unwrap();
+        assert!(code.local_variable_table().is_some());
+        assert!(code.local_variable_type_table().is_none());
+    }
+
+    #[test]
+    fn rejects_single_byte_mutations_without_panicking() {
+        let golden = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden/classes/ru/khlevnov");
+        let paths = [
+            golden.join("Loops.class"),
+            golden.join("Lambdas$Counter.class"),
+        ];
+        let classes = ["Main.class", "Foo.class", "Foo$FooInner.class"]
+            .into_iter()
+            .map(class_bytes)
+            .chain(paths.iter().map(|path| fs::read(path).unwrap()));
+        for bytes in classes {
+            for offset in 0..bytes.len() {
+                for mask in [0x01, 0x80, 0xff] {
+                    let mut mutated = bytes.clone();
+                    mutated[offset] ^= mask;
+                    if let Ok(class_file) = ClassFile::try_from(&mutated[..]) {
+                        let _ = Class::try_from(class_file);
+                    }
+                }
+            }
+        }
+    }
+
+    fn link_error(class_file: &ClassFile) -> (Vec<u8>, ClassFormatError) {
+        let bytes = class_file.to_bytes();
+        let class_file = ClassFile::try_from(&bytes[..]).unwrap();
+        (bytes, Class::try_from(class_file).unwrap_err())
+    }
+
+    fn read_u2(bytes: &[u8], offset: usize) -> u16 {
+        u16::from_be_bytes([bytes[offset], bytes[offset + 1]])
+    }
+
+    fn code(method: &mut MethodInfo) -> &mut CodeAttribute {
+        method
+            .attributes
+            .iter_mut()
+            .find_map(|info| match &mut info.attribute {
+                Attribute::Code(code) => Some(code),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn reports_the_offset_of_a_method_with_a_malformed_descriptor() {
+        let mut class_file = parse("Main.class");
+        let descriptor_index = utf8(&mut class_file, "(I");
+        class_file.methods[1].descriptor_index = descriptor_index;
+
+        let (bytes, error) = link_error(&class_file);
+        assert!(matches!(
+            error.kind,
+            ClassFormatErrorKind::Descriptor { .. }
+        ));
+        // after the access flags and the name index
+        assert_eq!(read_u2(&bytes, error.offset + 4), descriptor_index);
+    }
+
+    #[test]
+    fn reports_the_offset_of_a_method_without_code() {
+        let mut class_file = parse("Main.class");
+        class_file.methods[1].attributes.clear();
+
+        let (bytes, error) = link_error(&class_file);
+        assert!(matches!(
+            error.kind,
+            ClassFormatErrorKind::MissingAttribute { .. }
+        ));
+        let name_index = class_file.methods[1].name_index;
+        assert_eq!(read_u2(&bytes, error.offset + 2), name_index);
+    }
+
+    #[test]
+    fn reports_the_offset_of_an_exception_table_entry() {
+        let mut class_file = parse("Main.class");
+        let code = code(&mut class_file.methods[1]);
+        let length = code.code.len();
+        for handler_pc in [0, length + 7] {
+            code.exception_table.push(ExceptionTableEntry {
+                start_pc: 0,
+                end_pc: length,
+                handler_pc,
+                catch_type: 0,
+            });
+        }
+
+        let (bytes, error) = link_error(&class_file);
+        assert_eq!(
+            error.kind,
+            ClassFormatErrorKind::ExceptionTable { pc: length + 7 }
+        );
+        // after the start and the end
+        assert_eq!(read_u2(&bytes, error.offset + 4) as usize, length + 7);
+    }
+
+    #[test]
+    fn reports_the_offset_of_an_instruction_in_the_class_file() {
+        let mut class_file = parse("Main.class");
+        let code = code(&mut class_file.methods[1]);
+        code.code[0] = 0xff;
+
+        let (bytes, error) = link_error(&class_file);
+        assert_eq!(
+            error.kind,
+            ClassFormatErrorKind::Instruction { opcode: 0xff }
+        );
+        assert_eq!(bytes[error.offset], 0xff);
     }
 }
@@ -1,32 +1,132 @@
-use crate::class::Class;
-use crate::class_file::ClassFile;
-use std::fs::File;
-use std::io::Read;
+use crate::class::{Class, CLONEABLE, OBJECT, SERIALIZABLE};
+use crate::class_file::{ClassFile, ConstantClassInfo, ConstantInfo, ConstantUtf8Info, MAGIC};
+use crate::class_path::ClassPath;
+use crate::throwable::{Throwable, CLASS_FORMAT_ERROR, NO_CLASS_DEF_FOUND_ERROR};
+use crate::version::{self, SupportedVersions};
+
+const ACC_PUBLIC: u16 = 0x0001;
+const ACC_FINAL: u16 = 0x0010;
+const ACC_ABSTRACT: u16 = 0x0400;
 
 pub struct ClassLoader {
-    class_path: String,
+    boot_class_path: ClassPath,
+    class_path: ClassPath,
+    supported_versions: SupportedVersions,
 }
 
 impl ClassLoader {
-    pub(crate) fn find_class(&self, name: &str) -> Class {
-        let path = format!("{}/{}.class", &self.class_path, name.replace(".", "/"));
+    /// Returns the class along with the source it was loaded from.
+    pub(crate) fn find_class(&self, name: &str) -> Result<(Class, String), Throwable> {
+        let name = name.replace('.', "/");
+        let boot_class = self.boot_class_path.find(&name);
+        let bootstrap = boot_class.is_some();
+        let (bytes, source) = boot_class
+            .or_else(|| self.class_path.find(&name))
+            .ok_or_else(|| Throwable::new(NO_CLASS_DEF_FOUND_ERROR, &name))?;
+        // the class is there, but its bytes cannot be read
+        let bytes = bytes.map_err(|error| {
+            let message = format!("{} in class file {} from {}", error, name, source);
+            Throwable::new(CLASS_FORMAT_ERROR, &message)
+        })?;
+
+        let mut class = self.define_class(&name, bytes.as_slice())?;
+        class.bootstrap = bootstrap;
+        Ok((class, source))
+    }
 
-        let mut buf = vec![];
-        let _ = File::open(path).unwrap().read_to_end(&mut buf);
+    /// Creates the class of the arrays with the name, such as `[I` or
+    /// `[Ljava/lang/String;`, which has no class file (JVMS 5.3.3). It
+    /// belongs to the loader of its component class.
+    pub(crate) fn array_class(&self, name: &str, bootstrap: bool) -> Class {
+        let utf8 = |value: &str| {
+            ConstantInfo::Utf8(ConstantUtf8Info {
+                value: value.to_string(),
+                bytes: None,
+            })
+        };
+        let class = |name_index| ConstantInfo::Class(ConstantClassInfo { name_index });
 
-        self.define_class(buf.as_slice())
+        let class_file = ClassFile {
+            magic: MAGIC,
+            minor_version: 0,
+            major_version: 0,
+            constant_pool: vec![
+                utf8(name),
+                class(1),
+                utf8(OBJECT),
+                class(3),
+                utf8(CLONEABLE),
+                class(5),
+                utf8(SERIALIZABLE),
+                class(7),
+            ],
+            access_flags: ACC_PUBLIC | ACC_FINAL | ACC_ABSTRACT,
+            this_class: 2,
+            super_class: 4,
+            interfaces: Box::new([6, 8]),
+            fields: vec![],
+            methods: vec![],
+            attributes: vec![],
+        };
+        let mut class = Class::try_from(class_file).expect("array classes have no methods");
+        class.bootstrap = bootstrap;
+        class
     }
 
-    fn define_class(&self, bytes: &[u8]) -> Class {
-        let class_file = ClassFile::from(bytes);
-        Class::from(class_file)
+    fn define_class(&self, name: &str, bytes: &[u8]) -> Result<Class, Throwable> {
+        let class_format_error = |error| {
+            let message = format!("{} in class file {}", error, name);
+            Throwable::new(CLASS_FORMAT_ERROR, &message)
+        };
+
+        let class_file = ClassFile::try_from(bytes).map_err(class_format_error)?;
+        self.supported_versions.check(name, class_file.version())?;
+        version::check_features(name, &class_file)?;
+
+        Class::try_from(class_file).map_err(class_format_error)
     }
 }
 
 impl ClassLoader {
     pub fn new(class_path: &str) -> ClassLoader {
         ClassLoader {
-            class_path: class_path.to_string(),
+            boot_class_path: ClassPath::runtime(),
+            class_path: ClassPath::new(class_path),
+            supported_versions: SupportedVersions::default(),
         }
     }
+
+    pub fn with_supported_versions(mut self, supported_versions: SupportedVersions) -> ClassLoader {
+        self.supported_versions = supported_versions;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zip::tests::{archive, stored};
+    use std::{env, fs, process};
+
+    #[test]
+    fn reports_unreadable_class_files() {
+        let path = env::temp_dir().join(format!("smerdvm-corrupted-{}.jar", process::id()));
+        let mut entry = stored("Main.class", b"original");
+        entry.data = b"modified";
+        fs::write(&path, archive(&[entry])).unwrap();
+        let path = fs::canonicalize(path).unwrap();
+        let loader = ClassLoader::new(&path.display().to_string());
+
+        let throwable = loader.find_class("Main").unwrap_err();
+        assert_eq!(throwable.class_name, CLASS_FORMAT_ERROR);
+        let message = format!(
+            "invalid entry size or CRC in class file Main from file:{}",
+            path.display()
+        );
+        assert_eq!(throwable.message, Some(message));
+
+        let throwable = loader.find_class("Missing").unwrap_err();
+        assert_eq!(throwable.class_name, NO_CLASS_DEF_FOUND_ERROR);
+        fs::remove_file(path).unwrap();
+    }
 }
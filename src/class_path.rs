@@ -0,0 +1,331 @@
+use crate::zip::ZipArchive;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::Result;
+use std::path::{Path, PathBuf};
+
+const MANIFEST: &str = "META-INF/MANIFEST.MF";
+const RUNTIME: &[u8] = include_bytes!("../runtime/rt.jar");
+
+#[derive(Debug)]
+enum ClassPathEntry {
+    Directory(PathBuf),
+    Archive(PathBuf, ZipArchive),
+    Runtime(ZipArchive),
+}
+
+impl fmt::Display for ClassPathEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClassPathEntry::Directory(path) => write!(f, "file:{}/", path.display()),
+            ClassPathEntry::Archive(path, _) => write!(f, "file:{}", path.display()),
+            ClassPathEntry::Runtime(_) => write!(f, "jrt:/java.base"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ClassPath {
+    entries: Vec<ClassPathEntry>,
+    visited: HashSet<PathBuf>,
+}
+
+impl ClassPath {
+    pub fn new(class_path: &str) -> ClassPath {
+        let mut entries = ClassPath::default();
+        for path in env::split_paths(class_path) {
+            if path.as_os_str().is_empty() {
+                entries.add(PathBuf::from("."));
+            } else if path.file_name().is_some_and(|name| name == "*") {
+                entries.add_wildcard(path.parent().unwrap_or(Path::new(".")));
+            } else {
+                entries.add(path);
+            }
+        }
+        entries
+    }
+
+    /// The bootstrap class path: the runtime classes embedded into the binary.
+    pub fn runtime() -> ClassPath {
+        let archive = ZipArchive::new(RUNTIME.to_vec()).expect("embedded runtime is corrupted");
+        ClassPath {
+            entries: vec![ClassPathEntry::Runtime(archive)],
+            ..ClassPath::default()
+        }
+    }
+
+    /// Returns the class file bytes along with the source they were read from.
+    pub(crate) fn find(&self, name: &str) -> Option<(Result<Vec<u8>>, String)> {
+        let file_name = format!("{}.class", name);
+
+        self.entries.iter().find_map(|entry| {
+            let bytes = match entry {
+                ClassPathEntry::Directory(directory) => {
+                    let path = directory.join(&file_name);
+                    path.is_file().then(|| fs::read(path))
+                }
+                ClassPathEntry::Archive(_, archive) | ClassPathEntry::Runtime(archive) => {
+                    archive.read(&file_name)
+                }
+            };
+            bytes.map(|bytes| (bytes, entry.to_string()))
+        })
+    }
+
+    fn add(&mut self, path: PathBuf) {
+        let path = fs::canonicalize(&path).unwrap_or(path);
+        if !self.visited.insert(path.clone()) {
+            return;
+        }
+
+        if path.is_dir() {
+            self.entries.push(ClassPathEntry::Directory(path));
+            return;
+        }
+
+        let archive = match ZipArchive::open(&path) {
+            Ok(archive) => archive,
+            Err(_) => return,
+        };
+        let class_path = Manifest::read(&archive)
+            .map(|manifest| {
+                let class_path = manifest.class_path().into_iter();
+                class_path.map(PathBuf::from).collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let base = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+        self.entries.push(ClassPathEntry::Archive(path, archive));
+
+        for entry in class_path {
+            self.add(base.join(entry));
+        }
+    }
+
+    fn add_wildcard(&mut self, directory: &Path) {
+        let mut archives = fs::read_dir(directory)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .extension()
+                        .is_some_and(|extension| extension.eq_ignore_ascii_case("jar"))
+            })
+            .collect::<Vec<_>>();
+        archives.sort();
+
+        for archive in archives {
+            self.add(archive);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Manifest {
+    main_attributes: HashMap<String, String>,
+}
+
+impl Manifest {
+    pub fn read(archive: &ZipArchive) -> Option<Manifest> {
+        let bytes = archive.read(MANIFEST)?.ok()?;
+        Some(Manifest::parse(&bytes))
+    }
+
+    pub fn parse(bytes: &[u8]) -> Manifest {
+        // continuation lines are joined before decoding, as the line wrap
+        // may split a multibyte character
+        let mut lines: Vec<Vec<u8>> = vec![];
+        for line in bytes.split(|&byte| byte == b'\n') {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            if line.is_empty() {
+                break;
+            }
+
+            match (line.strip_prefix(b" "), lines.last_mut()) {
+                (Some(continuation), Some(last)) => last.extend_from_slice(continuation),
+                _ => lines.push(line.to_vec()),
+            }
+        }
+
+        let main_attributes = lines
+            .iter()
+            .map(|line| String::from_utf8_lossy(line))
+            .filter_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                Some((name.trim().to_ascii_lowercase(), value.trim().to_string()))
+            })
+            .collect();
+
+        Manifest { main_attributes }
+    }
+
+    pub fn main_class(&self) -> Option<&str> {
+        self.attribute("Main-Class")
+    }
+
+    pub fn class_path(&self) -> Vec<&str> {
+        self.attribute("Class-Path")
+            .map(|class_path| class_path.split_whitespace().collect())
+            .unwrap_or_default()
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.main_attributes
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+            .filter(|value| !value.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zip::tests::{archive, stored};
+    use std::process;
+
+    /// A fresh directory under the system temporary directory.
+    fn temp_dir(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("smerdvm-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        fs::canonicalize(path).unwrap()
+    }
+
+    fn jar(path: &Path, manifest: Option<&str>, classes: &[&str]) {
+        let names = classes
+            .iter()
+            .map(|class| format!("{}.class", class))
+            .collect::<Vec<_>>();
+        let mut entries = names
+            .iter()
+            .map(|name| stored(name, name.as_bytes()))
+            .collect::<Vec<_>>();
+        if let Some(manifest) = manifest {
+            entries.push(stored(MANIFEST, manifest.as_bytes()));
+        }
+        fs::write(path, archive(&entries)).unwrap();
+    }
+
+    fn source(class_path: &ClassPath, name: &str) -> Option<String> {
+        let (bytes, source) = class_path.find(name)?;
+        assert_eq!(bytes.unwrap(), format!("{}.class", name).as_bytes());
+        Some(source)
+    }
+
+    #[test]
+    fn parses_manifest_main_attributes() {
+        let manifest = Manifest::parse(
+            b"Manifest-Version: 1.0\r\n\
+             main-class: ru.khlevnov.Main\r\n\
+             Class-Path: lib/a.jar\r\n  lib/b.jar\r\n  lib/c.j\r\n ar\r\n\
+             \r\n\
+             Name: ru/khlevnov/\r\n\
+             Class-Path: ignored.jar\r\n",
+        );
+        assert_eq!(manifest.main_class(), Some("ru.khlevnov.Main"));
+        assert_eq!(
+            manifest.class_path(),
+            ["lib/a.jar", "lib/b.jar", "lib/c.jar"]
+        );
+    }
+
+    #[test]
+    fn joins_continuation_lines_before_decoding() {
+        // "Grüße" wrapped in the middle of the two bytes of the "ü"
+        let manifest = Manifest::parse(b"Main-Class: ru.khlevnov.Gr\xc3\r\n \xbc\xc3\x9fe\r\n");
+        assert_eq!(manifest.main_class(), Some("ru.khlevnov.Gr\u{fc}\u{df}e"));
+
+        let manifest = Manifest::parse(b"Main-Class: Bad\xff\n");
+        assert_eq!(manifest.main_class(), Some("Bad\u{fffd}"));
+    }
+
+    #[test]
+    fn ignores_empty_manifest_attributes() {
+        let manifest = Manifest::parse(b"Main-Class:\nClass-Path:  \n");
+        assert_eq!(manifest.main_class(), None);
+        assert!(manifest.class_path().is_empty());
+        assert_eq!(Manifest::parse(b"").main_class(), None);
+    }
+
+    #[test]
+    fn expands_wildcard_into_sorted_jars() {
+        let dir = temp_dir("wildcard");
+        jar(&dir.join("b.JAR"), None, &["B", "Common"]);
+        jar(&dir.join("a.jar"), None, &["A", "Common"]);
+        fs::write(dir.join("C.class"), "C.class").unwrap();
+        fs::create_dir(dir.join("nested.jar")).unwrap();
+
+        let class_path = ClassPath::new(&format!("{}/*", dir.display()));
+        let a = format!("file:{}", dir.join("a.jar").display());
+        let b = format!("file:{}", dir.join("b.JAR").display());
+        assert_eq!(source(&class_path, "A"), Some(a.clone()));
+        assert_eq!(source(&class_path, "B"), Some(b));
+        assert_eq!(source(&class_path, "Common"), Some(a));
+        // the directory itself and the directories in it are not searched
+        assert_eq!(source(&class_path, "C"), None);
+        assert_eq!(class_path.entries.len(), 2);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn follows_manifest_class_path() {
+        let dir = temp_dir("manifest");
+        fs::create_dir(dir.join("lib")).unwrap();
+        jar(
+            &dir.join("main.jar"),
+            Some("Class-Path: lib/a.jar\n"),
+            &["Main"],
+        );
+        // relative to the jar that names it, with a cycle back to the first one
+        jar(
+            &dir.join("lib/a.jar"),
+            Some("Class-Path: b.jar ../main.jar missing.jar\n"),
+            &["A"],
+        );
+        jar(&dir.join("lib/b.jar"), None, &["B"]);
+
+        let class_path = ClassPath::new(&dir.join("main.jar").display().to_string());
+        let b = format!("file:{}", dir.join("lib/b.jar").display());
+        assert!(source(&class_path, "Main").is_some());
+        assert!(source(&class_path, "A").is_some());
+        assert_eq!(source(&class_path, "B"), Some(b));
+        assert_eq!(class_path.entries.len(), 3);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn skips_missing_and_corrupt_archives() {
+        let dir = temp_dir("corrupt");
+        fs::write(dir.join("corrupt.jar"), b"PK\x03\x04 not really").unwrap();
+        jar(&dir.join("valid.jar"), None, &["Valid"]);
+
+        let class_path = ClassPath::new(&format!(
+            "{0}/missing.jar:{0}/corrupt.jar:{0}/*",
+            dir.display()
+        ));
+        assert!(source(&class_path, "Valid").is_some());
+        assert_eq!(class_path.entries.len(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn searches_directories() {
+        let dir = temp_dir("directory");
+        fs::create_dir_all(dir.join("ru/khlevnov")).unwrap();
+        fs::write(dir.join("ru/khlevnov/Main.class"), "ru/khlevnov/Main.class").unwrap();
+
+        let class_path = ClassPath::new(&dir.display().to_string());
+        let expected = format!("file:{}/", dir.display());
+        assert_eq!(source(&class_path, "ru/khlevnov/Main"), Some(expected));
+        assert_eq!(source(&class_path, "ru/khlevnov/Missing"), None);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
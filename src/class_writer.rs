@@ -0,0 +1,662 @@
+use crate::attribute::{
+    Annotation, Attribute, AttributeInfo, BootstrapMethod, CodeAttribute, ElementValue,
+    ElementValuePair, EnclosingMethod, ExceptionTableEntry, InnerClass, LineNumber, LocalVarTarget,
+    LocalVariable, MethodParameter, RecordComponent, StackMapFrame, TargetInfo, TypeAnnotation,
+    TypePathEntry, VerificationTypeInfo,
+};
+use crate::class_file::{ClassFile, ConstantInfo, FieldInfo, MethodInfo};
+use crate::mutf8;
+
+pub(crate) trait ToWriter {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter;
+}
+
+pub trait ClassWriter {
+    fn write_bytes(&mut self, bytes: &[u8]);
+
+    fn write_u1(&mut self, value: u8) {
+        self.write_bytes(&value.to_be_bytes())
+    }
+
+    fn write_u2(&mut self, value: u16) {
+        self.write_bytes(&value.to_be_bytes())
+    }
+
+    fn write_u4(&mut self, value: u32) {
+        self.write_bytes(&value.to_be_bytes())
+    }
+
+    fn emit<T>(&mut self, value: &T)
+    where
+        T: ToWriter,
+        Self: Sized,
+    {
+        value.to_writer(self)
+    }
+
+    fn emit_table<T>(&mut self, values: &[T])
+    where
+        T: ToWriter,
+        Self: Sized,
+    {
+        self.write_u2(values.len() as u16);
+        values.iter().for_each(|value| value.to_writer(self))
+    }
+}
+
+impl ClassWriter for Vec<u8> {
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        self.extend_from_slice(bytes)
+    }
+}
+
+impl ClassFile {
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.emit(self);
+        bytes
+    }
+}
+
+impl ToWriter for ClassFile {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        writer.write_u4(self.magic);
+        writer.write_u2(self.minor_version);
+        writer.write_u2(self.major_version);
+
+        writer.write_u2(self.constant_pool.len() as u16 + 1);
+        self.constant_pool.iter().for_each(|info| writer.emit(info));
+
+        writer.write_u2(self.access_flags);
+        writer.write_u2(self.this_class);
+        writer.write_u2(self.super_class);
+        writer.emit_table(&self.interfaces);
+        writer.emit_table(&self.fields);
+        writer.emit_table(&self.methods);
+        writer.emit_table(&self.attributes);
+    }
+}
+
+impl ToWriter for ConstantInfo {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        if let ConstantInfo::Unusable = self {
+            return;
+        }
+
+        writer.write_u1(self.tag());
+        match self {
+            ConstantInfo::Utf8(info) => {
+                let bytes = match &info.bytes {
+                    Some(bytes) if mutf8::decode(bytes).as_ref() == Ok(&info.value) => {
+                        bytes.to_vec()
+                    }
+                    _ => mutf8::encode(&info.value),
+                };
+                writer.write_u2(bytes.len() as u16);
+                writer.write_bytes(&bytes);
+            }
+            ConstantInfo::Integer(info) => {
+                writer.write_bytes(&info.value.to_be_bytes());
+            }
+            ConstantInfo::Float(info) => {
+                writer.write_bytes(&info.value.to_be_bytes());
+            }
+            ConstantInfo::Long(info) => {
+                writer.write_bytes(&info.value.to_be_bytes());
+            }
+            ConstantInfo::Double(info) => {
+                writer.write_bytes(&info.value.to_be_bytes());
+            }
+            ConstantInfo::Class(info) => {
+                writer.write_u2(info.name_index);
+            }
+            ConstantInfo::String(info) => {
+                writer.write_u2(info.string_index);
+            }
+            ConstantInfo::FieldRef(info) => {
+                writer.write_u2(info.class_index);
+                writer.write_u2(info.name_and_type_index);
+            }
+            ConstantInfo::MethodRef(info) => {
+                writer.write_u2(info.class_index);
+                writer.write_u2(info.name_and_type_index);
+            }
+            ConstantInfo::InterfaceMethodRef(info) => {
+                writer.write_u2(info.class_index);
+                writer.write_u2(info.name_and_type_index);
+            }
+            ConstantInfo::NameAndType(info) => {
+                writer.write_u2(info.name_index);
+                writer.write_u2(info.descriptor_index);
+            }
+            ConstantInfo::MethodHandle(info) => {
+                writer.write_u1(info.reference_kind as u8);
+                writer.write_u2(info.reference_index);
+            }
+            ConstantInfo::MethodType(info) => {
+                writer.write_u2(info.descriptor_index);
+            }
+            ConstantInfo::Dynamic(info) => {
+                writer.write_u2(info.bootstrap_method_attr_index);
+                writer.write_u2(info.name_and_type_index);
+            }
+            ConstantInfo::InvokeDynamic(info) => {
+                writer.write_u2(info.bootstrap_method_attr_index);
+                writer.write_u2(info.name_and_type_index);
+            }
+            ConstantInfo::Module(info) => {
+                writer.write_u2(info.name_index);
+            }
+            ConstantInfo::Package(info) => {
+                writer.write_u2(info.name_index);
+            }
+            ConstantInfo::Unusable => (),
+        }
+    }
+}
+
+impl ToWriter for FieldInfo {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        writer.write_u2(self.access_flags);
+        writer.write_u2(self.name_index);
+        writer.write_u2(self.descriptor_index);
+        writer.emit_table(&self.attributes);
+    }
+}
+
+impl ToWriter for MethodInfo {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        writer.write_u2(self.access_flags);
+        writer.write_u2(self.name_index);
+        writer.write_u2(self.descriptor_index);
+        writer.emit_table(&self.attributes);
+    }
+}
+
+impl ToWriter for AttributeInfo {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        let mut info = vec![];
+        info.emit(&self.attribute);
+
+        writer.write_u2(self.attribute_name_index);
+        writer.write_u4(info.len() as u32);
+        writer.write_bytes(&info);
+    }
+}
+
+impl ToWriter for Attribute {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        match self {
+            Attribute::ConstantValue(index)
+            | Attribute::Signature(index)
+            | Attribute::SourceFile(index)
+            | Attribute::NestHost(index) => writer.write_u2(*index),
+            Attribute::Code(code) => writer.emit(code),
+            Attribute::StackMapTable(frames) => writer.emit_table(frames),
+            Attribute::Exceptions(classes)
+            | Attribute::NestMembers(classes)
+            | Attribute::PermittedSubclasses(classes) => writer.emit_table(classes),
+            Attribute::InnerClasses(classes) => writer.emit_table(classes),
+            Attribute::EnclosingMethod(method) => writer.emit(method),
+            Attribute::Synthetic | Attribute::Deprecated => (),
+            Attribute::SourceDebugExtension(bytes) | Attribute::Unknown(bytes) => {
+                writer.write_bytes(bytes)
+            }
+            Attribute::LineNumberTable(table) => writer.emit_table(table),
+            Attribute::LocalVariableTable(table) | Attribute::LocalVariableTypeTable(table) => {
+                writer.emit_table(table)
+            }
+            Attribute::RuntimeVisibleAnnotations(annotations)
+            | Attribute::RuntimeInvisibleAnnotations(annotations) => writer.emit_table(annotations),
+            Attribute::RuntimeVisibleParameterAnnotations(parameters)
+            | Attribute::RuntimeInvisibleParameterAnnotations(parameters) => {
+                writer.write_u1(parameters.len() as u8);
+                parameters
+                    .iter()
+                    .for_each(|annotations| writer.emit_table(annotations));
+            }
+            Attribute::RuntimeVisibleTypeAnnotations(annotations)
+            | Attribute::RuntimeInvisibleTypeAnnotations(annotations) => {
+                writer.emit_table(annotations)
+            }
+            Attribute::AnnotationDefault(value) => writer.emit(value),
+            Attribute::BootstrapMethods(methods) => writer.emit_table(methods),
+            Attribute::MethodParameters(parameters) => {
+                writer.write_u1(parameters.len() as u8);
+                parameters
+                    .iter()
+                    .for_each(|parameter| writer.emit(parameter));
+            }
+            Attribute::Record(components) => writer.emit_table(components),
+        }
+    }
+}
+
+impl ToWriter for CodeAttribute {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        writer.write_u2(self.max_stack as u16);
+        writer.write_u2(self.max_locals as u16);
+        writer.write_u4(self.code.len() as u32);
+        writer.write_bytes(&self.code);
+        writer.emit_table(&self.exception_table);
+        writer.emit_table(&self.attributes);
+    }
+}
+
+impl ToWriter for u16 {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        writer.write_u2(*self)
+    }
+}
+
+impl ToWriter for ExceptionTableEntry {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        writer.write_u2(self.start_pc as u16);
+        writer.write_u2(self.end_pc as u16);
+        writer.write_u2(self.handler_pc as u16);
+        writer.write_u2(self.catch_type);
+    }
+}
+
+impl ToWriter for StackMapFrame {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        match self {
+            StackMapFrame::Same { frame_type } => writer.write_u1(*frame_type),
+            StackMapFrame::SameLocals1StackItem { frame_type, stack } => {
+                writer.write_u1(*frame_type);
+                writer.emit(stack);
+            }
+            StackMapFrame::SameLocals1StackItemExtended {
+                offset_delta,
+                stack,
+            } => {
+                writer.write_u1(247);
+                writer.write_u2(*offset_delta);
+                writer.emit(stack);
+            }
+            StackMapFrame::Chop {
+                frame_type,
+                offset_delta,
+            } => {
+                writer.write_u1(*frame_type);
+                writer.write_u2(*offset_delta);
+            }
+            StackMapFrame::SameExtended { offset_delta } => {
+                writer.write_u1(251);
+                writer.write_u2(*offset_delta);
+            }
+            StackMapFrame::Append {
+                frame_type,
+                offset_delta,
+                locals,
+            } => {
+                writer.write_u1(*frame_type);
+                writer.write_u2(*offset_delta);
+                locals.iter().for_each(|local| writer.emit(local));
+            }
+            StackMapFrame::Full {
+                offset_delta,
+                locals,
+                stack,
+            } => {
+                writer.write_u1(255);
+                writer.write_u2(*offset_delta);
+                writer.emit_table(locals);
+                writer.emit_table(stack);
+            }
+        }
+    }
+}
+
+impl ToWriter for VerificationTypeInfo {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        match self {
+            VerificationTypeInfo::Top => writer.write_u1(0),
+            VerificationTypeInfo::Integer => writer.write_u1(1),
+            VerificationTypeInfo::Float => writer.write_u1(2),
+            VerificationTypeInfo::Double => writer.write_u1(3),
+            VerificationTypeInfo::Long => writer.write_u1(4),
+            VerificationTypeInfo::Null => writer.write_u1(5),
+            VerificationTypeInfo::UninitializedThis => writer.write_u1(6),
+            VerificationTypeInfo::Object { cpool_index } => {
+                writer.write_u1(7);
+                writer.write_u2(*cpool_index);
+            }
+            VerificationTypeInfo::Uninitialized { offset } => {
+                writer.write_u1(8);
+                writer.write_u2(*offset);
+            }
+        }
+    }
+}
+
+impl ToWriter for InnerClass {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        writer.write_u2(self.inner_class_info_index);
+        writer.write_u2(self.outer_class_info_index);
+        writer.write_u2(self.inner_name_index);
+        writer.write_u2(self.inner_class_access_flags);
+    }
+}
+
+impl ToWriter for EnclosingMethod {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        writer.write_u2(self.class_index);
+        writer.write_u2(self.method_index);
+    }
+}
+
+impl ToWriter for LineNumber {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        writer.write_u2(self.start_pc);
+        writer.write_u2(self.line_number);
+    }
+}
+
+impl ToWriter for LocalVariable {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        writer.write_u2(self.start_pc);
+        writer.write_u2(self.length);
+        writer.write_u2(self.name_index);
+        writer.write_u2(self.descriptor_index);
+        writer.write_u2(self.index);
+    }
+}
+
+impl ToWriter for Annotation {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        writer.write_u2(self.type_index);
+        writer.emit_table(&self.element_value_pairs);
+    }
+}
+
+impl ToWriter for ElementValuePair {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        writer.write_u2(self.element_name_index);
+        writer.emit(&self.value);
+    }
+}
+
+impl ToWriter for ElementValue {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        match self {
+            ElementValue::Const {
+                tag,
+                const_value_index,
+            } => {
+                writer.write_u1(*tag);
+                writer.write_u2(*const_value_index);
+            }
+            ElementValue::Enum {
+                type_name_index,
+                const_name_index,
+            } => {
+                writer.write_u1(b'e');
+                writer.write_u2(*type_name_index);
+                writer.write_u2(*const_name_index);
+            }
+            ElementValue::Class { class_info_index } => {
+                writer.write_u1(b'c');
+                writer.write_u2(*class_info_index);
+            }
+            ElementValue::Annotation(annotation) => {
+                writer.write_u1(b'@');
+                writer.emit(annotation);
+            }
+            ElementValue::Array(values) => {
+                writer.write_u1(b'[');
+                writer.emit_table(values);
+            }
+        }
+    }
+}
+
+impl ToWriter for TypeAnnotation {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        writer.write_u1(self.target_type);
+        match &self.target_info {
+            TargetInfo::TypeParameter {
+                type_parameter_index,
+            } => writer.write_u1(*type_parameter_index),
+            TargetInfo::Supertype { supertype_index } => writer.write_u2(*supertype_index),
+            TargetInfo::TypeParameterBound {
+                type_parameter_index,
+                bound_index,
+            } => {
+                writer.write_u1(*type_parameter_index);
+                writer.write_u1(*bound_index);
+            }
+            TargetInfo::Empty => (),
+            TargetInfo::FormalParameter {
+                formal_parameter_index,
+            } => writer.write_u1(*formal_parameter_index),
+            TargetInfo::Throws { throws_type_index } => writer.write_u2(*throws_type_index),
+            TargetInfo::LocalVar(table) => writer.emit_table(table),
+            TargetInfo::Catch {
+                exception_table_index,
+            } => writer.write_u2(*exception_table_index),
+            TargetInfo::Offset { offset } => writer.write_u2(*offset),
+            TargetInfo::TypeArgument {
+                offset,
+                type_argument_index,
+            } => {
+                writer.write_u2(*offset);
+                writer.write_u1(*type_argument_index);
+            }
+        }
+
+        writer.write_u1(self.target_path.len() as u8);
+        self.target_path.iter().for_each(|entry| writer.emit(entry));
+        writer.emit(&self.annotation);
+    }
+}
+
+impl ToWriter for LocalVarTarget {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        writer.write_u2(self.start_pc);
+        writer.write_u2(self.length);
+        writer.write_u2(self.index);
+    }
+}
+
+impl ToWriter for TypePathEntry {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        writer.write_u1(self.type_path_kind);
+        writer.write_u1(self.type_argument_index);
+    }
+}
+
+impl ToWriter for BootstrapMethod {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        writer.write_u2(self.bootstrap_method_ref);
+        writer.emit_table(&self.bootstrap_arguments);
+    }
+}
+
+impl ToWriter for MethodParameter {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        writer.write_u2(self.name_index);
+        writer.write_u2(self.access_flags);
+    }
+}
+
+impl ToWriter for RecordComponent {
+    fn to_writer<W>(&self, writer: &mut W)
+    where
+        W: ClassWriter,
+    {
+        writer.write_u2(self.name_index);
+        writer.write_u2(self.descriptor_index);
+        writer.emit_table(&self.attributes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::class_file::{ClassFile, ConstantInfo};
+    use crate::zip::ZipArchive;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn class_files(dir: &Path) -> Vec<PathBuf> {
+        let mut paths = vec![];
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                paths.extend(class_files(&path));
+            } else if path.extension().is_some_and(|ext| ext == "class") {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+
+    fn playground_classes() -> Vec<PathBuf> {
+        class_files(&Path::new(env!("CARGO_MANIFEST_DIR")).join("playground/classes"))
+    }
+
+    fn assert_byte_identical(name: &str, bytes: &[u8]) {
+        let class_file = ClassFile::try_from(bytes).unwrap_or_else(|e| panic!("{}: {}", name, e));
+        assert!(class_file.to_bytes() == bytes, "{}", name);
+    }
+
+    #[test]
+    fn writes_unmodified_classes_byte_identical() {
+        let paths = playground_classes();
+        assert!(!paths.is_empty());
+
+        for path in paths {
+            assert_byte_identical(&path.display().to_string(), &fs::read(&path).unwrap());
+        }
+    }
+
+    /// The golden programs cover long and double constants, invokedynamic
+    /// with its bootstrap methods, stack map frames and switch tables.
+    #[test]
+    fn writes_golden_classes_byte_identical() {
+        let paths =
+            class_files(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden/classes"));
+        assert!(!paths.is_empty());
+
+        for path in paths {
+            assert_byte_identical(&path.display().to_string(), &fs::read(&path).unwrap());
+        }
+    }
+
+    #[test]
+    fn writes_runtime_classes_byte_identical() {
+        let archive =
+            ZipArchive::open(Path::new(env!("CARGO_MANIFEST_DIR")).join("runtime/rt.jar")).unwrap();
+        let mut names = archive
+            .names()
+            .filter(|name| name.ends_with(".class"))
+            .peekable();
+        assert!(names.peek().is_some());
+
+        for name in names {
+            assert_byte_identical(name, &archive.read(name).unwrap().unwrap());
+        }
+    }
+
+    #[test]
+    fn writes_patched_constants() {
+        for path in playground_classes() {
+            let bytes = fs::read(&path).unwrap();
+            let mut class_file = ClassFile::try_from(&bytes[..]).unwrap();
+            for info in class_file.constant_pool.iter_mut() {
+                match info {
+                    ConstantInfo::Utf8(info) if info.value == "ru/khlevnov/PrintStream" => {
+                        info.value = "ru/khlevnov/Стрим\0".to_string()
+                    }
+                    ConstantInfo::Integer(info) => info.value = -info.value,
+                    _ => (),
+                }
+            }
+
+            let patched = ClassFile::try_from(&class_file.to_bytes()[..]).unwrap();
+            assert_eq!(patched.to_bytes(), class_file.to_bytes());
+            for (expected, actual) in class_file.constant_pool.iter().zip(&patched.constant_pool) {
+                match (expected, actual) {
+                    (ConstantInfo::Utf8(expected), ConstantInfo::Utf8(actual)) => {
+                        assert_eq!(expected.value, actual.value)
+                    }
+                    (ConstantInfo::Integer(expected), ConstantInfo::Integer(actual)) => {
+                        assert_eq!(expected.value, actual.value)
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use crate::class_file::{
-    ConstantClassInfo, ConstantDoubleInfo, ConstantFieldRefInfo, ConstantFloatInfo, ConstantInfo,
-    ConstantIntegerInfo, ConstantLongInfo, ConstantMethodRefInfo, ConstantNameAndTypeInfo,
-    ConstantStringInfo,
+    ConstantClassInfo, ConstantDoubleInfo, ConstantDynamicInfo, ConstantFieldRefInfo,
+    ConstantFloatInfo, ConstantInfo, ConstantIntegerInfo, ConstantInterfaceMethodRefInfo,
+    ConstantLongInfo, ConstantMethodHandleInfo, ConstantMethodRefInfo, ConstantMethodTypeInfo,
+    ConstantModuleInfo, ConstantNameAndTypeInfo, ConstantPackageInfo, ConstantStringInfo,
+    ReferenceKind,
 };
 use crate::r#type::Type;
 
@@ -10,6 +12,7 @@ pub enum Constant {
     Class(ConstantClassInfo),
     FieldRef(ConstantFieldRefInfo),
     MethodRef(ConstantMethodRefInfo),
+    InterfaceMethodRef(ConstantInterfaceMethodRefInfo),
     String(ConstantStringInfo),
     Integer(ConstantIntegerInfo),
     Float(ConstantFloatInfo),
@@ -17,6 +20,14 @@ pub enum Constant {
     Double(ConstantDoubleInfo),
     NameAndType(ConstantNameAndTypeInfo),
     Utf8(String),
+    MethodHandle(ConstantMethodHandleInfo),
+    MethodType(ConstantMethodTypeInfo),
+    Dynamic(ConstantDynamicInfo),
+    InvokeDynamic(ConstantDynamicInfo),
+    /// The name of the module, resolved when the pool is built.
+    Module(String),
+    /// The name of the package, resolved when the pool is built.
+    Package(String),
     Unusable,
 }
 
@@ -26,6 +37,7 @@ impl From<ConstantInfo> for Constant {
             ConstantInfo::Class(info) => Constant::Class(info),
             ConstantInfo::FieldRef(info) => Constant::FieldRef(info),
             ConstantInfo::MethodRef(info) => Constant::MethodRef(info),
+            ConstantInfo::InterfaceMethodRef(info) => Constant::InterfaceMethodRef(info),
             ConstantInfo::String(info) => Constant::String(info),
             ConstantInfo::Integer(info) => Constant::Integer(info),
             ConstantInfo::Float(info) => Constant::Float(info),
@@ -33,27 +45,27 @@ impl From<ConstantInfo> for Constant {
             ConstantInfo::Double(info) => Constant::Double(info),
             ConstantInfo::NameAndType(info) => Constant::NameAndType(info),
             ConstantInfo::Utf8(info) => Constant::Utf8(info.value),
+            ConstantInfo::MethodHandle(info) => Constant::MethodHandle(info),
+            ConstantInfo::MethodType(info) => Constant::MethodType(info),
+            ConstantInfo::Dynamic(info) => Constant::Dynamic(info),
+            ConstantInfo::InvokeDynamic(info) => Constant::InvokeDynamic(info),
+            ConstantInfo::Module(_) | ConstantInfo::Package(_) => {
+                unreachable!("resolved by the constant pool")
+            }
             ConstantInfo::Unusable => Constant::Unusable,
         }
     }
 }
 
+/// The run-time constant pool. Loading a class checks that the entries refer
+/// to each other with the right tags, so the accessors only need the callers
+/// to check the index they start from.
 #[derive(Debug)]
 pub struct ConstantPool {
     constant_pool: Vec<Constant>,
 }
 
 impl ConstantPool {
-    pub(crate) fn classes(&self) -> Vec<String> {
-        self.constant_pool
-            .iter()
-            .filter_map(|constant| match constant {
-                Constant::Class(class_info) => Some(self.utf8(class_info.name_index)),
-                _ => None,
-            })
-            .collect::<Vec<String>>()
-    }
-
     pub(crate) fn class(&self, index: u16) -> String {
         match &self.constant_pool[index as usize - 1] {
             Constant::Class(info) => self.utf8(info.name_index),
@@ -68,6 +80,111 @@ impl ConstantPool {
                 let name_and_type = self.name_and_type(info.name_and_type_index);
                 (class, name_and_type)
             }
+            // invokestatic and invokespecial may refer to interface methods since Java 8
+            Constant::InterfaceMethodRef(_) => self.interface_method_ref(index),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Whether the method reference is to an interface method.
+    pub(crate) fn is_interface_method_ref(&self, index: u16) -> bool {
+        matches!(
+            self.entry(index as usize),
+            Some(Constant::InterfaceMethodRef(_))
+        )
+    }
+
+    pub(crate) fn field_ref(&self, index: u16) -> (String, String) {
+        match &self.constant_pool[index as usize - 1] {
+            Constant::FieldRef(info) => {
+                let class = self.class(info.class_index);
+                let name_and_type = self.name_and_type(info.name_and_type_index);
+                (class, name_and_type)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    pub(crate) fn interface_method_ref(&self, index: u16) -> (String, String) {
+        match &self.constant_pool[index as usize - 1] {
+            Constant::InterfaceMethodRef(info) => {
+                let class = self.class(info.class_index);
+                let name_and_type = self.name_and_type(info.name_and_type_index);
+                (class, name_and_type)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    pub(crate) fn method_handle(&self, index: u16) -> (ReferenceKind, String, String) {
+        match &self.constant_pool[index as usize - 1] {
+            Constant::MethodHandle(info) => {
+                let (class, name_and_type) = match info.reference_kind {
+                    ReferenceKind::GetField
+                    | ReferenceKind::GetStatic
+                    | ReferenceKind::PutField
+                    | ReferenceKind::PutStatic => self.field_ref(info.reference_index),
+                    _ => match &self.constant_pool[info.reference_index as usize - 1] {
+                        Constant::InterfaceMethodRef(_) => {
+                            self.interface_method_ref(info.reference_index)
+                        }
+                        _ => self.method_ref(info.reference_index),
+                    },
+                };
+                (info.reference_kind, class, name_and_type)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    pub(crate) fn string(&self, index: u16) -> String {
+        match &self.constant_pool[index as usize - 1] {
+            Constant::String(info) => self.utf8(info.string_index),
+            _ => unreachable!(),
+        }
+    }
+
+    pub(crate) fn method_type(&self, index: u16) -> String {
+        match &self.constant_pool[index as usize - 1] {
+            Constant::MethodType(info) => self.utf8(info.descriptor_index),
+            _ => unreachable!(),
+        }
+    }
+
+    pub(crate) fn dynamic(&self, index: u16) -> (u16, String) {
+        match &self.constant_pool[index as usize - 1] {
+            Constant::Dynamic(info) => {
+                let name_and_type = self.name_and_type(info.name_and_type_index);
+                (info.bootstrap_method_attr_index, name_and_type)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    pub(crate) fn invoke_dynamic(&self, index: u16) -> (u16, String) {
+        match &self.constant_pool[index as usize - 1] {
+            Constant::InvokeDynamic(info) => {
+                let name_and_type = self.name_and_type(info.name_and_type_index);
+                (info.bootstrap_method_attr_index, name_and_type)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    // only module-info classes have module and package constants, and the
+    // VM never loads those as classes
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub(crate) fn module(&self, index: u16) -> String {
+        match &self.constant_pool[index as usize - 1] {
+            Constant::Module(name) => name.to_owned(),
+            _ => unreachable!(),
+        }
+    }
+
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub(crate) fn package(&self, index: u16) -> String {
+        match &self.constant_pool[index as usize - 1] {
+            Constant::Package(name) => name.to_owned(),
             _ => unreachable!(),
         }
     }
@@ -90,8 +207,83 @@ impl ConstantPool {
         }
     }
 
+    fn entry(&self, index: usize) -> Option<&Constant> {
+        self.constant_pool.get(index.wrapping_sub(1))
+    }
+
+    pub(crate) fn is_numeric(&self, index: usize) -> bool {
+        matches!(
+            self.entry(index),
+            Some(
+                Constant::Integer(_) | Constant::Float(_) | Constant::Long(_) | Constant::Double(_)
+            )
+        )
+    }
+
+    /// Whether the entry takes up two slots, as loaded by `ldc2_w`.
+    pub(crate) fn is_wide(&self, index: usize) -> bool {
+        matches!(
+            self.entry(index),
+            Some(Constant::Long(_) | Constant::Double(_))
+        )
+    }
+
+    pub(crate) fn is_integer(&self, index: usize) -> bool {
+        matches!(self.entry(index), Some(Constant::Integer(_)))
+    }
+
+    pub(crate) fn is_string(&self, index: usize) -> bool {
+        matches!(self.entry(index), Some(Constant::String(_)))
+    }
+
+    pub(crate) fn is_class(&self, index: usize) -> bool {
+        matches!(self.entry(index), Some(Constant::Class(_)))
+    }
+
+    pub(crate) fn is_field_ref(&self, index: usize) -> bool {
+        matches!(self.entry(index), Some(Constant::FieldRef(_)))
+    }
+
+    /// Whether the entry is a reference to a method of a class or an interface.
+    pub(crate) fn is_method_ref(&self, index: usize) -> bool {
+        matches!(
+            self.entry(index),
+            Some(Constant::MethodRef(_) | Constant::InterfaceMethodRef(_))
+        )
+    }
+
+    pub(crate) fn is_method_handle(&self, index: usize) -> bool {
+        matches!(self.entry(index), Some(Constant::MethodHandle(_)))
+    }
+
+    pub(crate) fn is_method_type(&self, index: usize) -> bool {
+        matches!(self.entry(index), Some(Constant::MethodType(_)))
+    }
+
+    pub(crate) fn is_dynamic(&self, index: usize) -> bool {
+        matches!(self.entry(index), Some(Constant::Dynamic(_)))
+    }
+
+    pub(crate) fn is_invoke_dynamic(&self, index: usize) -> bool {
+        matches!(self.entry(index), Some(Constant::InvokeDynamic(_)))
+    }
+
+    /// Whether `ldc` loads a reference from the entry: a string, a class, a
+    /// method type or a method handle.
+    pub(crate) fn is_loadable_reference(&self, index: usize) -> bool {
+        matches!(
+            self.entry(index),
+            Some(
+                Constant::String(_)
+                    | Constant::Class(_)
+                    | Constant::MethodType(_)
+                    | Constant::MethodHandle(_)
+            )
+        )
+    }
+
     pub(crate) fn get(&self, index: usize) -> Type {
-        match &self.constant_pool[index as usize - 1] {
+        match &self.constant_pool[index - 1] {
             Constant::Integer(x) => Type::Int(x.value),
             Constant::Float(x) => Type::Float(x.value),
             Constant::Long(x) => Type::Long(x.value),
@@ -103,8 +295,67 @@ impl ConstantPool {
 
 impl From<Vec<ConstantInfo>> for ConstantPool {
     fn from(constant_pool: Vec<ConstantInfo>) -> Self {
-        ConstantPool {
-            constant_pool: constant_pool.into_iter().map(Constant::from).collect(),
-        }
+        let name = |index: u16| match &constant_pool[index as usize - 1] {
+            ConstantInfo::Utf8(info) => info.value.clone(),
+            _ => unreachable!(),
+        };
+        let names: Vec<_> = constant_pool
+            .iter()
+            .map(|info| match info {
+                ConstantInfo::Module(ConstantModuleInfo { name_index })
+                | ConstantInfo::Package(ConstantPackageInfo { name_index }) => {
+                    Some(name(*name_index))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let constant_pool = constant_pool
+            .into_iter()
+            .zip(names)
+            .map(|(info, name)| match (info, name) {
+                (ConstantInfo::Module(_), Some(name)) => Constant::Module(name),
+                (ConstantInfo::Package(_), Some(name)) => Constant::Package(name),
+                (info, _) => Constant::from(info),
+            })
+            .collect();
+        ConstantPool { constant_pool }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::class_file::ConstantUtf8Info;
+
+    fn utf8(value: &str) -> ConstantInfo {
+        ConstantInfo::Utf8(ConstantUtf8Info {
+            value: value.to_string(),
+            bytes: None,
+        })
+    }
+
+    #[test]
+    fn resolves_module_names() {
+        let constant_pool = ConstantPool::from(vec![
+            ConstantInfo::Module(ConstantModuleInfo { name_index: 3 }),
+            ConstantInfo::Module(ConstantModuleInfo { name_index: 4 }),
+            utf8("java.base"),
+            utf8("ru.khlevnov.app"),
+        ]);
+        assert_eq!(constant_pool.module(1), "java.base");
+        assert_eq!(constant_pool.module(2), "ru.khlevnov.app");
+    }
+
+    #[test]
+    fn resolves_package_names() {
+        let constant_pool = ConstantPool::from(vec![
+            utf8("ru/khlevnov"),
+            ConstantInfo::Package(ConstantPackageInfo { name_index: 1 }),
+            ConstantInfo::Package(ConstantPackageInfo { name_index: 4 }),
+            utf8("java/lang/invoke"),
+        ]);
+        assert_eq!(constant_pool.package(2), "ru/khlevnov");
+        assert_eq!(constant_pool.package(3), "java/lang/invoke");
     }
 }
@@ -0,0 +1,320 @@
+use std::fmt;
+
+/// The type of a field, parameter, local variable or value (JVMS 4.3.2).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FieldDescriptor {
+    Byte,
+    Char,
+    Double,
+    Float,
+    Int,
+    Long,
+    Short,
+    Boolean,
+    Object(String),
+    Array(Box<FieldDescriptor>),
+}
+
+const PRIMITIVES: [FieldDescriptor; 8] = [
+    FieldDescriptor::Byte,
+    FieldDescriptor::Char,
+    FieldDescriptor::Double,
+    FieldDescriptor::Float,
+    FieldDescriptor::Int,
+    FieldDescriptor::Long,
+    FieldDescriptor::Short,
+    FieldDescriptor::Boolean,
+];
+
+/// The parameter types and the return type of a method, `None` for `void`
+/// (JVMS 4.3.3).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MethodDescriptor {
+    pub(crate) parameters: Vec<FieldDescriptor>,
+    pub(crate) return_type: Option<FieldDescriptor>,
+}
+
+impl FieldDescriptor {
+    pub(crate) fn parse(descriptor: &str) -> Option<FieldDescriptor> {
+        let mut parser = DescriptorParser::new(descriptor);
+        let field_type = parser.field_type()?;
+        parser.is_empty().then_some(field_type)
+    }
+
+    /// The number of local variable slots a value of the type takes up
+    /// (JVMS 2.6.1).
+    pub(crate) fn slots(&self) -> usize {
+        match self {
+            FieldDescriptor::Long | FieldDescriptor::Double => 2,
+            _ => 1,
+        }
+    }
+
+    /// The name of the class of a reference type, such as `java/lang/String`
+    /// or `[I`, `None` for a primitive type.
+    pub(crate) fn class_name(&self) -> Option<String> {
+        match self {
+            FieldDescriptor::Object(class_name) => Some(class_name.clone()),
+            FieldDescriptor::Array(_) => Some(self.to_string()),
+            _ => None,
+        }
+    }
+
+    /// The wrapper class of a primitive type along with its method that
+    /// unboxes the value.
+    pub(crate) fn wrapper(&self) -> Option<(&'static str, &'static str)> {
+        match self {
+            FieldDescriptor::Byte => Some(("java/lang/Byte", "byteValue")),
+            FieldDescriptor::Char => Some(("java/lang/Character", "charValue")),
+            FieldDescriptor::Double => Some(("java/lang/Double", "doubleValue")),
+            FieldDescriptor::Float => Some(("java/lang/Float", "floatValue")),
+            FieldDescriptor::Int => Some(("java/lang/Integer", "intValue")),
+            FieldDescriptor::Long => Some(("java/lang/Long", "longValue")),
+            FieldDescriptor::Short => Some(("java/lang/Short", "shortValue")),
+            FieldDescriptor::Boolean => Some(("java/lang/Boolean", "booleanValue")),
+            FieldDescriptor::Object(_) | FieldDescriptor::Array(_) => None,
+        }
+    }
+
+    /// The primitive type the wrapper class boxes.
+    pub(crate) fn unwrapped(class_name: &str) -> Option<FieldDescriptor> {
+        PRIMITIVES.into_iter().find(
+            |primitive| matches!(primitive.wrapper(), Some((wrapper, _)) if wrapper == class_name),
+        )
+    }
+}
+
+impl MethodDescriptor {
+    pub(crate) fn parse(descriptor: &str) -> Option<MethodDescriptor> {
+        let mut parser = DescriptorParser::new(descriptor);
+        parser.expect(b'(')?;
+
+        let mut parameters = vec![];
+        while !parser.eat(b')') {
+            parameters.push(parser.field_type()?);
+        }
+        let return_type = if parser.eat(b'V') {
+            None
+        } else {
+            Some(parser.field_type()?)
+        };
+
+        parser.is_empty().then_some(MethodDescriptor {
+            parameters,
+            return_type,
+        })
+    }
+
+    /// The number of local variable slots the parameters take up, not
+    /// counting `this`.
+    pub(crate) fn parameter_slots(&self) -> usize {
+        self.parameters.iter().map(FieldDescriptor::slots).sum()
+    }
+}
+
+impl fmt::Display for FieldDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldDescriptor::Byte => write!(f, "B"),
+            FieldDescriptor::Char => write!(f, "C"),
+            FieldDescriptor::Double => write!(f, "D"),
+            FieldDescriptor::Float => write!(f, "F"),
+            FieldDescriptor::Int => write!(f, "I"),
+            FieldDescriptor::Long => write!(f, "J"),
+            FieldDescriptor::Short => write!(f, "S"),
+            FieldDescriptor::Boolean => write!(f, "Z"),
+            FieldDescriptor::Object(class_name) => write!(f, "L{};", class_name),
+            FieldDescriptor::Array(component) => write!(f, "[{}", component),
+        }
+    }
+}
+
+impl fmt::Display for MethodDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(")?;
+        for parameter in &self.parameters {
+            write!(f, "{}", parameter)?;
+        }
+        match &self.return_type {
+            Some(return_type) => write!(f, "){}", return_type),
+            None => write!(f, ")V"),
+        }
+    }
+}
+
+struct DescriptorParser<'a> {
+    descriptor: &'a str,
+    position: usize,
+}
+
+impl<'a> DescriptorParser<'a> {
+    fn new(descriptor: &'a str) -> DescriptorParser<'a> {
+        DescriptorParser {
+            descriptor,
+            position: 0,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position == self.descriptor.len()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.descriptor.as_bytes().get(self.position).copied()
+    }
+
+    fn eat(&mut self, expected: u8) -> bool {
+        let matches = self.peek() == Some(expected);
+        if matches {
+            self.position += 1;
+        }
+        matches
+    }
+
+    fn expect(&mut self, expected: u8) -> Option<()> {
+        self.eat(expected).then_some(())
+    }
+
+    fn field_type(&mut self) -> Option<FieldDescriptor> {
+        let field_type = match self.peek()? {
+            b'B' => FieldDescriptor::Byte,
+            b'C' => FieldDescriptor::Char,
+            b'D' => FieldDescriptor::Double,
+            b'F' => FieldDescriptor::Float,
+            b'I' => FieldDescriptor::Int,
+            b'J' => FieldDescriptor::Long,
+            b'S' => FieldDescriptor::Short,
+            b'Z' => FieldDescriptor::Boolean,
+            b'L' => {
+                self.position += 1;
+                let length = self.descriptor[self.position..].find(';')?;
+                let class_name = &self.descriptor[self.position..self.position + length];
+                if class_name.is_empty() {
+                    return None;
+                }
+                self.position += length + 1;
+                return Some(FieldDescriptor::Object(class_name.to_string()));
+            }
+            b'[' => {
+                self.position += 1;
+                let component = self.field_type()?;
+                return Some(FieldDescriptor::Array(Box::new(component)));
+            }
+            _ => return None,
+        };
+
+        self.position += 1;
+        Some(field_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(class_name: &str) -> FieldDescriptor {
+        FieldDescriptor::Object(class_name.to_string())
+    }
+
+    fn array(component: FieldDescriptor) -> FieldDescriptor {
+        FieldDescriptor::Array(Box::new(component))
+    }
+
+    #[test]
+    fn parses_field_descriptors() {
+        assert_eq!(FieldDescriptor::parse("I"), Some(FieldDescriptor::Int));
+        assert_eq!(
+            FieldDescriptor::parse("Ljava/lang/String;"),
+            Some(object("java/lang/String"))
+        );
+        assert_eq!(
+            FieldDescriptor::parse("[Ljava/lang/Object;"),
+            Some(array(object("java/lang/Object")))
+        );
+        assert_eq!(
+            FieldDescriptor::parse("[[[J"),
+            Some(array(array(array(FieldDescriptor::Long))))
+        );
+        assert_eq!(
+            FieldDescriptor::parse("[[Ljava/lang/String;"),
+            Some(array(array(object("java/lang/String"))))
+        );
+    }
+
+    #[test]
+    fn parses_method_descriptors() {
+        let descriptor = "(I[[Ljava/lang/String;JLjava/lang/Object;)[D";
+        assert_eq!(
+            MethodDescriptor::parse(descriptor),
+            Some(MethodDescriptor {
+                parameters: vec![
+                    FieldDescriptor::Int,
+                    array(array(object("java/lang/String"))),
+                    FieldDescriptor::Long,
+                    object("java/lang/Object"),
+                ],
+                return_type: Some(array(FieldDescriptor::Double)),
+            })
+        );
+        assert_eq!(
+            MethodDescriptor::parse("()V"),
+            Some(MethodDescriptor {
+                parameters: vec![],
+                return_type: None,
+            })
+        );
+    }
+
+    #[test]
+    fn displays_parsed_descriptors() {
+        for descriptor in ["Z", "[[Ljava/lang/String;", "[C"] {
+            let field_descriptor = FieldDescriptor::parse(descriptor).unwrap();
+            assert_eq!(field_descriptor.to_string(), descriptor);
+        }
+        let descriptor = "(BCSZ[Ljava/lang/Object;F)Ljava/lang/Class;";
+        let method_descriptor = MethodDescriptor::parse(descriptor).unwrap();
+        assert_eq!(method_descriptor.to_string(), descriptor);
+    }
+
+    #[test]
+    fn rejects_malformed_field_descriptors() {
+        for descriptor in [
+            "",
+            "V",
+            "X",
+            "[",
+            "[[",
+            "[V",
+            "L;",
+            "Ljava/lang/String",
+            "[Ljava/lang/Object",
+            "II",
+            "Ljava/lang/String;I",
+            "[I]",
+        ] {
+            assert_eq!(FieldDescriptor::parse(descriptor), None, "{}", descriptor);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_method_descriptors() {
+        for descriptor in [
+            "",
+            "V",
+            "I)V",
+            "(I",
+            "(IV",
+            "(V)V",
+            "()",
+            "()VV",
+            "()II",
+            "(Ljava/lang/String)V",
+            "(L;)V",
+            "([)V",
+            "()Ljava/lang/Object",
+            "()V;",
+        ] {
+            assert_eq!(MethodDescriptor::parse(descriptor), None, "{}", descriptor);
+        }
+    }
+}
@@ -0,0 +1,1455 @@
+use crate::attribute::{
+    Annotation, Attribute, AttributeInfo, CodeAttribute, ElementValue, StackMapFrame, TargetInfo,
+    TypeAnnotation, VerificationTypeInfo,
+};
+use crate::bytecode::{instructions, mnemonic, read_i32, switch_operands};
+use crate::class_file::{ClassFile, ConstantInfo, FieldInfo, MethodInfo, ReferenceKind};
+use crate::floating_decimal::{double_to_string, float_to_string};
+use crate::signature;
+use crate::thread::*;
+use std::fs;
+use std::time::UNIX_EPOCH;
+
+const TAB_COLUMN: usize = 40;
+
+const ACC_PUBLIC: u16 = 0x0001;
+const ACC_PRIVATE: u16 = 0x0002;
+const ACC_PROTECTED: u16 = 0x0004;
+const ACC_STATIC: u16 = 0x0008;
+const ACC_FINAL: u16 = 0x0010;
+const ACC_SYNCHRONIZED: u16 = 0x0020;
+const ACC_VOLATILE: u16 = 0x0040;
+const ACC_VARARGS: u16 = 0x0080;
+const ACC_TRANSIENT: u16 = 0x0080;
+const ACC_NATIVE: u16 = 0x0100;
+const ACC_INTERFACE: u16 = 0x0200;
+const ACC_ABSTRACT: u16 = 0x0400;
+const ACC_STRICT: u16 = 0x0800;
+
+const CLASS_FLAGS: &[(u16, &str)] = &[
+    (0x0001, "ACC_PUBLIC"),
+    (0x0010, "ACC_FINAL"),
+    (0x0020, "ACC_SUPER"),
+    (0x0200, "ACC_INTERFACE"),
+    (0x0400, "ACC_ABSTRACT"),
+    (0x1000, "ACC_SYNTHETIC"),
+    (0x2000, "ACC_ANNOTATION"),
+    (0x4000, "ACC_ENUM"),
+    (0x8000, "ACC_MODULE"),
+];
+
+const FIELD_FLAGS: &[(u16, &str)] = &[
+    (0x0001, "ACC_PUBLIC"),
+    (0x0002, "ACC_PRIVATE"),
+    (0x0004, "ACC_PROTECTED"),
+    (0x0008, "ACC_STATIC"),
+    (0x0010, "ACC_FINAL"),
+    (0x0040, "ACC_VOLATILE"),
+    (0x0080, "ACC_TRANSIENT"),
+    (0x1000, "ACC_SYNTHETIC"),
+    (0x4000, "ACC_ENUM"),
+];
+
+const METHOD_FLAGS: &[(u16, &str)] = &[
+    (0x0001, "ACC_PUBLIC"),
+    (0x0002, "ACC_PRIVATE"),
+    (0x0004, "ACC_PROTECTED"),
+    (0x0008, "ACC_STATIC"),
+    (0x0010, "ACC_FINAL"),
+    (0x0020, "ACC_SYNCHRONIZED"),
+    (0x0040, "ACC_BRIDGE"),
+    (0x0080, "ACC_VARARGS"),
+    (0x0100, "ACC_NATIVE"),
+    (0x0400, "ACC_ABSTRACT"),
+    (0x0800, "ACC_STRICT"),
+    (0x1000, "ACC_SYNTHETIC"),
+];
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    let verbose = args.iter().any(|arg| arg == "-v" || arg == "-verbose");
+    let paths = args
+        .iter()
+        .filter(|arg| !arg.starts_with('-'))
+        .collect::<Vec<_>>();
+
+    if paths.is_empty() {
+        return Err("Usage: smerdvm disasm [-v] <class file>...".to_string());
+    }
+
+    for path in paths {
+        let bytes = fs::read(path).map_err(|_| format!("Error: class not found: {}", path))?;
+        let class_file = ClassFile::try_from(bytes.as_slice())
+            .map_err(|error| format!("Error: error while reading {}: {}", path, error))?;
+
+        if verbose {
+            let path = fs::canonicalize(path).map_or_else(
+                |_| path.to_string(),
+                |path| path.to_string_lossy().into_owned(),
+            );
+            println!("Classfile {}", path);
+
+            let modified = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok());
+            if let Some(modified) = modified {
+                println!(
+                    "  Last modified {}; size {} bytes",
+                    date(modified.as_secs()),
+                    bytes.len()
+                );
+            }
+        }
+
+        print!("{}", Disassembler::new(&class_file, verbose).disassemble());
+    }
+
+    Ok(())
+}
+
+fn date(seconds: u64) -> String {
+    // Howard Hinnant's days-to-civil conversion
+    let days = (seconds / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = (shifted_month + 2) % 12;
+    let year = year_of_era + era * 400 + (month < 2) as i64;
+
+    format!("{} {}, {}", MONTHS[month as usize], day, year)
+}
+
+pub struct Disassembler<'a> {
+    class_file: &'a ClassFile,
+    verbose: bool,
+    output: String,
+    indent: usize,
+}
+
+impl<'a> Disassembler<'a> {
+    pub fn new(class_file: &'a ClassFile, verbose: bool) -> Disassembler<'a> {
+        Disassembler {
+            class_file,
+            verbose,
+            output: String::new(),
+            indent: 0,
+        }
+    }
+
+    pub fn disassemble(mut self) -> String {
+        let class_file = self.class_file;
+
+        if let Some(source_file) = class_file.source_file() {
+            let indent = if self.verbose { "  " } else { "" };
+            let line = format!("{}Compiled from \"{}\"", indent, self.utf8(source_file));
+            self.println(&line);
+        }
+
+        let declaration = self.class_declaration();
+        if self.verbose {
+            self.println(&declaration);
+            self.indent += 2;
+            self.class_header();
+            self.indent -= 2;
+            self.println("Constant pool:");
+            self.constant_pool();
+            self.println("{");
+        } else {
+            self.println(&format!("{} {{", declaration));
+        }
+
+        self.indent += 2;
+        for field in &class_file.fields {
+            self.field(field);
+            self.println("");
+        }
+        for (index, method) in class_file.methods.iter().enumerate() {
+            if index > 0 {
+                self.println("");
+            }
+            self.method(method);
+        }
+        self.indent -= 2;
+
+        self.println("}");
+        if self.verbose {
+            self.attributes(&class_file.attributes);
+        }
+
+        self.output
+    }
+
+    fn println(&mut self, line: &str) {
+        if !line.is_empty() {
+            self.output.push_str(&" ".repeat(self.indent));
+            self.output.push_str(line.trim_end());
+        }
+        self.output.push('\n');
+    }
+
+    fn println_commented(&mut self, line: &str, comment: &str) {
+        let padding = TAB_COLUMN.saturating_sub(line.chars().count()).max(1);
+        let line = format!("{}{}// {}", line, " ".repeat(padding), comment);
+        self.println(&line);
+    }
+
+    fn constant(&self, index: u16) -> Option<&'a ConstantInfo> {
+        let constant_pool = &self.class_file.constant_pool;
+        constant_pool.get((index as usize).wrapping_sub(1))
+    }
+
+    fn utf8(&self, index: u16) -> &'a str {
+        self.class_file.utf8(index).unwrap_or_default()
+    }
+
+    fn class_name(&self, index: u16) -> &'a str {
+        match self.constant(index) {
+            Some(ConstantInfo::Class(info)) => self.utf8(info.name_index),
+            _ => "",
+        }
+    }
+
+    fn this_class_name(&self) -> &'a str {
+        self.class_name(self.class_file.this_class)
+    }
+
+    fn name_and_type(&self, index: u16) -> String {
+        match self.constant(index) {
+            Some(ConstantInfo::NameAndType(info)) => format!(
+                "{}:{}",
+                check_name(self.utf8(info.name_index)),
+                self.utf8(info.descriptor_index)
+            ),
+            _ => format!("#{}", index),
+        }
+    }
+
+    fn member_ref(&self, class_index: u16, name_and_type_index: u16, in_code: bool) -> String {
+        let class_name = self.class_name(class_index);
+        let name_and_type = self.name_and_type(name_and_type_index);
+
+        if in_code && class_name == self.this_class_name() {
+            name_and_type
+        } else {
+            format!("{}.{}", check_name(class_name), name_and_type)
+        }
+    }
+
+    fn string_value(&self, index: u16, in_code: bool) -> String {
+        let constant = match self.constant(index) {
+            Some(constant) => constant,
+            None => return format!("#{}", index),
+        };
+
+        match constant {
+            ConstantInfo::Utf8(info) => escape(&info.value),
+            ConstantInfo::Integer(info) => info.value.to_string(),
+            ConstantInfo::Float(info) => format!("{}f", float_to_string(info.value)),
+            ConstantInfo::Long(info) => format!("{}l", info.value),
+            ConstantInfo::Double(info) => format!("{}d", double_to_string(info.value)),
+            ConstantInfo::Class(info) => check_name(self.utf8(info.name_index)),
+            ConstantInfo::String(info) => escape(self.utf8(info.string_index)),
+            ConstantInfo::FieldRef(info) => {
+                self.member_ref(info.class_index, info.name_and_type_index, in_code)
+            }
+            ConstantInfo::MethodRef(info) => {
+                self.member_ref(info.class_index, info.name_and_type_index, in_code)
+            }
+            ConstantInfo::InterfaceMethodRef(info) => {
+                self.member_ref(info.class_index, info.name_and_type_index, in_code)
+            }
+            ConstantInfo::NameAndType(_) => self.name_and_type(index),
+            ConstantInfo::MethodHandle(info) => format!(
+                "{} {}",
+                reference_kind_name(info.reference_kind),
+                self.string_value(info.reference_index, false)
+            ),
+            ConstantInfo::MethodType(info) => self.utf8(info.descriptor_index).to_string(),
+            ConstantInfo::Dynamic(info) | ConstantInfo::InvokeDynamic(info) => format!(
+                "#{}:{}",
+                info.bootstrap_method_attr_index,
+                self.name_and_type(info.name_and_type_index)
+            ),
+            ConstantInfo::Module(info) => check_name(self.utf8(info.name_index)),
+            ConstantInfo::Package(info) => check_name(self.utf8(info.name_index)),
+            ConstantInfo::Unusable => String::new(),
+        }
+    }
+
+    fn constant_comment(&self, index: u16) -> String {
+        let kind = match self.constant(index) {
+            Some(ConstantInfo::Class(_)) => "class",
+            Some(ConstantInfo::String(_)) => "String",
+            Some(ConstantInfo::Integer(_)) => "int",
+            Some(ConstantInfo::Float(_)) => "float",
+            Some(ConstantInfo::Long(_)) => "long",
+            Some(ConstantInfo::Double(_)) => "double",
+            Some(ConstantInfo::FieldRef(_)) => "Field",
+            Some(ConstantInfo::MethodRef(_)) => "Method",
+            Some(ConstantInfo::InterfaceMethodRef(_)) => "InterfaceMethod",
+            Some(ConstantInfo::MethodHandle(_)) => "MethodHandle",
+            Some(ConstantInfo::MethodType(_)) => "MethodType",
+            Some(ConstantInfo::Dynamic(_)) => "Dynamic",
+            Some(ConstantInfo::InvokeDynamic(_)) => "InvokeDynamic",
+            _ => return self.string_value(index, true),
+        };
+
+        format!("{} {}", kind, self.string_value(index, true))
+    }
+
+    fn class_declaration(&self) -> String {
+        let class_file = self.class_file;
+        let access_flags = class_file.access_flags;
+        let is_interface = access_flags & ACC_INTERFACE != 0;
+
+        let mut declaration = modifiers(access_flags & (ACC_PUBLIC | ACC_FINAL));
+        if is_interface {
+            declaration.push_str("interface ");
+        } else {
+            if access_flags & ACC_ABSTRACT != 0 {
+                declaration.push_str("abstract ");
+            }
+            declaration.push_str("class ");
+        }
+        declaration.push_str(&self.this_class_name().replace('/', "."));
+
+        let signature = class_file
+            .signature()
+            .and_then(|index| signature::class_signature(self.utf8(index), self.verbose));
+
+        let (super_class, interfaces, separator) = match signature {
+            Some(signature) => {
+                declaration.push_str(&signature.type_parameters);
+                let super_class = Some(signature.super_class).filter(|super_class| {
+                    !is_interface && (self.verbose || super_class != "java.lang.Object")
+                });
+                (super_class, signature.interfaces, ", ")
+            }
+            None => {
+                let super_class = Some(self.class_name(class_file.super_class))
+                    .filter(|_| !is_interface && class_file.super_class != 0)
+                    .filter(|super_class| *super_class != "java/lang/Object")
+                    .map(|super_class| super_class.replace('/', "."));
+                let interfaces = class_file
+                    .interfaces
+                    .iter()
+                    .map(|&index| self.class_name(index).replace('/', "."))
+                    .collect();
+                (super_class, interfaces, ",")
+            }
+        };
+
+        if let Some(super_class) = super_class {
+            declaration.push_str(" extends ");
+            declaration.push_str(&super_class);
+        }
+        if !interfaces.is_empty() {
+            declaration.push_str(if is_interface {
+                " extends "
+            } else {
+                " implements "
+            });
+            declaration.push_str(&interfaces.join(separator));
+        }
+
+        declaration
+    }
+
+    fn class_header(&mut self) {
+        let class_file = self.class_file;
+
+        self.println(&format!("minor version: {}", class_file.minor_version));
+        self.println(&format!("major version: {}", class_file.major_version));
+        self.println(&flags(class_file.access_flags, CLASS_FLAGS));
+
+        let this_class = format!("this_class: #{}", class_file.this_class);
+        self.println_commented(&this_class, &check_name(self.this_class_name()));
+        let super_class = format!("super_class: #{}", class_file.super_class);
+        if class_file.super_class == 0 {
+            self.println(&super_class);
+        } else {
+            let comment = check_name(self.class_name(class_file.super_class));
+            self.println_commented(&super_class, &comment);
+        }
+
+        self.println(&format!(
+            "interfaces: {}, fields: {}, methods: {}, attributes: {}",
+            class_file.interfaces.len(),
+            class_file.fields.len(),
+            class_file.methods.len(),
+            class_file.attributes.len()
+        ));
+    }
+
+    fn constant_pool(&mut self) {
+        let constant_pool = &self.class_file.constant_pool;
+        let width = (constant_pool.len() + 1).to_string().len() + 1;
+
+        self.indent += 2;
+        for (index, constant) in constant_pool.iter().enumerate() {
+            let index = index as u16 + 1;
+            let (tag, operands) = match constant {
+                ConstantInfo::Utf8(info) => ("Utf8", escape(&info.value)),
+                ConstantInfo::Integer(_) => ("Integer", self.string_value(index, false)),
+                ConstantInfo::Float(_) => ("Float", self.string_value(index, false)),
+                ConstantInfo::Long(_) => ("Long", self.string_value(index, false)),
+                ConstantInfo::Double(_) => ("Double", self.string_value(index, false)),
+                ConstantInfo::Class(info) => ("Class", format!("#{}", info.name_index)),
+                ConstantInfo::String(info) => ("String", format!("#{}", info.string_index)),
+                ConstantInfo::FieldRef(info) => (
+                    "Fieldref",
+                    format!("#{}.#{}", info.class_index, info.name_and_type_index),
+                ),
+                ConstantInfo::MethodRef(info) => (
+                    "Methodref",
+                    format!("#{}.#{}", info.class_index, info.name_and_type_index),
+                ),
+                ConstantInfo::InterfaceMethodRef(info) => (
+                    "InterfaceMethodref",
+                    format!("#{}.#{}", info.class_index, info.name_and_type_index),
+                ),
+                ConstantInfo::NameAndType(info) => (
+                    "NameAndType",
+                    format!("#{}:#{}", info.name_index, info.descriptor_index),
+                ),
+                ConstantInfo::MethodHandle(info) => (
+                    "MethodHandle",
+                    format!("{}:#{}", info.reference_kind as u8, info.reference_index),
+                ),
+                ConstantInfo::MethodType(info) => {
+                    ("MethodType", format!("#{}", info.descriptor_index))
+                }
+                ConstantInfo::Dynamic(info) => (
+                    "Dynamic",
+                    format!(
+                        "#{}:#{}",
+                        info.bootstrap_method_attr_index, info.name_and_type_index
+                    ),
+                ),
+                ConstantInfo::InvokeDynamic(info) => (
+                    "InvokeDynamic",
+                    format!(
+                        "#{}:#{}",
+                        info.bootstrap_method_attr_index, info.name_and_type_index
+                    ),
+                ),
+                ConstantInfo::Module(info) => ("Module", format!("#{}", info.name_index)),
+                ConstantInfo::Package(info) => ("Package", format!("#{}", info.name_index)),
+                ConstantInfo::Unusable => continue,
+            };
+
+            let line = format!(
+                "{:>width$} = {:<18} {}",
+                format!("#{}", index),
+                tag,
+                operands,
+                width = width
+            );
+
+            match constant {
+                ConstantInfo::Utf8(_)
+                | ConstantInfo::Integer(_)
+                | ConstantInfo::Float(_)
+                | ConstantInfo::Long(_)
+                | ConstantInfo::Double(_) => self.println(&line),
+                ConstantInfo::MethodType(_) => {
+                    let comment = format!(" {}", self.string_value(index, false));
+                    self.println_commented(&line, &comment);
+                }
+                _ => self.println_commented(&line, &self.string_value(index, false)),
+            }
+        }
+        self.indent -= 2;
+    }
+
+    fn field(&mut self, field: &FieldInfo) {
+        let descriptor = self.utf8(field.descriptor_index);
+        let java_type = field
+            .signature()
+            .and_then(|index| signature::field_signature(self.utf8(index), self.verbose))
+            .or_else(|| signature::field_signature(descriptor, self.verbose))
+            .unwrap_or_else(|| descriptor.to_string());
+
+        let access_flags = field.access_flags
+            & (ACC_PUBLIC
+                | ACC_PRIVATE
+                | ACC_PROTECTED
+                | ACC_STATIC
+                | ACC_FINAL
+                | ACC_VOLATILE
+                | ACC_TRANSIENT);
+        let declaration = format!(
+            "{}{} {};",
+            modifiers(access_flags),
+            java_type,
+            self.utf8(field.name_index)
+        );
+        self.println(&declaration);
+
+        if self.verbose {
+            self.indent += 2;
+            self.println(&format!("descriptor: {}", descriptor));
+            self.println(&flags(field.access_flags, FIELD_FLAGS));
+            self.attributes(&field.attributes);
+            self.indent -= 2;
+        }
+    }
+
+    fn method_declaration(&self, method: &MethodInfo) -> String {
+        let class_file = self.class_file;
+        let access_flags = method.access_flags;
+        let name = self.utf8(method.name_index);
+        let descriptor = self.utf8(method.descriptor_index);
+
+        let mut declaration = modifiers(
+            access_flags
+                & (ACC_PUBLIC
+                    | ACC_PRIVATE
+                    | ACC_PROTECTED
+                    | ACC_STATIC
+                    | ACC_FINAL
+                    | ACC_SYNCHRONIZED
+                    | ACC_NATIVE
+                    | ACC_ABSTRACT
+                    | ACC_STRICT),
+        );
+        if class_file.access_flags & ACC_INTERFACE != 0
+            && access_flags & (ACC_ABSTRACT | ACC_STATIC | ACC_PRIVATE) == 0
+        {
+            declaration.push_str("default ");
+        }
+
+        if name == "<clinit>" {
+            declaration.push_str("{}");
+            return declaration;
+        }
+
+        let signature = method
+            .signature()
+            .and_then(|index| signature::method_signature(self.utf8(index), self.verbose))
+            .or_else(|| signature::method_signature(descriptor, self.verbose));
+        let signature = match signature {
+            Some(signature) => signature,
+            None => return format!("{}{}", declaration, name),
+        };
+
+        if !signature.type_parameters.is_empty() {
+            declaration.push_str(&signature.type_parameters);
+            declaration.push(' ');
+        }
+
+        if name == "<init>" {
+            declaration.push_str(&self.this_class_name().replace('/', "."));
+        } else {
+            declaration.push_str(&signature.return_type);
+            declaration.push(' ');
+            declaration.push_str(name);
+        }
+
+        let mut parameters = signature.parameters;
+        if access_flags & ACC_VARARGS != 0 {
+            if let Some(last) = parameters.last_mut() {
+                if let Some(element_type) = last.strip_suffix("[]") {
+                    *last = format!("{}...", element_type);
+                }
+            }
+        }
+        declaration.push('(');
+        declaration.push_str(&parameters.join(", "));
+        declaration.push(')');
+
+        let exceptions = if signature.exceptions.is_empty() {
+            method
+                .exceptions()
+                .unwrap_or_default()
+                .iter()
+                .map(|&index| self.class_name(index).replace('/', "."))
+                .collect()
+        } else {
+            signature.exceptions
+        };
+        if !exceptions.is_empty() {
+            declaration.push_str(" throws ");
+            declaration.push_str(&exceptions.join(", "));
+        }
+
+        declaration
+    }
+
+    fn method(&mut self, method: &MethodInfo) {
+        let declaration = format!("{};", self.method_declaration(method));
+        self.println(&declaration);
+
+        self.indent += 2;
+        if self.verbose {
+            let descriptor = format!("descriptor: {}", self.utf8(method.descriptor_index));
+            self.println(&descriptor);
+            self.println(&flags(method.access_flags, METHOD_FLAGS));
+        }
+
+        for info in &method.attributes {
+            match &info.attribute {
+                Attribute::Code(code) => self.code(method, code),
+                _ if self.verbose => self.attribute(info),
+                _ => (),
+            }
+        }
+        self.indent -= 2;
+    }
+
+    fn code(&mut self, method: &MethodInfo, code: &CodeAttribute) {
+        self.println("Code:");
+
+        let indent = self.indent;
+        if self.verbose {
+            self.indent += 2;
+
+            let descriptor = self.utf8(method.descriptor_index);
+            let mut args_size = signature::method_signature(descriptor, false)
+                .map_or(0, |descriptor| descriptor.parameters.len());
+            if method.access_flags & ACC_STATIC == 0 {
+                args_size += 1;
+            }
+            self.println(&format!(
+                "stack={}, locals={}, args_size={}",
+                code.max_stack, code.max_locals, args_size
+            ));
+        }
+
+        for (pc, _) in instructions(&code.code) {
+            self.instruction(&code.code, pc);
+        }
+
+        if !code.exception_table.is_empty() {
+            self.println("Exception table:");
+            self.indent += 2;
+            self.println(" from    to  target type");
+            for entry in &code.exception_table {
+                let catch_type = if entry.catch_type == 0 {
+                    "any".to_string()
+                } else {
+                    format!("Class {}", self.class_name(entry.catch_type))
+                };
+                self.println(&format!(
+                    " {:>5} {:>5} {:>5}   {}",
+                    entry.start_pc, entry.end_pc, entry.handler_pc, catch_type
+                ));
+            }
+            self.indent -= 2;
+        }
+
+        for info in &code.attributes {
+            match info.attribute {
+                Attribute::LineNumberTable(_) | Attribute::LocalVariableTable(_) => {
+                    self.attribute(info)
+                }
+                _ if self.verbose => self.attribute(info),
+                _ => (),
+            }
+        }
+
+        self.indent = indent;
+    }
+
+    fn instruction(&mut self, code: &[u8], pc: usize) {
+        let u1 = |at: usize| code.get(at).copied().unwrap_or_default();
+        let u2 = |at: usize| u16::from_be_bytes([u1(at), u1(at + 1)]);
+        let branch = |offset: i64| (pc as i64 + offset).to_string();
+
+        let opcode = code[pc];
+        let name = mnemonic(opcode).map_or_else(|| format!("{:#04x}", opcode), str::to_string);
+
+        let (name, operands, comment) = match opcode {
+            BIPUSH => (name, (u1(pc + 1) as i8).to_string(), None),
+            SIPUSH => (name, (u2(pc + 1) as i16).to_string(), None),
+            LDC => {
+                let index = u1(pc + 1) as u16;
+                (name, format!("#{}", index), Some(index))
+            }
+            LDC_W
+            | LDC2_W
+            | GETSTATIC..=INVOKESTATIC
+            | NEW
+            | ANEWARRAY
+            | CHECKCAST
+            | INSTANCEOF => {
+                let index = u2(pc + 1);
+                (name, format!("#{}", index), Some(index))
+            }
+            INVOKEINTERFACE | INVOKEDYNAMIC | MULTIANEWARRAY => {
+                let index = u2(pc + 1);
+                (name, format!("#{},  {}", index, u1(pc + 3)), Some(index))
+            }
+            ILOAD..=ALOAD | ISTORE..=ASTORE | RET => (name, u1(pc + 1).to_string(), None),
+            IINC => (name, format!("{}, {}", u1(pc + 1), u1(pc + 2) as i8), None),
+            IFEQ..=JSR | IFNULL | IFNONNULL => (name, branch(u2(pc + 1) as i16 as i64), None),
+            GOTO_W | JSR_W => {
+                let offset = read_i32(code, pc + 1).unwrap_or_default();
+                (name, branch(offset as i64), None)
+            }
+            NEWARRAY => {
+                let element_type = match u1(pc + 1) {
+                    4 => "boolean".to_string(),
+                    5 => "char".to_string(),
+                    6 => "float".to_string(),
+                    7 => "double".to_string(),
+                    8 => "byte".to_string(),
+                    9 => "short".to_string(),
+                    10 => "int".to_string(),
+                    11 => "long".to_string(),
+                    atype => atype.to_string(),
+                };
+                (name, format!(" {}", element_type), None)
+            }
+            WIDE => {
+                let opcode = u1(pc + 1);
+                let name = format!("{}_w", mnemonic(opcode).unwrap_or("wide"));
+                let operands = if opcode == IINC {
+                    format!("{}, {}", u2(pc + 2), u2(pc + 4) as i16)
+                } else {
+                    u2(pc + 2).to_string()
+                };
+                (name, operands, None)
+            }
+            TABLESWITCH | LOOKUPSWITCH => {
+                self.switch(code, pc, &name);
+                return;
+            }
+            _ => (name, String::new(), None),
+        };
+
+        let line = if operands.is_empty() {
+            format!("{:>4}: {}", pc, name)
+        } else {
+            format!("{:>4}: {:<13} {}", pc, name, operands)
+        };
+
+        match comment {
+            Some(index) => self.println_commented(&line, &self.constant_comment(index)),
+            None => self.println(&line),
+        }
+    }
+
+    fn switch(&mut self, code: &[u8], pc: usize, name: &str) {
+        let read = |at: usize| read_i32(code, at).unwrap_or_default();
+        let target = |offset: i32| pc as i64 + offset as i64;
+        let operands = switch_operands(pc);
+        let default = target(read(operands));
+
+        let mut cases = vec![];
+        let header = if code[pc] == TABLESWITCH {
+            let (low, high) = (read(operands + 4), read(operands + 8));
+            for (i, value) in (low..=high).enumerate() {
+                cases.push((value, target(read(operands + 12 + 4 * i))));
+            }
+            format!("{} to {}", low, high)
+        } else {
+            let npairs = read(operands + 4);
+            for i in 0..npairs.max(0) as usize {
+                let pair = operands + 8 + 8 * i;
+                cases.push((read(pair), target(read(pair + 4))));
+            }
+            npairs.to_string()
+        };
+
+        self.println(&format!("{:>4}: {:<13} {{ // {}", pc, name, header));
+        for (value, target) in cases {
+            self.println(&format!("{:>18}: {}", value, target));
+        }
+        self.println(&format!("{:>18}: {}", "default", default));
+        self.println("      }");
+    }
+
+    fn attributes(&mut self, attributes: &[AttributeInfo]) {
+        for info in attributes {
+            self.attribute(info);
+        }
+    }
+
+    fn attribute(&mut self, info: &AttributeInfo) {
+        let name = self.utf8(info.attribute_name_index);
+
+        match &info.attribute {
+            Attribute::ConstantValue(index) => {
+                let line = format!("ConstantValue: {}", self.constant_comment(*index));
+                self.println(&line);
+            }
+            Attribute::Code(_) => (),
+            Attribute::StackMapTable(frames) => {
+                self.println(&format!(
+                    "StackMapTable: number_of_entries = {}",
+                    frames.len()
+                ));
+                self.indent += 2;
+                for frame in frames {
+                    self.stack_map_frame(frame);
+                }
+                self.indent -= 2;
+            }
+            Attribute::Exceptions(exceptions) => {
+                self.println("Exceptions:");
+                let exceptions = exceptions
+                    .iter()
+                    .map(|&index| self.class_name(index).replace('/', "."))
+                    .collect::<Vec<_>>();
+                self.println(&format!("  throws {}", exceptions.join(", ")));
+            }
+            Attribute::InnerClasses(inner_classes) => {
+                self.println("InnerClasses:");
+                self.indent += 2;
+                for inner_class in inner_classes {
+                    let access_flags = inner_class.inner_class_access_flags;
+                    let mut mask =
+                        ACC_PUBLIC | ACC_PRIVATE | ACC_PROTECTED | ACC_STATIC | ACC_FINAL;
+                    if access_flags & ACC_INTERFACE == 0 {
+                        mask |= ACC_ABSTRACT;
+                    }
+
+                    let mut line = modifiers(access_flags & mask);
+                    let mut comment = String::new();
+                    if inner_class.inner_name_index != 0 {
+                        line.push_str(&format!("#{}= ", inner_class.inner_name_index));
+                        comment.push_str(self.utf8(inner_class.inner_name_index));
+                        comment.push('=');
+                    }
+                    line.push_str(&format!("#{}", inner_class.inner_class_info_index));
+                    comment.push_str(&format!(
+                        "class {}",
+                        self.class_name(inner_class.inner_class_info_index)
+                    ));
+                    if inner_class.outer_class_info_index != 0 {
+                        line.push_str(&format!(" of #{}", inner_class.outer_class_info_index));
+                        comment.push_str(&format!(
+                            " of class {}",
+                            self.class_name(inner_class.outer_class_info_index)
+                        ));
+                    }
+                    line.push(';');
+                    self.println_commented(&line, &comment);
+                }
+                self.indent -= 2;
+            }
+            Attribute::EnclosingMethod(enclosing_method) => {
+                let line = format!(
+                    "EnclosingMethod: #{}.#{}",
+                    enclosing_method.class_index, enclosing_method.method_index
+                );
+                let mut comment = self
+                    .class_name(enclosing_method.class_index)
+                    .replace('/', ".");
+                if let Some(ConstantInfo::NameAndType(info)) =
+                    self.constant(enclosing_method.method_index)
+                {
+                    comment.push('.');
+                    comment.push_str(self.utf8(info.name_index));
+                }
+                self.println_commented(&line, &comment);
+            }
+            Attribute::Synthetic => self.println("Synthetic: true"),
+            Attribute::Deprecated => self.println("Deprecated: true"),
+            Attribute::Signature(index) => {
+                let line = format!("Signature: #{}", index);
+                self.println_commented(&line, self.utf8(*index));
+            }
+            Attribute::SourceFile(index) => {
+                let line = format!("SourceFile: \"{}\"", self.utf8(*index));
+                self.println(&line);
+            }
+            Attribute::SourceDebugExtension(bytes) => {
+                self.println("SourceDebugExtension:");
+                for line in String::from_utf8_lossy(bytes).lines() {
+                    self.println(&format!("  {}", line));
+                }
+            }
+            Attribute::LineNumberTable(line_numbers) => {
+                self.println("LineNumberTable:");
+                for line_number in line_numbers {
+                    self.println(&format!(
+                        "  line {}: {}",
+                        line_number.line_number, line_number.start_pc
+                    ));
+                }
+            }
+            Attribute::LocalVariableTable(local_variables)
+            | Attribute::LocalVariableTypeTable(local_variables) => {
+                self.println(&format!("{}:", name));
+                self.println("  Start  Length  Slot  Name   Signature");
+                for local_variable in local_variables {
+                    self.println(&format!(
+                        "  {:>5} {:>7} {:>5} {:>5}   {}",
+                        local_variable.start_pc,
+                        local_variable.length,
+                        local_variable.index,
+                        self.utf8(local_variable.name_index),
+                        self.utf8(local_variable.descriptor_index)
+                    ));
+                }
+            }
+            Attribute::RuntimeVisibleAnnotations(annotations)
+            | Attribute::RuntimeInvisibleAnnotations(annotations) => {
+                self.println(&format!("{}:", name));
+                self.indent += 2;
+                for (index, annotation) in annotations.iter().enumerate() {
+                    self.annotation(index, annotation);
+                }
+                self.indent -= 2;
+            }
+            Attribute::RuntimeVisibleParameterAnnotations(parameters)
+            | Attribute::RuntimeInvisibleParameterAnnotations(parameters) => {
+                self.println(&format!("{}:", name));
+                self.indent += 2;
+                for (parameter, annotations) in parameters.iter().enumerate() {
+                    self.println(&format!("parameter {}:", parameter));
+                    self.indent += 2;
+                    for (index, annotation) in annotations.iter().enumerate() {
+                        self.annotation(index, annotation);
+                    }
+                    self.indent -= 2;
+                }
+                self.indent -= 2;
+            }
+            Attribute::RuntimeVisibleTypeAnnotations(annotations)
+            | Attribute::RuntimeInvisibleTypeAnnotations(annotations) => {
+                self.println(&format!("{}:", name));
+                self.indent += 2;
+                for (index, annotation) in annotations.iter().enumerate() {
+                    self.type_annotation(index, annotation);
+                }
+                self.indent -= 2;
+            }
+            Attribute::AnnotationDefault(value) => {
+                self.println("AnnotationDefault:");
+                self.println(&format!(
+                    "  default_value: {}",
+                    self.raw_element_value(value)
+                ));
+                self.println(&format!("    {}", self.element_value(value)));
+            }
+            Attribute::BootstrapMethods(bootstrap_methods) => {
+                self.println("BootstrapMethods:");
+                self.indent += 2;
+                for (index, bootstrap_method) in bootstrap_methods.iter().enumerate() {
+                    let method_ref = bootstrap_method.bootstrap_method_ref;
+                    self.println(&format!(
+                        "{}: #{} {}",
+                        index,
+                        method_ref,
+                        self.string_value(method_ref, false)
+                    ));
+                    self.println("  Method arguments:");
+                    for &argument in &bootstrap_method.bootstrap_arguments {
+                        let line =
+                            format!("    #{} {}", argument, self.string_value(argument, false));
+                        self.println(&line);
+                    }
+                }
+                self.indent -= 2;
+            }
+            Attribute::MethodParameters(parameters) => {
+                self.println("MethodParameters:");
+                self.println(&format!("  {:<31}{}", "Name", "Flags"));
+                for parameter in parameters {
+                    let mut flags = vec![];
+                    if parameter.access_flags & 0x0010 != 0 {
+                        flags.push("final");
+                    }
+                    if parameter.access_flags & 0x1000 != 0 {
+                        flags.push("synthetic");
+                    }
+                    if parameter.access_flags & 0x8000 != 0 {
+                        flags.push("mandated");
+                    }
+                    let name = if parameter.name_index == 0 {
+                        "<no name>"
+                    } else {
+                        self.utf8(parameter.name_index)
+                    };
+                    self.println(&format!("  {:<31}{}", name, flags.join(" ")));
+                }
+            }
+            Attribute::NestHost(index) => {
+                let line = format!("NestHost: class {}", self.class_name(*index));
+                self.println(&line);
+            }
+            Attribute::NestMembers(classes) | Attribute::PermittedSubclasses(classes) => {
+                self.println(&format!("{}:", name));
+                for &index in classes {
+                    self.println(&format!("  {}", self.class_name(index)));
+                }
+            }
+            Attribute::Record(components) => {
+                self.println("Record:");
+                self.indent += 2;
+                for component in components {
+                    let descriptor = self.utf8(component.descriptor_index);
+                    let java_type = signature::field_signature(descriptor, self.verbose)
+                        .unwrap_or_else(|| descriptor.to_string());
+                    let line = format!("{} {};", java_type, self.utf8(component.name_index));
+                    self.println(&line);
+                    self.indent += 2;
+                    self.println(&format!("descriptor: {}", descriptor));
+                    self.attributes(&component.attributes);
+                    self.indent -= 2;
+                    self.println("");
+                }
+                self.indent -= 2;
+            }
+            Attribute::Unknown(bytes) => {
+                self.println(&format!(
+                    "{}: length = {:#x} (unknown attribute)",
+                    name,
+                    bytes.len()
+                ));
+                for chunk in bytes.chunks(16) {
+                    let line = chunk
+                        .iter()
+                        .map(|byte| format!("{:02x}", byte))
+                        .collect::<Vec<_>>();
+                    self.println(&format!("   {}", line.join(" ")));
+                }
+            }
+        }
+    }
+
+    fn stack_map_frame(&mut self, frame: &StackMapFrame) {
+        match frame {
+            StackMapFrame::Same { frame_type } => {
+                self.println(&format!("frame_type = {} /* same */", frame_type));
+            }
+            StackMapFrame::SameLocals1StackItem { frame_type, stack } => {
+                let line = format!("frame_type = {} /* same_locals_1_stack_item */", frame_type);
+                self.println(&line);
+                self.verification_types("stack", std::slice::from_ref(stack));
+            }
+            StackMapFrame::SameLocals1StackItemExtended {
+                offset_delta,
+                stack,
+            } => {
+                self.println("frame_type = 247 /* same_locals_1_stack_item_frame_extended */");
+                self.println(&format!("  offset_delta = {}", offset_delta));
+                self.verification_types("stack", std::slice::from_ref(stack));
+            }
+            StackMapFrame::Chop {
+                frame_type,
+                offset_delta,
+            } => {
+                self.println(&format!("frame_type = {} /* chop */", frame_type));
+                self.println(&format!("  offset_delta = {}", offset_delta));
+            }
+            StackMapFrame::SameExtended { offset_delta } => {
+                self.println("frame_type = 251 /* same_frame_extended */");
+                self.println(&format!("  offset_delta = {}", offset_delta));
+            }
+            StackMapFrame::Append {
+                frame_type,
+                offset_delta,
+                locals,
+            } => {
+                self.println(&format!("frame_type = {} /* append */", frame_type));
+                self.println(&format!("  offset_delta = {}", offset_delta));
+                self.verification_types("locals", locals);
+            }
+            StackMapFrame::Full {
+                offset_delta,
+                locals,
+                stack,
+            } => {
+                self.println("frame_type = 255 /* full_frame */");
+                self.println(&format!("  offset_delta = {}", offset_delta));
+                self.verification_types("locals", locals);
+                self.verification_types("stack", stack);
+            }
+        }
+    }
+
+    fn verification_types(&mut self, name: &str, types: &[VerificationTypeInfo]) {
+        if types.is_empty() {
+            self.println(&format!("  {} = []", name));
+            return;
+        }
+
+        let types = types
+            .iter()
+            .map(|info| match info {
+                VerificationTypeInfo::Top => "top".to_string(),
+                VerificationTypeInfo::Integer => "int".to_string(),
+                VerificationTypeInfo::Float => "float".to_string(),
+                VerificationTypeInfo::Double => "double".to_string(),
+                VerificationTypeInfo::Long => "long".to_string(),
+                VerificationTypeInfo::Null => "null".to_string(),
+                VerificationTypeInfo::UninitializedThis => "this".to_string(),
+                VerificationTypeInfo::Object { cpool_index } => {
+                    format!("class {}", check_name(self.class_name(*cpool_index)))
+                }
+                VerificationTypeInfo::Uninitialized { offset } => {
+                    format!("uninitialized {}", offset)
+                }
+            })
+            .collect::<Vec<_>>();
+        self.println(&format!("  {} = [ {} ]", name, types.join(", ")));
+    }
+
+    fn annotation(&mut self, index: usize, annotation: &Annotation) {
+        self.println(&format!("{}: {}", index, self.raw_annotation(annotation)));
+        self.indent += 2;
+        self.resolved_annotation(annotation);
+        self.indent -= 2;
+    }
+
+    fn type_annotation(&mut self, index: usize, type_annotation: &TypeAnnotation) {
+        let mut target = target_type_name(type_annotation.target_type).to_string();
+        match &type_annotation.target_info {
+            TargetInfo::TypeParameter {
+                type_parameter_index,
+            } => target.push_str(&format!(", param_index={}", type_parameter_index)),
+            TargetInfo::Supertype { supertype_index } => {
+                target.push_str(&format!(", type_index={}", supertype_index))
+            }
+            TargetInfo::TypeParameterBound {
+                type_parameter_index,
+                bound_index,
+            } => target.push_str(&format!(
+                ", param_index={}, bound_index={}",
+                type_parameter_index, bound_index
+            )),
+            TargetInfo::Empty => (),
+            TargetInfo::FormalParameter {
+                formal_parameter_index,
+            } => target.push_str(&format!(", param_index={}", formal_parameter_index)),
+            TargetInfo::Throws { throws_type_index } => {
+                target.push_str(&format!(", type_index={}", throws_type_index))
+            }
+            TargetInfo::LocalVar(table) => {
+                let table = table
+                    .iter()
+                    .map(|entry| {
+                        format!(
+                            "start_pc={}, length={}, index={}",
+                            entry.start_pc, entry.length, entry.index
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                target.push_str(&format!(", {{{}}}", table.join("; ")));
+            }
+            TargetInfo::Catch {
+                exception_table_index,
+            } => target.push_str(&format!(", exception_index={}", exception_table_index)),
+            TargetInfo::Offset { offset } => target.push_str(&format!(", offset={}", offset)),
+            TargetInfo::TypeArgument {
+                offset,
+                type_argument_index,
+            } => target.push_str(&format!(
+                ", offset={}, type_index={}",
+                offset, type_argument_index
+            )),
+        }
+
+        if !type_annotation.target_path.is_empty() {
+            let location = type_annotation
+                .target_path
+                .iter()
+                .map(|entry| match entry.type_path_kind {
+                    0 => "ARRAY".to_string(),
+                    1 => "INNER_TYPE".to_string(),
+                    2 => "WILDCARD".to_string(),
+                    _ => format!("TYPE_ARGUMENT({})", entry.type_argument_index),
+                })
+                .collect::<Vec<_>>();
+            target.push_str(&format!(", location=[{}]", location.join(", ")));
+        }
+
+        let annotation = &type_annotation.annotation;
+        self.println(&format!(
+            "{}: {}: {}",
+            index,
+            self.raw_annotation(annotation),
+            target
+        ));
+        self.indent += 2;
+        self.resolved_annotation(annotation);
+        self.indent -= 2;
+    }
+
+    fn raw_annotation(&self, annotation: &Annotation) -> String {
+        let pairs = annotation
+            .element_value_pairs
+            .iter()
+            .map(|pair| {
+                format!(
+                    "#{}={}",
+                    pair.element_name_index,
+                    self.raw_element_value(&pair.value)
+                )
+            })
+            .collect::<Vec<_>>();
+        format!("#{}({})", annotation.type_index, pairs.join(","))
+    }
+
+    fn raw_element_value(&self, value: &ElementValue) -> String {
+        match value {
+            ElementValue::Const {
+                tag,
+                const_value_index,
+            } => format!("{}#{}", *tag as char, const_value_index),
+            ElementValue::Enum {
+                type_name_index,
+                const_name_index,
+            } => format!("e#{}.#{}", type_name_index, const_name_index),
+            ElementValue::Class { class_info_index } => format!("c#{}", class_info_index),
+            ElementValue::Annotation(annotation) => {
+                format!("@{}", self.raw_annotation(annotation))
+            }
+            ElementValue::Array(values) => {
+                let values = values
+                    .iter()
+                    .map(|value| self.raw_element_value(value))
+                    .collect::<Vec<_>>();
+                format!("[{}]", values.join(","))
+            }
+        }
+    }
+
+    fn resolved_annotation(&mut self, annotation: &Annotation) {
+        let type_name = self.annotation_type(annotation);
+        if annotation.element_value_pairs.is_empty() {
+            self.println(&type_name);
+            return;
+        }
+
+        self.println(&format!("{}(", type_name));
+        for pair in &annotation.element_value_pairs {
+            let line = format!(
+                "  {}={}",
+                self.utf8(pair.element_name_index),
+                self.element_value(&pair.value)
+            );
+            self.println(&line);
+        }
+        self.println(")");
+    }
+
+    fn annotation_type(&self, annotation: &Annotation) -> String {
+        let descriptor = self.utf8(annotation.type_index);
+        signature::field_signature(descriptor, false).unwrap_or_else(|| descriptor.to_string())
+    }
+
+    fn element_value(&self, value: &ElementValue) -> String {
+        match value {
+            ElementValue::Const {
+                tag,
+                const_value_index,
+            } => {
+                let index = *const_value_index;
+                match (tag, self.constant(index)) {
+                    (b's', Some(ConstantInfo::Utf8(info))) => {
+                        format!("\"{}\"", escape(&info.value))
+                    }
+                    (b'Z', Some(ConstantInfo::Integer(info))) => (info.value != 0).to_string(),
+                    (b'C', Some(ConstantInfo::Integer(info))) => {
+                        let c = char::from_u32(info.value as u32).unwrap_or_default();
+                        format!("'{}'", escape(&c.to_string()))
+                    }
+                    _ => self.string_value(index, false),
+                }
+            }
+            ElementValue::Enum {
+                type_name_index,
+                const_name_index,
+            } => format!(
+                "{}.{}",
+                self.utf8(*type_name_index),
+                self.utf8(*const_name_index)
+            ),
+            ElementValue::Class { class_info_index } => {
+                format!("class {}", self.utf8(*class_info_index))
+            }
+            ElementValue::Annotation(annotation) => {
+                let pairs = annotation
+                    .element_value_pairs
+                    .iter()
+                    .map(|pair| {
+                        format!(
+                            "{}={}",
+                            self.utf8(pair.element_name_index),
+                            self.element_value(&pair.value)
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                format!("@{}({})", self.annotation_type(annotation), pairs.join(","))
+            }
+            ElementValue::Array(values) => {
+                let values = values
+                    .iter()
+                    .map(|value| self.element_value(value))
+                    .collect::<Vec<_>>();
+                format!("[{}]", values.join(","))
+            }
+        }
+    }
+}
+
+fn modifiers(access_flags: u16) -> String {
+    const MODIFIERS: &[(u16, &str)] = &[
+        (ACC_PUBLIC, "public "),
+        (ACC_PRIVATE, "private "),
+        (ACC_PROTECTED, "protected "),
+        (ACC_STATIC, "static "),
+        (ACC_FINAL, "final "),
+        (ACC_SYNCHRONIZED, "synchronized "),
+        (ACC_VOLATILE, "volatile "),
+        (ACC_TRANSIENT, "transient "),
+        (ACC_NATIVE, "native "),
+        (ACC_ABSTRACT, "abstract "),
+        (ACC_STRICT, "strictfp "),
+    ];
+
+    MODIFIERS
+        .iter()
+        .filter(|(flag, _)| access_flags & flag != 0)
+        .map(|(_, modifier)| *modifier)
+        .collect()
+}
+
+fn flags(access_flags: u16, names: &[(u16, &str)]) -> String {
+    let names = names
+        .iter()
+        .filter(|(flag, _)| access_flags & flag != 0)
+        .map(|(_, name)| *name)
+        .collect::<Vec<_>>();
+
+    format!("flags: ({:#06x}) {}", access_flags, names.join(", "))
+}
+
+fn reference_kind_name(reference_kind: ReferenceKind) -> &'static str {
+    match reference_kind {
+        ReferenceKind::GetField => "REF_getField",
+        ReferenceKind::GetStatic => "REF_getStatic",
+        ReferenceKind::PutField => "REF_putField",
+        ReferenceKind::PutStatic => "REF_putStatic",
+        ReferenceKind::InvokeVirtual => "REF_invokeVirtual",
+        ReferenceKind::InvokeStatic => "REF_invokeStatic",
+        ReferenceKind::InvokeSpecial => "REF_invokeSpecial",
+        ReferenceKind::NewInvokeSpecial => "REF_newInvokeSpecial",
+        ReferenceKind::InvokeInterface => "REF_invokeInterface",
+    }
+}
+
+fn target_type_name(target_type: u8) -> &'static str {
+    match target_type {
+        0x00 => "CLASS_TYPE_PARAMETER",
+        0x01 => "METHOD_TYPE_PARAMETER",
+        0x10 => "CLASS_EXTENDS",
+        0x11 => "CLASS_TYPE_PARAMETER_BOUND",
+        0x12 => "METHOD_TYPE_PARAMETER_BOUND",
+        0x13 => "FIELD",
+        0x14 => "METHOD_RETURN",
+        0x15 => "METHOD_RECEIVER",
+        0x16 => "METHOD_FORMAL_PARAMETER",
+        0x17 => "THROWS",
+        0x40 => "LOCAL_VARIABLE",
+        0x41 => "RESOURCE_VARIABLE",
+        0x42 => "EXCEPTION_PARAMETER",
+        0x43 => "INSTANCEOF",
+        0x44 => "NEW",
+        0x45 => "CONSTRUCTOR_REFERENCE",
+        0x46 => "METHOD_REFERENCE",
+        0x47 => "CAST",
+        0x48 => "CONSTRUCTOR_INVOCATION_TYPE_ARGUMENT",
+        0x49 => "METHOD_INVOCATION_TYPE_ARGUMENT",
+        0x4a => "CONSTRUCTOR_REFERENCE_TYPE_ARGUMENT",
+        0x4b => "METHOD_REFERENCE_TYPE_ARGUMENT",
+        _ => "UNKNOWN",
+    }
+}
+
+fn check_name(name: &str) -> String {
+    let is_start = |c: char| c.is_alphabetic() || c == '_' || c == '$';
+    let is_part = |c: char| is_start(c) || c.is_numeric();
+
+    let mut previous = '/';
+    for c in name.chars() {
+        if (previous == '/' && !is_start(c)) || (c != '/' && !is_part(c)) {
+            return format!("\"{}\"", escape(name));
+        }
+        previous = c;
+    }
+
+    if name.is_empty() {
+        "\"\"".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\u{8}' => escaped.push_str("\\b"),
+            '\u{c}' => escaped.push_str("\\f"),
+            '"' => escaped.push_str("\\\""),
+            '\'' => escaped.push_str("\\'"),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::{Path, PathBuf};
+
+    /// The recorded `javap -v -p` outputs of each set of classes.
+    const RECORDED: &[(&str, &str)] = &[
+        ("golden", "tests/golden/classes"),
+        ("playground", "playground/classes"),
+    ];
+
+    fn recordings(dir: &Path) -> Vec<PathBuf> {
+        let mut paths = vec![];
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                paths.extend(recordings(&path));
+            } else {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+
+    #[test]
+    fn prints_what_javap_prints() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        for (set, classes) in RECORDED {
+            let recorded = root.join("tests/javap").join(set);
+            let paths = recordings(&recorded);
+            assert!(!paths.is_empty());
+
+            for path in paths {
+                let class = path.strip_prefix(&recorded).unwrap();
+                let class = root.join(classes).join(class.with_extension("class"));
+                let bytes = fs::read(&class).unwrap();
+                let class_file = ClassFile::try_from(bytes.as_slice()).unwrap();
+
+                let output = Disassembler::new(&class_file, true).disassemble();
+                let expected = fs::read_to_string(&path).unwrap();
+                assert_eq!(output, expected, "{}", class.display());
+            }
+        }
+    }
+}
@@ -0,0 +1,399 @@
+use crate::class::{Class, OBJECT};
+use crate::method::Method;
+use crate::registry::Registry;
+use crate::signature::simple_method_description;
+use crate::throwable::{Throwable, ABSTRACT_METHOD_ERROR, INCOMPATIBLE_CLASS_CHANGE_ERROR};
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+
+/// A method along with the class that declares it.
+#[derive(Clone, Debug)]
+pub struct VirtualMethod {
+    class: Weak<Class>,
+    pub(crate) method: Rc<Method>,
+    /// The methods that held its vtable entry before, which a method of a
+    /// subclass may still override directly.
+    overridden: Vec<VirtualMethod>,
+}
+
+impl VirtualMethod {
+    fn new(class: &Rc<Class>, method: &Rc<Method>) -> VirtualMethod {
+        VirtualMethod {
+            class: Rc::downgrade(class),
+            method: method.clone(),
+            overridden: vec![],
+        }
+    }
+
+    pub(crate) fn class(&self) -> Rc<Class> {
+        self.class.upgrade().expect("classes are never unloaded")
+    }
+}
+
+/// The method selected in a class for an interface method (JVMS 5.4.6).
+#[derive(Clone, Debug)]
+pub enum ItableEntry {
+    Method(VirtualMethod),
+    /// Several maximally-specific superinterface methods are not abstract.
+    Conflict(Vec<VirtualMethod>),
+}
+
+/// Builds the vtable of a class from the one of its superclass: a method
+/// overriding inherited ones takes over their entries and the index of the
+/// first, any other one is appended.
+pub(crate) fn vtable(
+    this: &Weak<Class>,
+    class: &Class,
+    super_class: Option<&Class>,
+) -> (Vec<VirtualMethod>, HashMap<String, usize>) {
+    let (mut vtable, mut indices) = match super_class {
+        Some(super_class) => (
+            super_class.vtable.clone(),
+            super_class.vtable_indices.clone(),
+        ),
+        None => (vec![], HashMap::new()),
+    };
+
+    let class_name = class.this_class();
+    for (name_and_type, method) in &class.methods {
+        if method.is_static() || method.is_private() || method.is_initializer() {
+            continue;
+        }
+
+        let entry = VirtualMethod {
+            class: this.clone(),
+            method: method.clone(),
+            overridden: vec![],
+        };
+        let mut index = None;
+        for (i, inherited) in vtable.iter_mut().enumerate() {
+            let same_method = inherited.method.name == method.name
+                && inherited.method.descriptor == method.descriptor;
+            if same_method && can_override(&class_name, inherited) {
+                let mut overridden = std::mem::take(&mut inherited.overridden);
+                overridden.push(inherited.clone());
+                *inherited = VirtualMethod {
+                    overridden,
+                    ..entry.clone()
+                };
+                index.get_or_insert(i);
+            }
+        }
+
+        let index = index.unwrap_or_else(|| {
+            vtable.push(entry);
+            vtable.len() - 1
+        });
+        indices.insert(name_and_type.clone(), index);
+    }
+
+    (vtable, indices)
+}
+
+/// Selects the method of a class for every method of its superinterfaces:
+/// the one it declares or inherits from its superclasses, or else the
+/// maximally-specific superinterface method that is not abstract. Methods
+/// with no selection are left out.
+pub(crate) fn itable(class: &Class) -> HashMap<String, ItableEntry> {
+    let mut itable = HashMap::new();
+
+    for interface in &class.superinterfaces {
+        for (name_and_type, method) in &interface.methods {
+            if method.is_static() || method.is_private() || itable.contains_key(name_and_type) {
+                continue;
+            }
+
+            if let Some(&index) = class.vtable_indices.get(name_and_type) {
+                let entry = ItableEntry::Method(class.vtable[index].clone());
+                itable.insert(name_and_type.clone(), entry);
+                continue;
+            }
+
+            let mut defaults = maximally_specific(&class.superinterfaces, name_and_type);
+            defaults.retain(|candidate| !candidate.method.is_abstract());
+            let entry = match defaults.len() {
+                0 => continue,
+                1 => ItableEntry::Method(defaults.remove(0)),
+                _ => ItableEntry::Conflict(defaults),
+            };
+            itable.insert(name_and_type.clone(), entry);
+        }
+    }
+
+    itable
+}
+
+/// The superinterface methods with the name and descriptor that are not
+/// overridden by such a method of a subinterface (JVMS 5.4.3.3).
+pub(crate) fn maximally_specific(
+    superinterfaces: &[Rc<Class>],
+    name_and_type: &str,
+) -> Vec<VirtualMethod> {
+    let declaring = superinterfaces
+        .iter()
+        .filter_map(|interface| {
+            let method = interface.method(name_and_type)?;
+            (!method.is_static() && !method.is_private()).then_some((interface, method))
+        })
+        .collect::<Vec<_>>();
+
+    declaring
+        .iter()
+        .filter(|(interface, _)| {
+            !declaring.iter().any(|(other, _)| {
+                other
+                    .superinterfaces
+                    .iter()
+                    .any(|superinterface| Rc::ptr_eq(superinterface, interface))
+            })
+        })
+        .map(|(interface, method)| VirtualMethod::new(interface, method))
+        .collect()
+}
+
+/// Selects the method to invoke on an instance of the receiver class for the
+/// resolved method (JVMS 5.4.6).
+pub(crate) fn select(
+    receiver: &Class,
+    class: &Rc<Class>,
+    method: &Rc<Method>,
+) -> Result<(Rc<Class>, Rc<Method>), Throwable> {
+    if method.is_private() {
+        return Ok((class.clone(), method.clone()));
+    }
+
+    let name_and_type = method.name_and_type();
+    let selected = if class.is_interface() {
+        match receiver.itable.get(&name_and_type) {
+            Some(ItableEntry::Method(selected)) => Some(selected),
+            Some(ItableEntry::Conflict(candidates)) => return Err(conflict(candidates)),
+            None => None,
+        }
+    } else {
+        // nothing to select for a method that is not virtual, or a receiver
+        // that is not a subclass
+        class
+            .vtable_indices
+            .get(&name_and_type)
+            .and_then(|&index| receiver.vtable.get(index))
+    };
+
+    match selected {
+        Some(selected) if !selected.method.is_abstract() => {
+            Ok((selected.class(), selected.method.clone()))
+        }
+        _ => Err(abstract_method_error(receiver, class, method)),
+    }
+}
+
+/// Selects the method for an `invokespecial` of the resolved method,
+/// looking it up from the class (JVMS 6.5 invokespecial).
+pub(crate) fn select_special(
+    registry: &Registry,
+    class: &Rc<Class>,
+    resolved_class: &Rc<Class>,
+    method: &Rc<Method>,
+) -> Result<(Rc<Class>, Rc<Method>), Throwable> {
+    let name_and_type = method.name_and_type();
+    let declared = if class.is_interface() {
+        let declared = class
+            .method(&name_and_type)
+            .map(|method| (class.clone(), method.clone()));
+        declared.or_else(|| object_method(registry, &name_and_type))
+    } else {
+        lookup(registry, class, &name_and_type)
+    };
+
+    let selected = match declared {
+        Some(declared) => declared,
+        None => {
+            let mut defaults = maximally_specific(&class.superinterfaces, &name_and_type);
+            defaults.retain(|candidate| !candidate.method.is_abstract());
+            match defaults.as_slice() {
+                [selected] => (selected.class(), selected.method.clone()),
+                [] => return Err(abstract_method_error(class, resolved_class, method)),
+                _ => return Err(conflict(&defaults)),
+            }
+        }
+    };
+
+    if selected.1.is_abstract() {
+        return Err(abstract_method_error(class, resolved_class, method));
+    }
+    Ok(selected)
+}
+
+/// Looks the method up in the class and then its superclasses.
+pub(crate) fn lookup(
+    registry: &Registry,
+    class: &Rc<Class>,
+    name_and_type: &str,
+) -> Option<(Rc<Class>, Rc<Method>)> {
+    let mut current = Some(class.clone());
+    while let Some(class) = current {
+        if let Some(method) = class.method(name_and_type) {
+            return Some((class.clone(), method.clone()));
+        }
+        current = class
+            .super_class()
+            .and_then(|super_class| registry.class(&super_class).cloned());
+    }
+    None
+}
+
+/// The public instance method of `java.lang.Object`, which interfaces
+/// implicitly declare (JLS 9.2).
+pub(crate) fn object_method(
+    registry: &Registry,
+    name_and_type: &str,
+) -> Option<(Rc<Class>, Rc<Method>)> {
+    let object = registry.class(OBJECT)?;
+    let method = object.method(name_and_type)?;
+    (method.is_public() && !method.is_static()).then(|| (object.clone(), method.clone()))
+}
+
+/// Whether a method of the class can override the inherited one, or any it
+/// overrides in turn (JVMS 5.4.5): a package-private method overriding a
+/// public one leaves the public one overridable from other packages.
+fn can_override(class_name: &str, inherited: &VirtualMethod) -> bool {
+    let can_override_directly = |inherited: &VirtualMethod| {
+        inherited.method.is_public()
+            || inherited.method.is_protected()
+            || package(class_name) == package(&inherited.class().this_class())
+    };
+    can_override_directly(inherited) || inherited.overridden.iter().any(can_override_directly)
+}
+
+fn package(class_name: &str) -> &str {
+    class_name
+        .rsplit_once('/')
+        .map(|(package, _)| package)
+        .unwrap_or_default()
+}
+
+fn abstract_method_error(receiver: &Class, class: &Class, method: &Method) -> Throwable {
+    let mut description = simple_method_description(&method.name_and_type());
+    if method.is_abstract() {
+        description.insert_str(1, "abstract ");
+    }
+    let kind = if class.is_interface() {
+        "interface"
+    } else if class.is_abstract() {
+        "abstract class"
+    } else {
+        "class"
+    };
+
+    let message = format!(
+        "Receiver class {} does not define or inherit an implementation of the resolved method {} of {} {}.",
+        receiver.this_class().replace('/', "."),
+        description,
+        kind,
+        class.this_class().replace('/', ".")
+    );
+    Throwable::new(ABSTRACT_METHOD_ERROR, &message)
+}
+
+fn conflict(candidates: &[VirtualMethod]) -> Throwable {
+    let methods = candidates
+        .iter()
+        .map(|candidate| {
+            let class_name = candidate.class().this_class();
+            format!("{}.{}", class_name, candidate.method.name)
+        })
+        .collect::<Vec<_>>();
+    let message = format!("Conflicting default methods: {}", methods.join(" "));
+    Throwable::new(INCOMPATIBLE_CLASS_CHANGE_ERROR, &message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::class_file::{
+        ClassFile, ConstantClassInfo, ConstantInfo, ConstantUtf8Info, MethodInfo,
+    };
+
+    const ACC_PUBLIC: u16 = 0x0001;
+    const ACC_NATIVE: u16 = 0x0100;
+
+    /// Links a class with native `m()V` methods of the access flags, which
+    /// need no code.
+    fn class(name: &str, super_class: Option<&Rc<Class>>, methods: &[(&str, u16)]) -> Rc<Class> {
+        let mut constant_pool = vec![];
+        let mut utf8 = |value: &str| {
+            constant_pool.push(ConstantInfo::Utf8(ConstantUtf8Info {
+                value: value.to_string(),
+                bytes: None,
+            }));
+            constant_pool.len() as u16
+        };
+        let name_index = utf8(name);
+        let super_name_index = utf8(&super_class.map_or(OBJECT.to_string(), |c| c.this_class()));
+        let descriptor_index = utf8("()V");
+        let methods = methods
+            .iter()
+            .map(|&(method, access_flags)| MethodInfo {
+                offset: 0,
+                access_flags: access_flags | ACC_NATIVE,
+                name_index: utf8(method),
+                descriptor_index,
+                attributes: vec![],
+            })
+            .collect();
+        for name_index in [name_index, super_name_index] {
+            constant_pool.push(ConstantInfo::Class(ConstantClassInfo { name_index }));
+        }
+
+        let class_file = ClassFile {
+            magic: 0,
+            minor_version: 0,
+            major_version: 0,
+            this_class: constant_pool.len() as u16 - 1,
+            super_class: constant_pool.len() as u16,
+            constant_pool,
+            access_flags: ACC_PUBLIC,
+            interfaces: Box::new([]),
+            fields: vec![],
+            methods,
+            attributes: vec![],
+        };
+        let mut class = Class::try_from(class_file).unwrap();
+        Rc::new_cyclic(|this| {
+            class.link(this, super_class, &[]);
+            class
+        })
+    }
+
+    fn selected(receiver: &Class, class: &Rc<Class>) -> String {
+        let method = class.method("m:()V").unwrap();
+        let (class, _) = select(receiver, class, method).unwrap();
+        class.this_class()
+    }
+
+    #[test]
+    fn overrides_package_private_methods_transitively() {
+        let a = class("a/A", None, &[("m", 0)]);
+        // b/B cannot override a/A.m, and a/C overrides both
+        let b = class("b/B", Some(&a), &[("m", ACC_PUBLIC)]);
+        let c = class("a/C", Some(&b), &[("m", 0)]);
+        // b/D cannot override a/C.m, but it can override the b/B.m it overrides
+        let d = class("b/D", Some(&c), &[("m", 0)]);
+
+        assert_eq!(selected(&d, &a), "a/C");
+        assert_eq!(selected(&d, &b), "b/D");
+        assert_eq!(selected(&d, &c), "a/C");
+        assert_eq!(selected(&c, &b), "a/C");
+    }
+
+    #[test]
+    fn selects_nothing_for_methods_outside_the_vtable() {
+        let a = class("a/A", None, &[("<init>", 0), ("m", 0)]);
+        let b = class("b/B", None, &[]);
+
+        let initializer = a.method("<init>:()V").unwrap();
+        let error = select(&a, &a, initializer).unwrap_err();
+        assert_eq!(error.class_name, ABSTRACT_METHOD_ERROR);
+        let error = select(&b, &a, a.method("m:()V").unwrap()).unwrap_err();
+        assert_eq!(error.class_name, ABSTRACT_METHOD_ERROR);
+    }
+}
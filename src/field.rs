@@ -0,0 +1,36 @@
+use crate::class_file::FieldInfo;
+use crate::constant_pool::ConstantPool;
+
+const ACC_STATIC: u16 = 0x0008;
+const ACC_FINAL: u16 = 0x0010;
+
+#[derive(Debug)]
+pub struct Field {
+    pub(crate) access_flags: u16,
+    pub(crate) name: String,
+    pub(crate) descriptor: String,
+    pub(crate) constant_value: Option<u16>,
+}
+
+impl Field {
+    pub(crate) fn is_static(&self) -> bool {
+        self.access_flags & ACC_STATIC != 0
+    }
+
+    pub(crate) fn is_final(&self) -> bool {
+        self.access_flags & ACC_FINAL != 0
+    }
+
+    pub(crate) fn name_and_type(&self) -> String {
+        format!("{}:{}", self.name, self.descriptor)
+    }
+
+    pub(crate) fn from(field_info: FieldInfo, constant_pool: &ConstantPool) -> Field {
+        Field {
+            access_flags: field_info.access_flags,
+            name: constant_pool.utf8(field_info.name_index),
+            descriptor: constant_pool.utf8(field_info.descriptor_index),
+            constant_value: field_info.constant_value(),
+        }
+    }
+}
@@ -0,0 +1,516 @@
+use std::cmp::Ordering;
+
+const EXP_SHIFT: i32 = 52;
+const FRACT_HOB: u64 = 1 << EXP_SHIFT;
+const SIGNIF_BIT_MASK: u64 = FRACT_HOB - 1;
+const EXP_ONE: u64 = 0x3ff << EXP_SHIFT;
+const EXP_BIAS: i32 = 1023;
+const MAX_SMALL_BIN_EXP: i32 = 62;
+const MIN_SMALL_BIN_EXP: i32 = -21;
+
+const SINGLE_EXP_SHIFT: i32 = 23;
+const SINGLE_FRACT_HOB: u32 = 1 << SINGLE_EXP_SHIFT;
+const SINGLE_SIGNIF_BIT_MASK: u32 = SINGLE_FRACT_HOB - 1;
+const SINGLE_EXP_BIAS: i32 = 127;
+
+/// The number of decimal digits of a long value multiplied by the power of
+/// two that cannot be significant.
+const INSIGNIFICANT_DIGITS: [i32; 64] = [
+    0, 0, 0, 0, 1, 1, 1, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 5, 5, 5, 6, 6, 6, 6, 7, 7, 7, 8, 8, 8, 9, 9,
+    9, 9, 10, 10, 10, 11, 11, 11, 12, 12, 12, 12, 13, 13, 13, 14, 14, 14, 15, 15, 15, 15, 16, 16,
+    16, 17, 17, 17, 18, 18, 18, 19,
+];
+/// The number of bits in the powers of five that fit in a long.
+const N_5_BITS: [i32; 27] = [
+    0, 3, 5, 7, 10, 12, 14, 17, 19, 21, 24, 26, 28, 31, 33, 35, 38, 40, 42, 45, 47, 49, 52, 54, 56,
+    59, 61,
+];
+
+/// Formats the float the way `Float.toString` does.
+pub(crate) fn float_to_string(x: f32) -> String {
+    if !x.is_finite() {
+        return special_string(x as f64);
+    }
+
+    let bits = x.to_bits();
+    let negative = bits >> 31 != 0;
+    let mut fract_bits = bits & SINGLE_SIGNIF_BIT_MASK;
+    let mut bin_exp = (bits >> SINGLE_EXP_SHIFT & 0xff) as i32;
+    let significant_bits = if bin_exp == 0 {
+        if fract_bits == 0 {
+            return zero_string(negative);
+        }
+        let leading_zeros = fract_bits.leading_zeros() as i32;
+        let shift = leading_zeros - (31 - SINGLE_EXP_SHIFT);
+        fract_bits <<= shift;
+        bin_exp = 1 - shift;
+        32 - leading_zeros
+    } else {
+        fract_bits |= SINGLE_FRACT_HOB;
+        SINGLE_EXP_SHIFT + 1
+    };
+
+    let fract_bits = (fract_bits as u64) << (EXP_SHIFT - SINGLE_EXP_SHIFT);
+    dtoa(bin_exp - SINGLE_EXP_BIAS, fract_bits, significant_bits).to_string(negative)
+}
+
+/// Formats the double the way `Double.toString` does.
+pub(crate) fn double_to_string(x: f64) -> String {
+    if !x.is_finite() {
+        return special_string(x);
+    }
+
+    let bits = x.to_bits();
+    let negative = bits >> 63 != 0;
+    let mut fract_bits = bits & SIGNIF_BIT_MASK;
+    let mut bin_exp = (bits >> EXP_SHIFT & 0x7ff) as i32;
+    let significant_bits = if bin_exp == 0 {
+        if fract_bits == 0 {
+            return zero_string(negative);
+        }
+        let leading_zeros = fract_bits.leading_zeros() as i32;
+        let shift = leading_zeros - (63 - EXP_SHIFT);
+        fract_bits <<= shift;
+        bin_exp = 1 - shift;
+        64 - leading_zeros
+    } else {
+        fract_bits |= FRACT_HOB;
+        EXP_SHIFT + 1
+    };
+
+    dtoa(bin_exp - EXP_BIAS, fract_bits, significant_bits).to_string(negative)
+}
+
+fn special_string(x: f64) -> String {
+    if x.is_nan() {
+        "NaN".to_string()
+    } else if x > 0.0 {
+        "Infinity".to_string()
+    } else {
+        "-Infinity".to_string()
+    }
+}
+
+fn zero_string(negative: bool) -> String {
+    if negative { "-0.0" } else { "0.0" }.to_string()
+}
+
+/// The decimal digits of a value, `0.d1d2... * 10^exponent`.
+struct Decimal {
+    digits: Vec<u8>,
+    exponent: i32,
+}
+
+impl Decimal {
+    fn round_up(&mut self) {
+        let mut i = self.digits.len() - 1;
+        while self.digits[i] == b'9' && i > 0 {
+            self.digits[i] = b'0';
+            i -= 1;
+        }
+        if self.digits[i] == b'9' {
+            self.exponent += 1;
+            self.digits[0] = b'1';
+        } else {
+            self.digits[i] += 1;
+        }
+    }
+
+    /// Lays the digits out as plain decimals between 10^-3 and 10^7, and in
+    /// scientific notation otherwise, always with a digit after the point.
+    fn to_string(&self, negative: bool) -> String {
+        let digits = std::str::from_utf8(&self.digits).unwrap();
+        let sign = if negative { "-" } else { "" };
+        let exponent = self.exponent;
+
+        if exponent > 0 && exponent < 8 {
+            let point = exponent as usize;
+            if digits.len() <= point {
+                let zeros = "0".repeat(point - digits.len());
+                format!("{}{}{}.0", sign, digits, zeros)
+            } else {
+                let (integer, fraction) = digits.split_at(point);
+                format!("{}{}.{}", sign, integer, fraction)
+            }
+        } else if exponent <= 0 && exponent > -3 {
+            let zeros = "0".repeat(-exponent as usize);
+            format!("{}0.{}{}", sign, zeros, digits)
+        } else {
+            let (first, rest) = digits.split_at(1);
+            let rest = if rest.is_empty() { "0" } else { rest };
+            format!("{}{}.{}E{}", sign, first, rest, exponent - 1)
+        }
+    }
+}
+
+/// Develops the digits of the value `fract_bits * 2^(bin_exp - 52)`, whose
+/// highest bit is set, the way `FloatingDecimal.dtoa` of JDK 17 does, quirks
+/// included: the digits stop as soon as they identify the value within half
+/// an ulp, which is not always the shortest way.
+fn dtoa(bin_exp: i32, fract_bits: u64, significant_bits: i32) -> Decimal {
+    let tail_zeros = fract_bits.trailing_zeros() as i32;
+    let fract_bits_count = EXP_SHIFT + 1 - tail_zeros;
+    let tiny_bits = (fract_bits_count - bin_exp - 1).max(0);
+
+    // an integer that fits in a long
+    if (MIN_SMALL_BIN_EXP..=MAX_SMALL_BIN_EXP).contains(&bin_exp)
+        && tiny_bits == 0
+        && fract_bits_count < 64
+    {
+        let insignificant = if bin_exp > significant_bits {
+            insignificant_digits(bin_exp - significant_bits - 1)
+        } else {
+            0
+        };
+        let value = if bin_exp >= EXP_SHIFT {
+            fract_bits << (bin_exp - EXP_SHIFT)
+        } else {
+            fract_bits >> (EXP_SHIFT - bin_exp)
+        };
+        return long_digits(value, insignificant);
+    }
+
+    // value = B / S * 10^dec_exp, and M is half an ulp scaled like B
+    let mut dec_exp = estimate_dec_exp(fract_bits, bin_exp);
+    let b5 = (-dec_exp).max(0);
+    let mut b2 = b5 + tiny_bits + bin_exp;
+    let s5 = dec_exp.max(0);
+    let mut s2 = s5 + tiny_bits;
+    let m5 = b5;
+    let mut m2 = b2 - significant_bits;
+
+    let fract_bits = fract_bits >> tail_zeros;
+    b2 -= fract_bits_count - 1;
+    let common2 = b2.min(s2);
+    b2 -= common2;
+    s2 -= common2;
+    m2 -= common2;
+    // the next smaller value is only half as far below a power of two
+    if fract_bits_count == 1 {
+        m2 -= 1;
+    }
+    if m2 < 0 {
+        b2 -= m2;
+        s2 -= m2;
+        m2 = 0;
+    }
+
+    let b_bits = fract_bits_count + b2 + bits_of_pow5(b5);
+    let ten_s_bits = s2 + 1 + bits_of_pow5(s5 + 1);
+    let (mut digits, low, high, low_digit_difference) = if b_bits < 64 && ten_s_bits < 64 {
+        // int arithmetic, wrapping as in Java, if everything fits in 32 bits
+        let wrap: fn(i64) -> i64 = if b_bits < 32 && ten_s_bits < 32 {
+            |x| x as i32 as i64
+        } else {
+            |x| x
+        };
+        let b = wrap(wrap((fract_bits as i64).wrapping_mul(pow5(b5))) << b2);
+        let s = wrap(pow5(s5) << s2);
+        let m = wrap(pow5(m5) << m2);
+        small_digits(b, s, m, wrap, &mut dec_exp)
+    } else {
+        big_digits(fract_bits, b5, b2, s5, s2, m5, m2, &mut dec_exp)
+    };
+    digits.exponent = dec_exp + 1;
+
+    // between the two digits, the closer one, or the even one when halfway
+    if high {
+        let odd = digits.digits.last().unwrap() & 1 != 0;
+        if !low || low_digit_difference > 0 || low_digit_difference == 0 && odd {
+            digits.round_up();
+        }
+    }
+    digits
+}
+
+/// Generates the digits of `b / s` with `i64` or wrapping `i32` arithmetic,
+/// returning them with whether the remainder is within `m` of the lower and
+/// the upper digit, and how far it is from the middle between them.
+fn small_digits(
+    mut b: i64,
+    s: i64,
+    mut m: i64,
+    wrap: fn(i64) -> i64,
+    dec_exp: &mut i32,
+) -> (Decimal, bool, bool, i64) {
+    let mut digits = vec![];
+    let tens = wrap(s.wrapping_mul(10));
+
+    let q = b / s;
+    b = wrap(10 * (b % s));
+    m = wrap(m.wrapping_mul(10));
+    let mut low = b < m;
+    let mut high = wrap(b.wrapping_add(m)) > tens;
+    // the estimate of the exponent was one too high
+    if q == 0 && !high {
+        *dec_exp -= 1;
+    } else {
+        digits.push(b'0' + q as u8);
+    }
+    // scientific notation needs a second digit
+    if *dec_exp < -3 || *dec_exp >= 8 {
+        low = false;
+        high = false;
+    }
+    while !low && !high {
+        let q = b / s;
+        b = wrap(10 * (b % s));
+        m = wrap(m.wrapping_mul(10));
+        if m > 0 {
+            low = b < m;
+            high = wrap(b.wrapping_add(m)) > tens;
+        } else {
+            // m overflowed, so it is certainly larger than b
+            low = true;
+            high = true;
+        }
+        digits.push(b'0' + q as u8);
+    }
+
+    let low_digit_difference = wrap(wrap(b << 1).wrapping_sub(tens));
+    let digits = Decimal {
+        digits,
+        exponent: 0,
+    };
+    (digits, low, high, low_digit_difference)
+}
+
+/// Generates the digits the way `small_digits` does with arbitrary precision,
+/// where the upper digit is also taken when the remainder is exactly `m` from it.
+#[allow(clippy::too_many_arguments)]
+fn big_digits(
+    fract_bits: u64,
+    b5: i32,
+    b2: i32,
+    s5: i32,
+    s2: i32,
+    m5: i32,
+    m2: i32,
+    dec_exp: &mut i32,
+) -> (Decimal, bool, bool, i64) {
+    let mut digits = vec![];
+    let s = BigInteger::pow52(1, s5, s2);
+    let mut b = BigInteger::pow52(fract_bits, b5, b2);
+    let mut m = BigInteger::pow52(1, m5 + 1, m2 + 1);
+    let ten_s = BigInteger::pow52(1, s5 + 1, s2 + 1);
+
+    let q = b.quotient_digit(&s);
+    let mut low = b.cmp(&m) == Ordering::Less;
+    let mut high = ten_s.cmp(&b.add(&m)) != Ordering::Greater;
+    if q == 0 && !high {
+        *dec_exp -= 1;
+    } else {
+        digits.push(b'0' + q);
+    }
+    if *dec_exp < -3 || *dec_exp >= 8 {
+        low = false;
+        high = false;
+    }
+    while !low && !high {
+        let q = b.quotient_digit(&s);
+        m.multiply(10);
+        low = b.cmp(&m) == Ordering::Less;
+        high = ten_s.cmp(&b.add(&m)) != Ordering::Greater;
+        digits.push(b'0' + q);
+    }
+
+    let low_digit_difference = if high && low {
+        b.multiply(2);
+        b.cmp(&ten_s) as i64
+    } else {
+        0
+    };
+    let digits = Decimal {
+        digits,
+        exponent: 0,
+    };
+    (digits, low, high, low_digit_difference)
+}
+
+/// The digits of a long value, rounded to the significant ones.
+fn long_digits(mut value: u64, insignificant: i32) -> Decimal {
+    let mut exponent = 0;
+    if insignificant != 0 {
+        let pow10 = 10u64.pow(insignificant as u32);
+        let residue = value % pow10;
+        value /= pow10;
+        exponent += insignificant;
+        if residue >= pow10 >> 1 {
+            value += 1;
+        }
+    }
+
+    let value = value.to_string();
+    exponent += value.len() as i32;
+    let digits = value.trim_end_matches('0').as_bytes().to_vec();
+    Decimal { digits, exponent }
+}
+
+fn insignificant_digits(p2: i32) -> i32 {
+    if p2 > 1 && p2 < INSIGNIFICANT_DIGITS.len() as i32 {
+        INSIGNIFICANT_DIGITS[p2 as usize]
+    } else {
+        0
+    }
+}
+
+/// Estimates `floor(log10(value))`, sometimes one too high. The constants
+/// are Java's, rounded differently from `LOG10_2`.
+#[allow(clippy::approx_constant)]
+fn estimate_dec_exp(fract_bits: u64, bin_exp: i32) -> i32 {
+    let d2 = f64::from_bits(EXP_ONE | (fract_bits & SIGNIF_BIT_MASK));
+    let d = (d2 - 1.5) * 0.289529654 + 0.176091259 + bin_exp as f64 * 0.301029995663981;
+    d.floor() as i32
+}
+
+fn bits_of_pow5(n: i32) -> i32 {
+    N_5_BITS.get(n as usize).copied().unwrap_or(n * 3)
+}
+
+fn pow5(n: i32) -> i64 {
+    5i64.pow(n as u32)
+}
+
+/// A non-negative integer of 32-bit words, least significant first.
+struct BigInteger {
+    words: Vec<u32>,
+}
+
+impl BigInteger {
+    /// `value * 5^p5 * 2^p2`.
+    fn pow52(value: u64, p5: i32, p2: i32) -> BigInteger {
+        let mut big = BigInteger {
+            words: vec![value as u32, (value >> 32) as u32],
+        };
+        for _ in 0..p5 {
+            big.multiply(5);
+        }
+        big.shift_left(p2 as usize);
+        big
+    }
+
+    fn multiply(&mut self, factor: u32) {
+        let mut carry = 0;
+        for word in &mut self.words {
+            let product = *word as u64 * factor as u64 + carry;
+            *word = product as u32;
+            carry = product >> 32;
+        }
+        if carry != 0 {
+            self.words.push(carry as u32);
+        }
+    }
+
+    fn shift_left(&mut self, bits: usize) {
+        let (words, bits) = (bits / 32, bits % 32);
+        if bits != 0 {
+            let mut carry = 0;
+            for word in &mut self.words {
+                let shifted = (*word as u64) << bits | carry;
+                *word = shifted as u32;
+                carry = shifted >> 32;
+            }
+            if carry != 0 {
+                self.words.push(carry as u32);
+            }
+        }
+        self.words.splice(0..0, vec![0; words]);
+    }
+
+    fn add(&self, other: &BigInteger) -> BigInteger {
+        let length = self.words.len().max(other.words.len());
+        let mut words = Vec::with_capacity(length + 1);
+        let mut carry = 0;
+        for i in 0..length {
+            let sum = *self.words.get(i).unwrap_or(&0) as u64
+                + *other.words.get(i).unwrap_or(&0) as u64
+                + carry;
+            words.push(sum as u32);
+            carry = sum >> 32;
+        }
+        words.push(carry as u32);
+        BigInteger { words }
+    }
+
+    fn subtract(&mut self, other: &BigInteger) {
+        let mut borrow = 0;
+        for i in 0..self.words.len() {
+            let difference =
+                self.words[i] as i64 - *other.words.get(i).unwrap_or(&0) as i64 - borrow;
+            self.words[i] = difference as u32;
+            borrow = i64::from(difference < 0);
+        }
+    }
+
+    /// Returns `self / divisor`, which must be a single digit, and replaces
+    /// `self` with ten times the remainder.
+    fn quotient_digit(&mut self, divisor: &BigInteger) -> u8 {
+        let mut q = 0;
+        while self.cmp(divisor) != Ordering::Less {
+            self.subtract(divisor);
+            q += 1;
+        }
+        self.multiply(10);
+        q
+    }
+
+    fn cmp(&self, other: &BigInteger) -> Ordering {
+        let length = self.words.len().max(other.words.len());
+        (0..length)
+            .rev()
+            .map(|i| {
+                let x = self.words.get(i).unwrap_or(&0);
+                x.cmp(other.words.get(i).unwrap_or(&0))
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_floats() {
+        assert_eq!(float_to_string(f32::MIN_POSITIVE), "1.17549435E-38");
+        assert_eq!(float_to_string(f32::MAX), "3.4028235E38");
+        assert_eq!(float_to_string(0.1), "0.1");
+        assert_eq!(float_to_string(0.001), "0.001");
+        assert_eq!(float_to_string(1.0e-4), "1.0E-4");
+        assert_eq!(float_to_string(1.0e7), "1.0E7");
+        assert_eq!(float_to_string(123456.7), "123456.7");
+        assert_eq!(float_to_string(1.0 / 3.0), "0.33333334");
+        assert_eq!(float_to_string(-0.0), "-0.0");
+        assert_eq!(float_to_string(f32::NEG_INFINITY), "-Infinity");
+        assert_eq!(float_to_string(f32::NAN), "NaN");
+    }
+
+    #[test]
+    fn formats_subnormal_floats() {
+        assert_eq!(float_to_string(f32::from_bits(1)), "1.4E-45");
+        assert_eq!(float_to_string(f32::from_bits(0x10)), "2.24E-44");
+        assert_eq!(float_to_string(f32::from_bits(0x7fffff)), "1.1754942E-38");
+    }
+
+    #[test]
+    fn formats_doubles() {
+        assert_eq!(double_to_string(f64::from_bits(1)), "4.9E-324");
+        assert_eq!(
+            double_to_string(f64::MIN_POSITIVE),
+            "2.2250738585072014E-308"
+        );
+        assert_eq!(double_to_string(f64::MAX), "1.7976931348623157E308");
+        assert_eq!(double_to_string(100.0), "100.0");
+        assert_eq!(double_to_string(0.1 + 0.2), "0.30000000000000004");
+        assert_eq!(double_to_string(2.0 / 3.0), "0.6666666666666666");
+        assert_eq!(double_to_string(0.002), "0.002");
+        assert_eq!(double_to_string(f64::INFINITY), "Infinity");
+    }
+
+    #[test]
+    fn keeps_digits_beyond_the_shortest_as_jdk_17_does() {
+        assert_eq!(float_to_string(f32::from_bits(0x4f083ce4)), "2.2856919E9");
+        assert_eq!(double_to_string(1.0e23), "9.999999999999999E22");
+    }
+}
@@ -1,25 +1,35 @@
-use crate::constant_pool::ConstantPool;
+use crate::class::Class;
 use crate::method::Method;
 use crate::r#type::Type;
-use crate::r#type::Type::{Double, Float, Int, Long, Null};
+use crate::r#type::Type::Null;
+use std::rc::Rc;
+
+const SLOT_SIZE: usize = 8;
+const FRAME_HEADER_SLOTS: usize = 12;
 
 #[derive(Debug)]
-pub struct Frame<'a> {
-    pub(crate) code: &'a Vec<u8>,
-    pub(crate) constant_pool: &'a ConstantPool,
+pub struct Frame {
+    pub(crate) class: Rc<Class>,
+    pub(crate) method: Rc<Method>,
     pub(crate) locals: Vec<Type>,
     pub(crate) operand_stack: Vec<Type>,
     pub(crate) return_address: usize,
 }
 
-impl Frame<'_> {
-    pub(crate) fn from<'a>(method: &'a Method, constant_pool: &'a ConstantPool) -> Frame<'a> {
+impl Frame {
+    pub(crate) fn from(method: Rc<Method>, class: Rc<Class>) -> Frame {
         Frame {
-            code: &method.code,
-            constant_pool,
             locals: vec![Null; method.max_locals],
             operand_stack: Vec::with_capacity(method.max_stack),
+            class,
+            method,
             return_address: 0,
         }
     }
+
+    /// Approximate footprint of the frame on a native interpreter stack,
+    /// used to enforce the thread stack size.
+    pub(crate) fn size(&self) -> usize {
+        (self.locals.len() + self.operand_stack.capacity() + FRAME_HEADER_SLOTS) * SLOT_SIZE
+    }
 }
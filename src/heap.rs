@@ -0,0 +1,140 @@
+use crate::class::Class;
+use crate::r#type::Type;
+use std::rc::Rc;
+
+const SLOT_SIZE: usize = 8;
+const OBJECT_HEADER_SIZE: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Reference(usize);
+
+#[derive(Debug)]
+pub struct Object {
+    pub(crate) class: Rc<Class>,
+    /// Instance fields by slot, or the components of an array.
+    pub(crate) fields: Vec<Type>,
+}
+
+impl Object {
+    /// Approximate footprint of the object, used to enforce the maximum
+    /// heap size.
+    pub(crate) fn size(&self) -> usize {
+        OBJECT_HEADER_SIZE + self.fields.len() * SLOT_SIZE
+    }
+}
+
+/// The objects, which a mark-and-sweep collector reclaims once they are no
+/// longer reachable. Objects are never moved, so a reference stays valid for
+/// as long as the object is alive, and the slots of the reclaimed ones are
+/// reused.
+#[derive(Debug, Default)]
+pub struct Heap {
+    objects: Vec<Option<Object>>,
+    free_slots: Vec<usize>,
+    /// The total size of the objects.
+    used: usize,
+}
+
+impl Heap {
+    /// Allocates an instance of the class with every field set to its default value.
+    pub(crate) fn allocate(&mut self, class: Rc<Class>) -> Reference {
+        let fields = class.instance_layout.clone();
+        self.add(Object { class, fields })
+    }
+
+    /// Allocates an array of the class with every component set to its default value.
+    pub(crate) fn allocate_array(&mut self, class: Rc<Class>, length: usize) -> Reference {
+        let component_type = class.component_type().expect("not an array class");
+        let fields = vec![Type::zero(&component_type); length];
+        self.add(Object { class, fields })
+    }
+
+    /// Allocates a shallow copy of the object.
+    pub(crate) fn copy(&mut self, reference: Reference) -> Reference {
+        let object = self.get(reference);
+        let (class, fields) = (object.class.clone(), object.fields.clone());
+        self.add(Object { class, fields })
+    }
+
+    fn add(&mut self, object: Object) -> Reference {
+        self.used += object.size();
+        match self.free_slots.pop() {
+            Some(index) => {
+                self.objects[index] = Some(object);
+                Reference(index)
+            }
+            None => {
+                self.objects.push(Some(object));
+                Reference(self.objects.len() - 1)
+            }
+        }
+    }
+
+    /// The size an instance of the class takes up.
+    pub(crate) fn instance_size(class: &Class) -> usize {
+        OBJECT_HEADER_SIZE + class.instance_layout.len() * SLOT_SIZE
+    }
+
+    /// The size an array of the length takes up.
+    pub(crate) fn array_size(length: usize) -> usize {
+        OBJECT_HEADER_SIZE.saturating_add(length.saturating_mul(SLOT_SIZE))
+    }
+
+    pub(crate) fn used(&self) -> usize {
+        self.used
+    }
+
+    /// Reclaims the objects that are not reachable from the roots, following
+    /// the references in the fields of the reachable ones, and returns the
+    /// number of bytes freed.
+    pub(crate) fn collect(&mut self, roots: impl IntoIterator<Item = Reference>) -> usize {
+        let mut marked = vec![false; self.objects.len()];
+        let mut pending = vec![];
+        for Reference(index) in roots {
+            if !marked[index] {
+                marked[index] = true;
+                pending.push(index);
+            }
+        }
+        while let Some(index) = pending.pop() {
+            let object = self.objects[index].as_ref().unwrap();
+            for field in &object.fields {
+                if let &Type::Reference(Reference(index)) = field {
+                    if !marked[index] {
+                        marked[index] = true;
+                        pending.push(index);
+                    }
+                }
+            }
+        }
+
+        let used = self.used;
+        for (index, slot) in self.objects.iter_mut().enumerate() {
+            if marked[index] {
+                continue;
+            }
+            if let Some(object) = slot.take() {
+                self.used -= object.size();
+                self.free_slots.push(index);
+            }
+        }
+        used - self.used
+    }
+
+    /// The hash code of the object that `Object.hashCode` returns.
+    pub(crate) fn identity_hash_code(&self, reference: Reference) -> i32 {
+        reference.0 as i32
+    }
+
+    pub(crate) fn get(&self, reference: Reference) -> &Object {
+        self.objects[reference.0]
+            .as_ref()
+            .expect("reclaimed object")
+    }
+
+    pub(crate) fn get_mut(&mut self, reference: Reference) -> &mut Object {
+        self.objects[reference.0]
+            .as_mut()
+            .expect("reclaimed object")
+    }
+}
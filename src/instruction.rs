@@ -0,0 +1,691 @@
+use crate::bytecode::{instruction_length, switch_operands};
+use crate::call_site::CallSite;
+use crate::class::array_class_name;
+use crate::class_file::{ClassFormatError, ClassFormatErrorKind};
+use crate::constant_pool::ConstantPool;
+use crate::descriptor::MethodDescriptor;
+use crate::method_handle::METHOD_HANDLE;
+use crate::r#type::Type;
+use crate::thread::*;
+use std::cell::OnceCell;
+
+const T_BOOLEAN: u8 = 4;
+const T_CHAR: u8 = 5;
+const T_FLOAT: u8 = 6;
+const T_DOUBLE: u8 = 7;
+const T_BYTE: u8 = 8;
+const T_SHORT: u8 = 9;
+const T_INT: u8 = 10;
+const T_LONG: u8 = 11;
+
+/// A field or method the constant pool refers to.
+#[derive(Debug)]
+pub struct MemberRef {
+    pub(crate) class_name: String,
+    pub(crate) name_and_type: String,
+    /// Whether it is an interface method, which `invokespecial` and
+    /// `invokestatic` may refer to since Java 8.
+    pub(crate) interface: bool,
+}
+
+/// An instruction decoded from the code of a method, with the branch targets
+/// being indices of instructions and the constant pool operands resolved to
+/// what they refer to.
+#[derive(Debug)]
+pub enum Instruction {
+    Nop,
+    /// `aconst_null`, `iconst_<i>`, `bipush`, `sipush`, `ldc` of a number and
+    /// the like.
+    Const(Type),
+    /// `ldc` of a string, class, method type, method handle or dynamically
+    /// computed constant, with the index of the constant pool entry, resolved
+    /// on its first execution.
+    Ldc(u16),
+    /// `iload`, `aload_<n>` and the like.
+    Load(usize),
+    /// `istore`, `astore_<n>` and the like.
+    Store(usize),
+    /// `iaload`, `aaload` and the like.
+    ArrayLoad,
+    /// `iastore`, `bastore` and the like, except for `aastore`.
+    ArrayStore,
+    Aastore,
+    Pop,
+    Pop2,
+    Dup,
+    DupX1,
+    DupX2,
+    Dup2,
+    Dup2X1,
+    Dup2X2,
+    Swap,
+    Iadd,
+    Ladd,
+    Fadd,
+    Dadd,
+    Isub,
+    Lsub,
+    Fsub,
+    Dsub,
+    Imul,
+    Lmul,
+    Fmul,
+    Dmul,
+    Idiv,
+    Ldiv,
+    Fdiv,
+    Ddiv,
+    Irem,
+    Lrem,
+    Frem,
+    Drem,
+    Ineg,
+    Lneg,
+    Fneg,
+    Dneg,
+    Ishl,
+    Lshl,
+    Ishr,
+    Lshr,
+    Iushr,
+    Lushr,
+    Iand,
+    Land,
+    Ior,
+    Lor,
+    Ixor,
+    Lxor,
+    Iinc(usize, i32),
+    I2l,
+    I2f,
+    I2d,
+    L2i,
+    L2f,
+    L2d,
+    F2i,
+    F2l,
+    F2d,
+    D2i,
+    D2l,
+    D2f,
+    I2b,
+    I2c,
+    I2s,
+    Lcmp,
+    Fcmpl,
+    Fcmpg,
+    Dcmpl,
+    Dcmpg,
+    Ifeq(usize),
+    Ifne(usize),
+    Iflt(usize),
+    Ifge(usize),
+    Ifgt(usize),
+    Ifle(usize),
+    IfIcmpeq(usize),
+    IfIcmpne(usize),
+    IfIcmplt(usize),
+    IfIcmpge(usize),
+    IfIcmpgt(usize),
+    IfIcmple(usize),
+    IfAcmpeq(usize),
+    IfAcmpne(usize),
+    /// `goto` and `goto_w`.
+    Goto(usize),
+    /// `jsr` and `jsr_w`.
+    Jsr(usize),
+    Ret(usize),
+    TableSwitch {
+        default: usize,
+        low: i32,
+        targets: Vec<usize>,
+    },
+    /// The pairs are sorted by their keys (JVMS 6.5 lookupswitch).
+    LookupSwitch {
+        default: usize,
+        pairs: Vec<(i32, usize)>,
+    },
+    /// `ireturn`, `areturn` and the like.
+    ReturnValue,
+    Return,
+    GetStatic(MemberRef),
+    PutStatic(MemberRef),
+    GetField(MemberRef),
+    PutField(MemberRef),
+    InvokeVirtual(MemberRef),
+    InvokeSpecial(MemberRef),
+    InvokeStatic(MemberRef),
+    InvokeInterface(MemberRef),
+    /// `invokevirtual` of `MethodHandle.invokeExact` or `invoke`, which are
+    /// signature polymorphic (JVMS 2.9.3), with the descriptor of the call
+    /// site.
+    InvokeHandle {
+        descriptor: MethodDescriptor,
+        exact: bool,
+    },
+    /// `invokedynamic`, with the index of the constant pool entry and the
+    /// call site it links to on its first execution.
+    InvokeDynamic {
+        index: u16,
+        descriptor: MethodDescriptor,
+        call_site: OnceCell<CallSite>,
+    },
+    New(String),
+    /// `newarray` and `anewarray`, with the name of the array class.
+    NewArray(String),
+    ArrayLength,
+    Athrow,
+    /// `monitorenter` and `monitorexit`, which only check the reference for
+    /// null as there is a single thread.
+    Monitor,
+    CheckCast(String),
+    InstanceOf(String),
+    MultiANewArray(String, usize),
+    IfNull(usize),
+    IfNonNull(usize),
+}
+
+/// Decodes the code of a method along with the index of the instruction at
+/// every offset of it, `None` in the middle of an instruction.
+pub(crate) fn decode(
+    code: &[u8],
+    constant_pool: &ConstantPool,
+) -> Result<(Vec<Instruction>, Vec<Option<usize>>), ClassFormatError> {
+    let mut indices = vec![None; code.len() + 1];
+    let mut pc = 0;
+    let mut count = 0;
+    while pc < code.len() {
+        indices[pc] = Some(count);
+        pc += instruction_length(code, pc);
+        count += 1;
+    }
+    if pc > code.len() {
+        let kind = ClassFormatErrorKind::UnexpectedEof {
+            expected: pc,
+            actual: code.len(),
+        };
+        return Err(ClassFormatError::new(code.len(), kind));
+    }
+    indices[code.len()] = Some(count);
+
+    let mut decoder = Decoder {
+        code,
+        constant_pool,
+        indices: &indices,
+        address: 0,
+        pc: 0,
+    };
+    let mut instructions = Vec::with_capacity(count);
+    while decoder.pc < code.len() {
+        instructions.push(decoder.instruction()?);
+    }
+    Ok((instructions, indices))
+}
+
+struct Decoder<'a> {
+    code: &'a [u8],
+    constant_pool: &'a ConstantPool,
+    indices: &'a [Option<usize>],
+    /// The offset of the instruction being decoded.
+    address: usize,
+    pc: usize,
+}
+
+impl Decoder<'_> {
+    fn instruction(&mut self) -> Result<Instruction, ClassFormatError> {
+        self.address = self.pc;
+        let opcode = self.read_u8()?;
+
+        let instruction = match opcode {
+            NOP => Instruction::Nop,
+            ACONST_NULL => Instruction::Const(Type::Null),
+            ICONST_M1..=ICONST_5 => Instruction::Const(Type::Int(opcode as i32 - ICONST_0 as i32)),
+            LCONST_0 | LCONST_1 => Instruction::Const(Type::Long((opcode - LCONST_0) as i64)),
+            FCONST_0..=FCONST_2 => Instruction::Const(Type::Float((opcode - FCONST_0) as f32)),
+            DCONST_0 | DCONST_1 => Instruction::Const(Type::Double((opcode - DCONST_0) as f64)),
+            BIPUSH => Instruction::Const(Type::Int(self.read_u8()? as i8 as i32)),
+            SIPUSH => Instruction::Const(Type::Int(self.read_u16()? as i16 as i32)),
+            LDC => {
+                let index = self.read_u8()? as usize;
+                self.constant(opcode, index)?
+            }
+            LDC_W | LDC2_W => {
+                let index = self.read_u16()? as usize;
+                self.constant(opcode, index)?
+            }
+            ILOAD..=ALOAD => Instruction::Load(self.read_u8()? as usize),
+            ILOAD_0..=ALOAD_3 => Instruction::Load(((opcode - ILOAD_0) % 4) as usize),
+            IALOAD..=SALOAD => Instruction::ArrayLoad,
+            ISTORE..=ASTORE => Instruction::Store(self.read_u8()? as usize),
+            ISTORE_0..=ASTORE_3 => Instruction::Store(((opcode - ISTORE_0) % 4) as usize),
+            AASTORE => Instruction::Aastore,
+            IASTORE..=SASTORE => Instruction::ArrayStore,
+            POP => Instruction::Pop,
+            POP2 => Instruction::Pop2,
+            DUP => Instruction::Dup,
+            DUP_X1 => Instruction::DupX1,
+            DUP_X2 => Instruction::DupX2,
+            DUP2 => Instruction::Dup2,
+            DUP2_X1 => Instruction::Dup2X1,
+            DUP2_X2 => Instruction::Dup2X2,
+            SWAP => Instruction::Swap,
+            IADD => Instruction::Iadd,
+            LADD => Instruction::Ladd,
+            FADD => Instruction::Fadd,
+            DADD => Instruction::Dadd,
+            ISUB => Instruction::Isub,
+            LSUB => Instruction::Lsub,
+            FSUB => Instruction::Fsub,
+            DSUB => Instruction::Dsub,
+            IMUL => Instruction::Imul,
+            LMUL => Instruction::Lmul,
+            FMUL => Instruction::Fmul,
+            DMUL => Instruction::Dmul,
+            IDIV => Instruction::Idiv,
+            LDIV => Instruction::Ldiv,
+            FDIV => Instruction::Fdiv,
+            DDIV => Instruction::Ddiv,
+            IREM => Instruction::Irem,
+            LREM => Instruction::Lrem,
+            FREM => Instruction::Frem,
+            DREM => Instruction::Drem,
+            INEG => Instruction::Ineg,
+            LNEG => Instruction::Lneg,
+            FNEG => Instruction::Fneg,
+            DNEG => Instruction::Dneg,
+            ISHL => Instruction::Ishl,
+            LSHL => Instruction::Lshl,
+            ISHR => Instruction::Ishr,
+            LSHR => Instruction::Lshr,
+            IUSHR => Instruction::Iushr,
+            LUSHR => Instruction::Lushr,
+            IAND => Instruction::Iand,
+            LAND => Instruction::Land,
+            IOR => Instruction::Ior,
+            LOR => Instruction::Lor,
+            IXOR => Instruction::Ixor,
+            LXOR => Instruction::Lxor,
+            IINC => {
+                let index = self.read_u8()? as usize;
+                Instruction::Iinc(index, self.read_u8()? as i8 as i32)
+            }
+            I2L => Instruction::I2l,
+            I2F => Instruction::I2f,
+            I2D => Instruction::I2d,
+            L2I => Instruction::L2i,
+            L2F => Instruction::L2f,
+            L2D => Instruction::L2d,
+            F2I => Instruction::F2i,
+            F2L => Instruction::F2l,
+            F2D => Instruction::F2d,
+            D2I => Instruction::D2i,
+            D2L => Instruction::D2l,
+            D2F => Instruction::D2f,
+            I2B => Instruction::I2b,
+            I2C => Instruction::I2c,
+            I2S => Instruction::I2s,
+            LCMP => Instruction::Lcmp,
+            FCMPL => Instruction::Fcmpl,
+            FCMPG => Instruction::Fcmpg,
+            DCMPL => Instruction::Dcmpl,
+            DCMPG => Instruction::Dcmpg,
+            IFEQ => Instruction::Ifeq(self.branch()?),
+            IFNE => Instruction::Ifne(self.branch()?),
+            IFLT => Instruction::Iflt(self.branch()?),
+            IFGE => Instruction::Ifge(self.branch()?),
+            IFGT => Instruction::Ifgt(self.branch()?),
+            IFLE => Instruction::Ifle(self.branch()?),
+            IF_ICMPEQ => Instruction::IfIcmpeq(self.branch()?),
+            IF_ICMPNE => Instruction::IfIcmpne(self.branch()?),
+            IF_ICMPLT => Instruction::IfIcmplt(self.branch()?),
+            IF_ICMPGE => Instruction::IfIcmpge(self.branch()?),
+            IF_ICMPGT => Instruction::IfIcmpgt(self.branch()?),
+            IF_ICMPLE => Instruction::IfIcmple(self.branch()?),
+            IF_ACMPEQ => Instruction::IfAcmpeq(self.branch()?),
+            IF_ACMPNE => Instruction::IfAcmpne(self.branch()?),
+            GOTO => Instruction::Goto(self.branch()?),
+            JSR => Instruction::Jsr(self.branch()?),
+            RET => Instruction::Ret(self.read_u8()? as usize),
+            TABLESWITCH => {
+                self.pc = switch_operands(self.address);
+                let default = self.branch_w()?;
+                let low = self.read_i32()?;
+                let high = self.read_i32()?;
+                if low > high {
+                    let kind = ClassFormatErrorKind::TableSwitchRange { low, high };
+                    return Err(ClassFormatError::new(self.address, kind));
+                }
+                let targets = (low..=high)
+                    .map(|_| self.branch_w())
+                    .collect::<Result<_, _>>()?;
+                Instruction::TableSwitch {
+                    default,
+                    low,
+                    targets,
+                }
+            }
+            LOOKUPSWITCH => {
+                self.pc = switch_operands(self.address);
+                let default = self.branch_w()?;
+                let count = self.read_i32()?;
+                if count < 0 {
+                    let kind = ClassFormatErrorKind::LookupSwitchCount { actual: count };
+                    return Err(ClassFormatError::new(self.address, kind));
+                }
+                let mut pairs = (0..count)
+                    .map(|_| Ok((self.read_i32()?, self.branch_w()?)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                pairs.sort_by_key(|&(key, _)| key);
+                Instruction::LookupSwitch { default, pairs }
+            }
+            IRETURN..=ARETURN => Instruction::ReturnValue,
+            RETURN => Instruction::Return,
+            GETSTATIC => Instruction::GetStatic(self.field_ref()?),
+            PUTSTATIC => Instruction::PutStatic(self.field_ref()?),
+            GETFIELD => Instruction::GetField(self.field_ref()?),
+            PUTFIELD => Instruction::PutField(self.field_ref()?),
+            INVOKEVIRTUAL => {
+                let method_ref = self.method_ref()?;
+                let (name, descriptor) = method_ref.name_and_type.split_once(':').unwrap();
+                match (method_ref.class_name.as_str(), name) {
+                    (METHOD_HANDLE, "invokeExact" | "invoke") => Instruction::InvokeHandle {
+                        descriptor: MethodDescriptor::parse(descriptor)
+                            .ok_or_else(|| self.error())?,
+                        exact: name == "invokeExact",
+                    },
+                    _ => Instruction::InvokeVirtual(method_ref),
+                }
+            }
+            INVOKESPECIAL => Instruction::InvokeSpecial(self.method_ref()?),
+            INVOKESTATIC => Instruction::InvokeStatic(self.method_ref()?),
+            INVOKEINTERFACE => {
+                let method_ref = self.method_ref()?;
+                // the count operand and a zero byte, both redundant
+                self.read_u16()?;
+                Instruction::InvokeInterface(method_ref)
+            }
+            INVOKEDYNAMIC => {
+                let index = self.read_u16()?;
+                if !self.constant_pool.is_invoke_dynamic(index as usize) {
+                    return Err(self.constant_error(index));
+                }
+                let (_, name_and_type) = self.constant_pool.invoke_dynamic(index);
+                let (_, descriptor) = name_and_type.split_once(':').unwrap();
+                let descriptor = MethodDescriptor::parse(descriptor).ok_or_else(|| self.error())?;
+                Instruction::InvokeDynamic {
+                    index,
+                    descriptor,
+                    call_site: OnceCell::new(),
+                }
+            }
+            NEW => Instruction::New(self.class()?),
+            NEWARRAY => {
+                let component_type = match self.read_u8()? {
+                    T_BOOLEAN => "Z",
+                    T_CHAR => "C",
+                    T_FLOAT => "F",
+                    T_DOUBLE => "D",
+                    T_BYTE => "B",
+                    T_SHORT => "S",
+                    T_INT => "I",
+                    T_LONG => "J",
+                    _ => return Err(self.error()),
+                };
+                Instruction::NewArray(format!("[{}", component_type))
+            }
+            ANEWARRAY => Instruction::NewArray(array_class_name(&self.class()?)),
+            ARRAYLENGTH => Instruction::ArrayLength,
+            ATHROW => Instruction::Athrow,
+            CHECKCAST => Instruction::CheckCast(self.class()?),
+            INSTANCEOF => Instruction::InstanceOf(self.class()?),
+            MONITORENTER | MONITOREXIT => Instruction::Monitor,
+            WIDE => {
+                let opcode = self.read_u8()?;
+                let index = self.read_u16()? as usize;
+                match opcode {
+                    ILOAD..=ALOAD => Instruction::Load(index),
+                    ISTORE..=ASTORE => Instruction::Store(index),
+                    IINC => Instruction::Iinc(index, self.read_u16()? as i16 as i32),
+                    RET => Instruction::Ret(index),
+                    _ => return Err(self.error()),
+                }
+            }
+            MULTIANEWARRAY => {
+                let class_name = self.class()?;
+                Instruction::MultiANewArray(class_name, self.read_u8()? as usize)
+            }
+            IFNULL => Instruction::IfNull(self.branch()?),
+            IFNONNULL => Instruction::IfNonNull(self.branch()?),
+            GOTO_W => Instruction::Goto(self.branch_w()?),
+            JSR_W => Instruction::Jsr(self.branch_w()?),
+            // loading the class rejects the undefined opcodes first
+            _ => return Err(self.error()),
+        };
+
+        self.pc = self.address + instruction_length(self.code, self.address);
+        Ok(instruction)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, ClassFormatError> {
+        Ok(u8::from_be_bytes(self.read_bytes()?))
+    }
+
+    fn read_u16(&mut self) -> Result<u16, ClassFormatError> {
+        Ok(u16::from_be_bytes(self.read_bytes()?))
+    }
+
+    fn read_i32(&mut self) -> Result<i32, ClassFormatError> {
+        Ok(i32::from_be_bytes(self.read_bytes()?))
+    }
+
+    fn read_bytes<const N: usize>(&mut self) -> Result<[u8; N], ClassFormatError> {
+        let bytes = self
+            .code
+            .get(self.pc..self.pc + N)
+            .ok_or_else(|| self.unexpected_eof(N))?;
+        self.pc += N;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    fn unexpected_eof(&self, expected: usize) -> ClassFormatError {
+        let kind = ClassFormatErrorKind::UnexpectedEof {
+            expected,
+            actual: self.code.len().saturating_sub(self.pc),
+        };
+        ClassFormatError::new(self.pc, kind)
+    }
+
+    fn branch(&mut self) -> Result<usize, ClassFormatError> {
+        let offset = self.read_u16()? as i16 as i32;
+        self.target(offset)
+    }
+
+    fn branch_w(&mut self) -> Result<usize, ClassFormatError> {
+        let offset = self.read_i32()?;
+        self.target(offset)
+    }
+
+    /// The index of the instruction at the offset from the current one.
+    fn target(&self, offset: i32) -> Result<usize, ClassFormatError> {
+        let target = self.address as i64 + offset as i64;
+        usize::try_from(target)
+            .ok()
+            .filter(|&target| target < self.code.len())
+            .and_then(|target| self.indices[target])
+            .ok_or_else(|| self.error())
+    }
+
+    fn constant(&self, opcode: u8, index: usize) -> Result<Instruction, ClassFormatError> {
+        // only ldc2_w loads the constants that take up two slots
+        let wide = if self.constant_pool.is_dynamic(index) {
+            let (_, name_and_type) = self.constant_pool.dynamic(index as u16);
+            name_and_type.ends_with(":J") || name_and_type.ends_with(":D")
+        } else {
+            self.constant_pool.is_wide(index)
+        };
+        if wide != (opcode == LDC2_W) {
+            return Err(self.constant_error(index as u16));
+        }
+        if self.constant_pool.is_numeric(index) {
+            Ok(Instruction::Const(self.constant_pool.get(index)))
+        } else if self.constant_pool.is_loadable_reference(index)
+            || self.constant_pool.is_dynamic(index)
+        {
+            Ok(Instruction::Ldc(index as u16))
+        } else {
+            Err(self.constant_error(index as u16))
+        }
+    }
+
+    fn class(&mut self) -> Result<String, ClassFormatError> {
+        let index = self.read_u16()?;
+        if !self.constant_pool.is_class(index as usize) {
+            return Err(self.constant_error(index));
+        }
+        Ok(self.constant_pool.class(index))
+    }
+
+    fn field_ref(&mut self) -> Result<MemberRef, ClassFormatError> {
+        let index = self.read_u16()?;
+        if !self.constant_pool.is_field_ref(index as usize) {
+            return Err(self.constant_error(index));
+        }
+        let (class_name, name_and_type) = self.constant_pool.field_ref(index);
+        Ok(MemberRef {
+            class_name,
+            name_and_type,
+            interface: false,
+        })
+    }
+
+    fn method_ref(&mut self) -> Result<MemberRef, ClassFormatError> {
+        let index = self.read_u16()?;
+        if !self.constant_pool.is_method_ref(index as usize) {
+            return Err(self.constant_error(index));
+        }
+        let (class_name, name_and_type) = self.constant_pool.method_ref(index);
+        Ok(MemberRef {
+            class_name,
+            name_and_type,
+            interface: self.constant_pool.is_interface_method_ref(index),
+        })
+    }
+
+    /// The error for an operand that is not an index of a constant of the
+    /// kind the instruction expects.
+    fn constant_error(&self, index: u16) -> ClassFormatError {
+        let kind = ClassFormatErrorKind::ConstantIndex { actual: index };
+        ClassFormatError::new(self.address, kind)
+    }
+
+    fn error(&self) -> ClassFormatError {
+        let kind = ClassFormatErrorKind::Instruction {
+            opcode: self.code[self.address],
+        };
+        ClassFormatError::new(self.address, kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_code(code: &[u8]) -> Result<Vec<Instruction>, ClassFormatError> {
+        let constant_pool = ConstantPool::from(vec![]);
+        decode(code, &constant_pool).map(|(instructions, _)| instructions)
+    }
+
+    #[test]
+    fn decodes_switches() {
+        // iload_0; tableswitch 0..1 -> return, return; return
+        let mut code = vec![ILOAD_0, TABLESWITCH, 0, 0];
+        code.extend([
+            0, 0, 0, 23, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 23, 0, 0, 0, 23,
+        ]);
+        code.push(RETURN);
+        let instructions = decode_code(&code).unwrap();
+        assert!(matches!(
+            &instructions[1],
+            Instruction::TableSwitch { default: 2, low: 0, targets } if targets == &[2, 2]
+        ));
+
+        // iload_0; lookupswitch 7 -> return; return
+        let mut code = vec![ILOAD_0, LOOKUPSWITCH, 0, 0];
+        code.extend([0, 0, 0, 19, 0, 0, 0, 1, 0, 0, 0, 7, 0, 0, 0, 19]);
+        code.push(RETURN);
+        let instructions = decode_code(&code).unwrap();
+        assert!(matches!(
+            &instructions[1],
+            Instruction::LookupSwitch { default: 2, pairs } if pairs == &[(7, 2)]
+        ));
+    }
+
+    #[test]
+    fn decodes_monitors() {
+        let code = [ALOAD_0, MONITORENTER, ALOAD_0, MONITOREXIT, RETURN];
+        let instructions = decode_code(&code).unwrap();
+        assert!(matches!(instructions[1], Instruction::Monitor));
+        assert!(matches!(instructions[3], Instruction::Monitor));
+    }
+
+    #[test]
+    fn rejects_undefined_opcodes() {
+        for opcode in [0xca, 0xcb, 0xfe, 0xff] {
+            let error = decode_code(&[opcode]).unwrap_err();
+            assert!(matches!(
+                error.kind,
+                ClassFormatErrorKind::Instruction { .. }
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_switches() {
+        let mut tableswitch = vec![ILOAD_0, TABLESWITCH, 0, 0];
+        tableswitch.extend([
+            0, 0, 0, 23, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 23, 0, 0, 0, 23,
+        ]);
+        let mut lookupswitch = vec![ILOAD_0, LOOKUPSWITCH, 0, 0];
+        lookupswitch.extend([0, 0, 0, 19, 0, 0, 0, 1, 0, 0, 0, 7, 0, 0, 0, 19]);
+
+        for code in [tableswitch, lookupswitch] {
+            for length in 2..code.len() {
+                let error = decode_code(&code[..length]).unwrap_err();
+                assert!(
+                    matches!(error.kind, ClassFormatErrorKind::UnexpectedEof { .. }),
+                    "{}: {}",
+                    length,
+                    error
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_switches() {
+        // tableswitch 1..0
+        let mut code = vec![TABLESWITCH, 0, 0, 0];
+        code.extend([0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0]);
+        let error = decode_code(&code).unwrap_err();
+        let kind = ClassFormatErrorKind::TableSwitchRange { low: 1, high: 0 };
+        assert_eq!(error.kind, kind);
+
+        // lookupswitch with -1 pairs
+        let mut code = vec![LOOKUPSWITCH, 0, 0, 0];
+        code.extend([0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff]);
+        let error = decode_code(&code).unwrap_err();
+        let kind = ClassFormatErrorKind::LookupSwitchCount { actual: -1 };
+        assert_eq!(error.kind, kind);
+    }
+
+    #[test]
+    fn rejects_truncated_operands() {
+        for code in [&[SIPUSH, 0][..], &[GOTO_W, 0, 0, 0], &[WIDE, IINC, 0, 1, 0]] {
+            let error = decode_code(code).unwrap_err();
+            assert!(matches!(
+                error.kind,
+                ClassFormatErrorKind::UnexpectedEof { .. }
+            ));
+        }
+    }
+}
@@ -0,0 +1,339 @@
+use crate::attribute::{Attribute, AttributeInfo, CodeAttribute};
+use crate::class::OBJECT;
+use crate::class_file::{
+    ClassFile, ConstantClassInfo, ConstantFieldRefInfo, ConstantInfo,
+    ConstantInterfaceMethodRefInfo, ConstantMethodRefInfo, ConstantNameAndTypeInfo,
+    ConstantUtf8Info, FieldInfo, MethodInfo, ReferenceKind, MAGIC,
+};
+use crate::descriptor::{FieldDescriptor, MethodDescriptor};
+use crate::thread::*;
+
+const ACC_PUBLIC: u16 = 0x0001;
+const ACC_PRIVATE: u16 = 0x0002;
+const ACC_FINAL: u16 = 0x0010;
+const ACC_SYNTHETIC: u16 = 0x1000;
+
+const CODE_ATTRIBUTE_NAME: &str = "Code";
+
+/// The method the lambda delegates to, the `implMethod` argument of
+/// `LambdaMetafactory.metafactory`.
+pub(crate) struct Implementation {
+    pub(crate) kind: ReferenceKind,
+    pub(crate) class_name: String,
+    pub(crate) name: String,
+    pub(crate) descriptor: MethodDescriptor,
+    pub(crate) interface: bool,
+}
+
+/// The class of a lambda, as `LambdaMetafactory` describes it.
+pub(crate) struct Lambda {
+    pub(crate) class_name: String,
+    /// The functional interface followed by the marker interfaces.
+    pub(crate) interfaces: Vec<String>,
+    /// The types of the values the lambda captures, the parameters of the
+    /// call site.
+    pub(crate) captured: Vec<FieldDescriptor>,
+    pub(crate) method_name: String,
+    /// The descriptor of the interface method followed by those of its
+    /// bridges.
+    pub(crate) method_descriptors: Vec<MethodDescriptor>,
+    pub(crate) implementation: Implementation,
+}
+
+impl Implementation {
+    /// The parameters of the implementation, with the receiver first for an
+    /// instance method.
+    pub(crate) fn parameters(&self) -> Vec<FieldDescriptor> {
+        let receiver = match self.kind {
+            ReferenceKind::InvokeVirtual
+            | ReferenceKind::InvokeSpecial
+            | ReferenceKind::InvokeInterface => {
+                Some(FieldDescriptor::Object(self.class_name.clone()))
+            }
+            _ => None,
+        };
+        receiver
+            .into_iter()
+            .chain(self.descriptor.parameters.iter().cloned())
+            .collect()
+    }
+
+    fn return_type(&self) -> Option<FieldDescriptor> {
+        match self.kind {
+            ReferenceKind::NewInvokeSpecial => {
+                Some(FieldDescriptor::Object(self.class_name.clone()))
+            }
+            _ => self.descriptor.return_type.clone(),
+        }
+    }
+}
+
+/// Spins the class file of the lambda, which keeps the captured values in its
+/// fields and implements the interface method by passing them to the
+/// implementation followed by its own arguments, adapting their types the
+/// way `MethodHandle.asType` does.
+pub(crate) fn spin(lambda: &Lambda) -> ClassFile {
+    let mut constant_pool = ConstantPoolBuilder::default();
+    let this_class = constant_pool.class(&lambda.class_name);
+    let super_class = constant_pool.class(OBJECT);
+    let interfaces = lambda
+        .interfaces
+        .iter()
+        .map(|interface| constant_pool.class(interface))
+        .collect();
+
+    let fields = lambda
+        .captured
+        .iter()
+        .enumerate()
+        .map(|(index, captured)| FieldInfo {
+            access_flags: ACC_PRIVATE | ACC_FINAL,
+            name_index: constant_pool.utf8(&format!("arg${}", index + 1)),
+            descriptor_index: constant_pool.utf8(&captured.to_string()),
+            attributes: vec![],
+        })
+        .collect::<Vec<_>>();
+    let captured_fields = fields
+        .iter()
+        .map(|field| {
+            let name_and_type_index =
+                constant_pool.add_name_and_type(field.name_index, field.descriptor_index);
+            constant_pool.add(ConstantInfo::FieldRef(ConstantFieldRefInfo {
+                class_index: this_class,
+                name_and_type_index,
+            }))
+        })
+        .collect::<Vec<_>>();
+
+    let methods = lambda
+        .method_descriptors
+        .iter()
+        .map(|descriptor| {
+            let code = method_code(lambda, &mut constant_pool, &captured_fields, descriptor);
+            MethodInfo {
+                // the class is never read from bytes, so nothing has an offset
+                offset: 0,
+                access_flags: ACC_PUBLIC,
+                name_index: constant_pool.utf8(&lambda.method_name),
+                descriptor_index: constant_pool.utf8(&descriptor.to_string()),
+                attributes: vec![AttributeInfo {
+                    attribute_name_index: constant_pool.utf8(CODE_ATTRIBUTE_NAME),
+                    attribute: Attribute::Code(code),
+                }],
+            }
+        })
+        .collect();
+
+    ClassFile {
+        magic: MAGIC,
+        minor_version: 0,
+        major_version: 0,
+        constant_pool: constant_pool.constants,
+        access_flags: ACC_FINAL | ACC_SYNTHETIC,
+        this_class,
+        super_class,
+        interfaces,
+        fields,
+        methods,
+        attributes: vec![],
+    }
+}
+
+fn method_code(
+    lambda: &Lambda,
+    constant_pool: &mut ConstantPoolBuilder,
+    captured_fields: &[u16],
+    descriptor: &MethodDescriptor,
+) -> CodeAttribute {
+    let implementation = &lambda.implementation;
+    let mut code = vec![];
+
+    if let ReferenceKind::NewInvokeSpecial = implementation.kind {
+        code.push(NEW);
+        code.extend(
+            constant_pool
+                .class(&implementation.class_name)
+                .to_be_bytes(),
+        );
+        code.push(DUP);
+    }
+    for &field in captured_fields {
+        code.extend([ALOAD_0, GETFIELD]);
+        code.extend(field.to_be_bytes());
+    }
+
+    let parameters = implementation.parameters();
+    let mut slot = 1;
+    for (parameter, target) in descriptor
+        .parameters
+        .iter()
+        .zip(&parameters[captured_fields.len()..])
+    {
+        let opcode = match parameter {
+            FieldDescriptor::Long => LLOAD,
+            FieldDescriptor::Float => FLOAD,
+            FieldDescriptor::Double => DLOAD,
+            FieldDescriptor::Object(_) | FieldDescriptor::Array(_) => ALOAD,
+            _ => ILOAD,
+        };
+        code.extend([opcode, slot as u8]);
+        slot += parameter.slots();
+        convert(&mut code, constant_pool, parameter, target);
+    }
+
+    let method_ref = constant_pool.method_ref(
+        &implementation.class_name,
+        &implementation.name,
+        &implementation.descriptor.to_string(),
+        implementation.interface,
+    );
+    let opcode = match implementation.kind {
+        ReferenceKind::InvokeStatic => INVOKESTATIC,
+        ReferenceKind::NewInvokeSpecial => INVOKESPECIAL,
+        // private methods, which invokeSpecial refers to, are invoked
+        // directly by invokevirtual and invokeinterface too
+        _ if implementation.interface => INVOKEINTERFACE,
+        _ => INVOKEVIRTUAL,
+    };
+    code.push(opcode);
+    code.extend(method_ref.to_be_bytes());
+    if opcode == INVOKEINTERFACE {
+        let count = 1 + implementation.descriptor.parameter_slots();
+        code.extend([count as u8, 0]);
+    }
+
+    match (implementation.return_type(), &descriptor.return_type) {
+        (Some(returned), Some(return_type)) => {
+            convert(&mut code, constant_pool, &returned, return_type);
+            code.push(match return_type {
+                FieldDescriptor::Long => LRETURN,
+                FieldDescriptor::Float => FRETURN,
+                FieldDescriptor::Double => DRETURN,
+                FieldDescriptor::Object(_) | FieldDescriptor::Array(_) => ARETURN,
+                _ => IRETURN,
+            });
+        }
+        (Some(returned), None) => {
+            code.extend([if returned.slots() == 2 { POP2 } else { POP }, RETURN])
+        }
+        (None, _) => code.push(RETURN),
+    }
+
+    CodeAttribute {
+        offset: 0,
+        // every value takes up at most two slots, and new and dup add two more
+        max_stack: 2 * (captured_fields.len() + descriptor.parameters.len()) + 2,
+        max_locals: slot,
+        code,
+        exception_table: vec![],
+        attributes: vec![],
+    }
+}
+
+/// Adapts the value on top of the operand stack to the type, boxing,
+/// unboxing, widening or casting it.
+fn convert(
+    code: &mut Vec<u8>,
+    constant_pool: &mut ConstantPoolBuilder,
+    from: &FieldDescriptor,
+    to: &FieldDescriptor,
+) {
+    if from == to {
+        return;
+    }
+
+    match (from.wrapper(), to.wrapper()) {
+        (Some(_), Some(_)) => code.extend(widening(from, to)),
+        (Some((wrapper, _)), None) => {
+            let descriptor = format!("({})L{};", from, wrapper);
+            let method_ref = constant_pool.method_ref(wrapper, "valueOf", &descriptor, false);
+            code.push(INVOKESTATIC);
+            code.extend(method_ref.to_be_bytes());
+        }
+        (None, Some((wrapper, value_method))) => {
+            code.push(CHECKCAST);
+            code.extend(constant_pool.class(wrapper).to_be_bytes());
+            let descriptor = format!("(){}", to);
+            let method_ref = constant_pool.method_ref(wrapper, value_method, &descriptor, false);
+            code.push(INVOKEVIRTUAL);
+            code.extend(method_ref.to_be_bytes());
+        }
+        (None, None) => {
+            let class_name = to.class_name().unwrap();
+            if class_name == OBJECT {
+                return;
+            }
+            code.push(CHECKCAST);
+            code.extend(constant_pool.class(&class_name).to_be_bytes());
+        }
+    }
+}
+
+/// The widening primitive conversion between the types (JLS 5.1.2), nothing
+/// between types that are all `int` on the operand stack.
+fn widening(from: &FieldDescriptor, to: &FieldDescriptor) -> Option<u8> {
+    let computational = |descriptor: &FieldDescriptor| match descriptor {
+        FieldDescriptor::Long | FieldDescriptor::Float | FieldDescriptor::Double => {
+            descriptor.clone()
+        }
+        _ => FieldDescriptor::Int,
+    };
+
+    match (computational(from), computational(to)) {
+        (FieldDescriptor::Int, FieldDescriptor::Long) => Some(I2L),
+        (FieldDescriptor::Int, FieldDescriptor::Float) => Some(I2F),
+        (FieldDescriptor::Int, FieldDescriptor::Double) => Some(I2D),
+        (FieldDescriptor::Long, FieldDescriptor::Float) => Some(L2F),
+        (FieldDescriptor::Long, FieldDescriptor::Double) => Some(L2D),
+        (FieldDescriptor::Float, FieldDescriptor::Double) => Some(F2D),
+        _ => None,
+    }
+}
+
+#[derive(Default)]
+struct ConstantPoolBuilder {
+    constants: Vec<ConstantInfo>,
+}
+
+impl ConstantPoolBuilder {
+    fn add(&mut self, constant: ConstantInfo) -> u16 {
+        self.constants.push(constant);
+        self.constants.len() as u16
+    }
+
+    fn utf8(&mut self, value: &str) -> u16 {
+        self.add(ConstantInfo::Utf8(ConstantUtf8Info {
+            value: value.to_string(),
+            bytes: None,
+        }))
+    }
+
+    fn class(&mut self, name: &str) -> u16 {
+        let name_index = self.utf8(name);
+        self.add(ConstantInfo::Class(ConstantClassInfo { name_index }))
+    }
+
+    fn add_name_and_type(&mut self, name_index: u16, descriptor_index: u16) -> u16 {
+        self.add(ConstantInfo::NameAndType(ConstantNameAndTypeInfo {
+            name_index,
+            descriptor_index,
+        }))
+    }
+
+    fn method_ref(&mut self, class: &str, name: &str, descriptor: &str, interface: bool) -> u16 {
+        let class_index = self.class(class);
+        let (name_index, descriptor_index) = (self.utf8(name), self.utf8(descriptor));
+        let name_and_type_index = self.add_name_and_type(name_index, descriptor_index);
+        self.add(if interface {
+            ConstantInfo::InterfaceMethodRef(ConstantInterfaceMethodRefInfo {
+                class_index,
+                name_and_type_index,
+            })
+        } else {
+            ConstantInfo::MethodRef(ConstantMethodRefInfo {
+                class_index,
+                name_and_type_index,
+            })
+        })
+    }
+}
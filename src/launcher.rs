@@ -0,0 +1,384 @@
+use crate::class_path::Manifest;
+use crate::throwable::NO_CLASS_DEF_FOUND_ERROR;
+use crate::version::SupportedVersions;
+use crate::vm::VirtualMachine;
+use crate::zip::ZipArchive;
+use std::env;
+
+const MIN_STACK_SIZE: usize = 136 * 1024;
+const MIN_HEAP_SIZE: usize = 2 * 1024 * 1024;
+
+const CREATE_VM_FAILED: &str = "Error: Could not create the Java Virtual Machine.
+Error: A fatal exception has occurred. Program will exit.";
+
+const USAGE: &str = "Usage: smerdvm [options] <mainclass> [args...]
+           (to execute a class)
+   or  smerdvm [options] -jar <jarfile> [args...]
+           (to execute a jar file)
+   or  smerdvm disasm [-v] <classfile>...
+           (to disassemble class files)
+
+ Arguments following the main class or -jar <jarfile> are passed as the
+ arguments to main class.
+
+ where options include:
+
+    -cp <class search path of directories and zip/jar files>
+    -classpath <class search path of directories and zip/jar files>
+    --class-path <class search path of directories and zip/jar files>
+                  A : separated list of directories, JAR archives,
+                  and ZIP archives to search for class files.
+    -D<name>=<value>
+                  set a system property
+    -verbose:class
+                  enable verbose output for class loading
+    -verbose:gc   enable verbose output for garbage collection
+    -ea[:<packagename>...|:<classname>]
+    -enableassertions[:<packagename>...|:<classname>]
+                  enable assertions with specified granularity
+    -da[:<packagename>...|:<classname>]
+    -disableassertions[:<packagename>...|:<classname>]
+                  disable assertions with specified granularity
+    --enable-preview
+                  allow classes to depend on preview features of this release
+    -Xss<size>    set java thread stack size
+    -Xmx<size>    set maximum Java heap size
+    -? -h -help --help
+                  print this help message";
+
+#[derive(Debug, Default)]
+struct Options {
+    class_path: Option<String>,
+    main_class: Option<String>,
+    args: Vec<String>,
+    properties: Vec<(String, String)>,
+    assertions: Vec<(String, bool)>,
+    stack_size: Option<usize>,
+    max_heap_size: Option<usize>,
+    verbose_class: bool,
+    verbose_gc: bool,
+    enable_preview: bool,
+    help: bool,
+}
+
+/// Runs the `java`-compatible launcher and returns the process exit code.
+pub fn launch(args: &[String]) -> i32 {
+    let options = match parse(args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            return 1;
+        }
+    };
+
+    if options.help {
+        println!("{}", USAGE);
+        return 0;
+    }
+
+    let main_class = match &options.main_class {
+        Some(main_class) => main_class,
+        None => {
+            eprintln!("{}", USAGE);
+            return 1;
+        }
+    };
+
+    let class_path = options
+        .class_path
+        .clone()
+        .or_else(|| env::var("CLASSPATH").ok())
+        .unwrap_or_else(|| ".".to_string());
+
+    let mut vm = VirtualMachine::new(&class_path, main_class)
+        .with_verbose_class(options.verbose_class)
+        .with_verbose_gc(options.verbose_gc)
+        .with_supported_versions(SupportedVersions::new(options.enable_preview))
+        .with_property("java.class.path", &class_path);
+    for (name, value) in &options.properties {
+        vm = vm.with_property(name, value);
+    }
+    for (target, enabled) in &options.assertions {
+        vm = vm.with_assertions(target, *enabled);
+    }
+    if let Some(stack_size) = options.stack_size {
+        vm = vm.with_stack_size(stack_size);
+    }
+    if let Some(max_heap_size) = options.max_heap_size {
+        vm = vm.with_max_heap_size(max_heap_size);
+    }
+
+    if let Err(throwable) = vm.load_main_class() {
+        let not_found = throwable.class_name == NO_CLASS_DEF_FOUND_ERROR
+            && throwable.message.as_deref() == Some(&main_class.replace('.', "/"));
+        if not_found {
+            eprintln!("Error: Could not find or load main class {}", main_class);
+            eprintln!(
+                "Caused by: java.lang.ClassNotFoundException: {}",
+                main_class
+            );
+        } else {
+            eprintln!(
+                "Error: LinkageError occurred while loading main class {}",
+                main_class
+            );
+            eprintln!("\t{}", throwable);
+        }
+        return 1;
+    }
+
+    match vm.main_method() {
+        Some(method) if method.is_static() => (),
+        Some(_) => {
+            eprintln!(
+                "Error: Main method is not static in class {}, please define the main method as:",
+                main_class
+            );
+            eprintln!("   public static void main(String[] args)");
+            return 1;
+        }
+        None => {
+            eprintln!(
+                "Error: Main method not found in class {}, please define the main method as:",
+                main_class
+            );
+            eprintln!("   public static void main(String[] args)");
+            return 1;
+        }
+    }
+
+    match vm.run(&options.args) {
+        Ok(()) => 0,
+        Err(throwable) => {
+            eprintln!("Exception in thread \"main\" {}", throwable);
+            let mut cause = &throwable.cause;
+            while let Some(throwable) = cause {
+                eprintln!("Caused by: {}", throwable);
+                cause = &throwable.cause;
+            }
+            1
+        }
+    }
+}
+
+fn parse(args: &[String]) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-cp" | "-classpath" | "--class-path" => {
+                let class_path = args
+                    .next()
+                    .ok_or_else(|| format!("Error: {} requires class path specification", arg))?;
+                options.class_path = Some(class_path.clone());
+            }
+            "-jar" => {
+                let jar = args.next().ok_or_else(|| {
+                    format!("Error: -jar requires jar file specification\n{}", USAGE)
+                })?;
+                options.main_class = Some(jar_main_class(jar)?);
+                options.class_path = Some(jar.clone());
+                break;
+            }
+            "-verbose:class" => options.verbose_class = true,
+            "-verbose:gc" => options.verbose_gc = true,
+            "--enable-preview" => options.enable_preview = true,
+            "-?" | "-h" | "-help" | "--help" => {
+                options.help = true;
+                return Ok(options);
+            }
+            _ if arg.starts_with("-D") => {
+                let property = &arg[2..];
+                let (name, value) = property.split_once('=').unwrap_or((property, ""));
+                options
+                    .properties
+                    .push((name.to_string(), value.to_string()));
+            }
+            _ if arg.starts_with("-Xss") => {
+                let stack_size = size(&arg[4..]).ok_or_else(|| {
+                    format!("Invalid thread stack size: {}\n{}", arg, CREATE_VM_FAILED)
+                })?;
+                if stack_size < MIN_STACK_SIZE {
+                    return Err(format!(
+                        "The Java thread stack size specified is too small. Specify at least {}k\n{}",
+                        MIN_STACK_SIZE / 1024,
+                        CREATE_VM_FAILED
+                    ));
+                }
+                options.stack_size = Some(stack_size);
+            }
+            _ if arg.starts_with("-Xmx") => {
+                let max_heap_size = size(&arg[4..]).ok_or_else(|| {
+                    format!("Invalid maximum heap size: {}\n{}", arg, CREATE_VM_FAILED)
+                })?;
+                if max_heap_size < MIN_HEAP_SIZE {
+                    return Err(
+                        "Error occurred during initialization of VM\nToo small maximum heap"
+                            .to_string(),
+                    );
+                }
+                options.max_heap_size = Some(max_heap_size);
+            }
+            _ if arg.starts_with('-') => {
+                let (option, target) = arg.split_once(':').unwrap_or((arg, ""));
+                let enabled = match option {
+                    "-ea" | "-enableassertions" => true,
+                    "-da" | "-disableassertions" => false,
+                    _ => {
+                        return Err(format!(
+                            "Unrecognized option: {}\n{}",
+                            arg, CREATE_VM_FAILED
+                        ))
+                    }
+                };
+                options.assertions.push((target.to_string(), enabled));
+            }
+            _ => {
+                options.main_class = Some(arg.clone());
+                break;
+            }
+        }
+    }
+
+    options.args = args.cloned().collect();
+    Ok(options)
+}
+
+fn jar_main_class(jar: &str) -> Result<String, String> {
+    let archive =
+        ZipArchive::open(jar).map_err(|_| format!("Error: Unable to access jarfile {}", jar))?;
+
+    Manifest::read(&archive)
+        .and_then(|manifest| manifest.main_class().map(str::to_string))
+        .ok_or_else(|| format!("no main manifest attribute, in {}", jar))
+}
+
+/// Parses a HotSpot memory size such as `512k`, `64m` or `1g`.
+fn size(value: &str) -> Option<usize> {
+    let (digits, multiplier) = match value.char_indices().last()? {
+        (index, 'k' | 'K') => (&value[..index], 1 << 10),
+        (index, 'm' | 'M') => (&value[..index], 1 << 20),
+        (index, 'g' | 'G') => (&value[..index], 1 << 30),
+        (index, 't' | 'T') => (&value[..index], 1 << 40),
+        _ => (value, 1),
+    };
+
+    if !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse::<usize>().ok()?.checked_mul(multiplier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        super::parse(&args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn parses_every_option() {
+        let options = parse(&[
+            "-cp",
+            "a:b",
+            "-Dname=value",
+            "-Dflag",
+            "-verbose:class",
+            "-verbose:gc",
+            "-ea",
+            "-da:ru.khlevnov...",
+            "-enableassertions:ru.khlevnov.Main",
+            "-disableassertions",
+            "--enable-preview",
+            "-Xss1m",
+            "-Xmx64M",
+            "ru.khlevnov.Main",
+            "-cp",
+            "arg",
+        ])
+        .unwrap();
+
+        assert_eq!(options.class_path.as_deref(), Some("a:b"));
+        assert_eq!(options.main_class.as_deref(), Some("ru.khlevnov.Main"));
+        assert_eq!(options.args, ["-cp", "arg"]);
+        let properties = [("name", "value"), ("flag", "")].map(|(n, v)| (n.into(), v.into()));
+        assert_eq!(options.properties, properties);
+        let assertions = [
+            ("", true),
+            ("ru.khlevnov...", false),
+            ("ru.khlevnov.Main", true),
+            ("", false),
+        ]
+        .map(|(target, enabled)| (target.to_string(), enabled));
+        assert_eq!(options.assertions, assertions);
+        assert_eq!(options.stack_size, Some(1 << 20));
+        assert_eq!(options.max_heap_size, Some(64 << 20));
+        assert!(options.verbose_class && options.verbose_gc && options.enable_preview);
+        assert!(!options.help);
+    }
+
+    #[test]
+    fn parses_class_path_spellings() {
+        for option in ["-cp", "-classpath", "--class-path"] {
+            let options = parse(&[option, "lib", "Main"]).unwrap();
+            assert_eq!(options.class_path.as_deref(), Some("lib"));
+        }
+        let error = parse(&["-classpath"]).unwrap_err();
+        assert_eq!(error, "Error: -classpath requires class path specification");
+    }
+
+    #[test]
+    fn stops_at_help() {
+        for option in ["-?", "-h", "-help", "--help"] {
+            let options = parse(&[option, "-bogus"]).unwrap();
+            assert!(options.help);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_options() {
+        let error = parse(&["-server", "Main"]).unwrap_err();
+        assert!(error.starts_with("Unrecognized option: -server\n"));
+        let error = parse(&["-jar"]).unwrap_err();
+        assert!(error.starts_with("Error: -jar requires jar file specification\n"));
+        let error = parse(&["-jar", "missing.jar"]).unwrap_err();
+        assert_eq!(error, "Error: Unable to access jarfile missing.jar");
+    }
+
+    #[test]
+    fn rejects_bad_sizes() {
+        let error = parse(&["-Xss1x"]).unwrap_err();
+        assert!(error.starts_with("Invalid thread stack size: -Xss1x\n"));
+        let error = parse(&["-Xss100k"]).unwrap_err();
+        assert!(error.starts_with("The Java thread stack size specified is too small."));
+        let error = parse(&["-Xmx"]).unwrap_err();
+        assert!(error.starts_with("Invalid maximum heap size: -Xmx\n"));
+        let error = parse(&["-Xmx1m"]).unwrap_err();
+        assert!(error.ends_with("Too small maximum heap"));
+    }
+
+    #[test]
+    fn parses_sizes() {
+        assert_eq!(size("4096"), Some(4096));
+        assert_eq!(size("512k"), Some(512 << 10));
+        assert_eq!(size("512K"), Some(512 << 10));
+        assert_eq!(size("64m"), Some(64 << 20));
+        assert_eq!(size("2g"), Some(2 << 30));
+        assert_eq!(size("1T"), Some(1 << 40));
+        for value in [
+            "",
+            "k",
+            "-1k",
+            "+1",
+            "1.5m",
+            "1kb",
+            "m1",
+            "99999999999999999999",
+        ] {
+            assert_eq!(size(value), None, "{}", value);
+        }
+        assert_eq!(size(&format!("{}g", usize::MAX)), None);
+    }
+}
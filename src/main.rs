@@ -1,20 +1,49 @@
-use crate::vm::VirtualMachine;
+use std::env;
+use std::process;
 
+mod attribute;
+mod bytecode;
+mod call_site;
 mod class;
 mod class_file;
 mod class_loader;
+mod class_path;
+#[cfg(test)]
+mod class_writer;
 mod constant_pool;
+mod descriptor;
+mod disassembler;
+mod dispatch;
+mod field;
+mod floating_decimal;
 mod frame;
+mod heap;
+mod inflate;
+mod instruction;
+mod lambda;
+mod launcher;
 mod method;
+mod method_handle;
+mod mutf8;
 mod registry;
+mod signature;
+mod string;
 mod thread;
+mod throwable;
 mod r#type;
+mod version;
 mod vm;
+mod zip;
 
 fn main() {
-    let class_path = "/Users/khlevnov/smerdvm/playground/app/build/classes/java/main";
-    let main_class = "ru.khlevnov.Main";
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    if args.first().map(String::as_str) == Some("disasm") {
+        if let Err(message) = disassembler::run(&args[1..]) {
+            eprintln!("{}", message);
+            process::exit(1);
+        }
+        return;
+    }
 
-    let mut vm = VirtualMachine::new(class_path, &*main_class);
-    vm.run(&[]);
+    process::exit(launcher::launch(&args));
 }
@@ -1,127 +1,149 @@
-use std::io::BufReader;
-use crate::class_file::{AttributeInfo, ClassReader, MethodInfo};
-use crate::constant_pool::{Constant, ConstantPool};
-
+use crate::attribute::{Attribute, CodeAttribute, ExceptionTableEntry};
+use crate::class_file::{ClassFormatError, ClassFormatErrorKind, MethodInfo};
+use crate::constant_pool::ConstantPool;
+use crate::descriptor::MethodDescriptor;
+use crate::instruction::{self, Instruction};
+
+const ACC_PUBLIC: u16 = 0x0001;
+const ACC_PRIVATE: u16 = 0x0002;
+const ACC_PROTECTED: u16 = 0x0004;
+const ACC_STATIC: u16 = 0x0008;
 const ACC_NATIVE: u16 = 0x0100;
 const ACC_ABSTRACT: u16 = 0x0400;
 const CODE_ATTRIBUTE_NAME: &str = "Code";
+const INIT: &str = "<init>";
 
 #[derive(Debug)]
 pub struct Method {
     pub(crate) access_flags: u16,
     pub(crate) name: String,
-    pub(crate) descriptor: String,
+    pub(crate) descriptor: MethodDescriptor,
     pub(crate) max_stack: usize,
     pub(crate) max_locals: usize,
-    pub(crate) code: Vec<u8>,
+    pub(crate) instructions: Vec<Instruction>,
+    /// The exception handlers, with indices of instructions in place of
+    /// offsets in the code.
+    pub(crate) exception_table: Vec<ExceptionTableEntry>,
 }
 
-#[derive(Debug, Default)]
-struct CodeAttribute {
-    max_stack: usize,
-    max_locals: usize,
-    code: Vec<u8>,
-    exception_table: Vec<ExceptionTableEntry>,
-    attributes: Vec<AttributeInfo>,
-}
+impl Method {
+    pub(crate) fn is_public(&self) -> bool {
+        self.access_flags & ACC_PUBLIC != 0
+    }
 
-impl<T> From<T> for CodeAttribute
-    where
-        T: ClassReader,
-{
-    fn from(mut reader: T) -> Self {
-        let max_stack = reader.read_u2() as usize;
-        let max_locals = reader.read_u2() as usize;
-        let code_length = reader.read_u4();
-
-        let mut code = vec![0u8; code_length as usize];
-        reader.read_exact(&mut code).unwrap();
-
-        let exception_table_length = reader.read_u2();
-        let exception_table = (0..exception_table_length)
-            .into_iter()
-            .map(|_| ExceptionTableEntry::from(&mut reader))
-            .collect::<Vec<ExceptionTableEntry>>();
-
-        let attributes_count = reader.read_u2();
-        let attributes = (0..attributes_count)
-            .into_iter()
-            .map(|_| AttributeInfo::from(&mut reader))
-            .collect::<Vec<AttributeInfo>>();
-
-        CodeAttribute {
-            max_stack,
-            max_locals,
-            code,
-            exception_table,
-            attributes,
-        }
+    pub(crate) fn is_private(&self) -> bool {
+        self.access_flags & ACC_PRIVATE != 0
     }
-}
 
-#[derive(Debug)]
-struct ExceptionTableEntry {
-    start_pc: usize,
-    end_pc: usize,
-    handler_pc: usize,
-    catch_type: u16,
-}
+    pub(crate) fn is_protected(&self) -> bool {
+        self.access_flags & ACC_PROTECTED != 0
+    }
 
-impl<T> From<&mut T> for ExceptionTableEntry
-    where
-        T: ClassReader,
-{
-    fn from(reader: &mut T) -> Self {
-        ExceptionTableEntry {
-            start_pc: reader.read_u2() as usize,
-            end_pc: reader.read_u2() as usize,
-            handler_pc: reader.read_u2() as usize,
-            catch_type: reader.read_u2(),
-        }
+    pub(crate) fn is_static(&self) -> bool {
+        self.access_flags & ACC_STATIC != 0
+    }
+
+    pub(crate) fn is_abstract(&self) -> bool {
+        self.access_flags & ACC_ABSTRACT != 0
     }
-}
 
-impl Method {
     pub(crate) fn is_native(&self) -> bool {
         self.access_flags & ACC_NATIVE != 0
     }
 
-    pub(crate) fn from(mut method_info: MethodInfo, constant_pool: &ConstantPool) -> Method {
+    pub(crate) fn is_initializer(&self) -> bool {
+        self.name == INIT
+    }
+
+    pub(crate) fn name_and_type(&self) -> String {
+        format!("{}:{}", self.name, self.descriptor)
+    }
+
+    pub(crate) fn from(
+        mut method_info: MethodInfo,
+        constant_pool: &ConstantPool,
+    ) -> Result<Method, ClassFormatError> {
         let CodeAttribute {
+            offset,
             max_stack,
             max_locals,
             code,
+            exception_table,
             ..
-        } = code_attribute(method_info.access_flags, &mut method_info.attributes, constant_pool);
-
-        Method {
+        } = code_attribute(&mut method_info)?;
+
+        // the offsets of the instructions are relative to the code
+        let (instructions, indices) =
+            instruction::decode(&code, constant_pool).map_err(|mut e| {
+                e.offset += offset;
+                e
+            })?;
+        // each entry takes eight bytes after the code and the table length
+        let exception_table_offset = offset + code.len() + 2;
+        let exception_table = exception_table
+            .iter()
+            .zip((exception_table_offset..).step_by(8))
+            .map(|(entry, offset)| exception_handler(entry, offset, &indices))
+            .collect::<Result<_, _>>()?;
+
+        let descriptor = constant_pool.utf8(method_info.descriptor_index);
+        let descriptor = MethodDescriptor::parse(&descriptor).ok_or_else(|| {
+            let kind = ClassFormatErrorKind::Descriptor { actual: descriptor };
+            ClassFormatError::new(method_info.offset, kind)
+        })?;
+
+        Ok(Method {
             access_flags: method_info.access_flags,
             name: constant_pool.utf8(method_info.name_index),
-            descriptor: constant_pool.utf8(method_info.descriptor_index),
+            descriptor,
             max_stack,
             max_locals,
-            code,
-        }
+            instructions,
+            exception_table,
+        })
     }
 }
 
-fn code_attribute(
-    access_flags: u16,
-    attributes: &mut Vec<AttributeInfo>,
-    constant_pool: &ConstantPool) -> CodeAttribute
-{
-    if access_flags & (ACC_NATIVE | ACC_ABSTRACT) != 0 {
-        return CodeAttribute::default()
-    }
+/// Refers the exception table entry to indices of instructions rather than
+/// offsets in the code.
+fn exception_handler(
+    entry: &ExceptionTableEntry,
+    offset: usize,
+    indices: &[Option<usize>],
+) -> Result<ExceptionTableEntry, ClassFormatError> {
+    let index = |pc: usize| {
+        indices.get(pc).copied().flatten().ok_or_else(|| {
+            let kind = ClassFormatErrorKind::ExceptionTable { pc };
+            ClassFormatError::new(offset, kind)
+        })
+    };
+
+    Ok(ExceptionTableEntry {
+        start_pc: index(entry.start_pc)?,
+        end_pc: index(entry.end_pc)?,
+        handler_pc: index(entry.handler_pc)?,
+        catch_type: entry.catch_type,
+    })
+}
 
-    let code_attribute_index = attributes.iter()
-        .map(|info| info.attribute_name_index)
-        .map(|index| constant_pool.utf8(index))
-        .enumerate()
-        .find(|(_, name)| name == CODE_ATTRIBUTE_NAME)
-        .map(|(index, _)| index)
-        .unwrap();
+fn code_attribute(method_info: &mut MethodInfo) -> Result<CodeAttribute, ClassFormatError> {
+    if method_info.access_flags & (ACC_NATIVE | ACC_ABSTRACT) != 0 {
+        return Ok(CodeAttribute::default());
+    }
 
-    let attribute_info = attributes.swap_remove(code_attribute_index);
-    BufReader::new(&attribute_info.info[..]).into()
+    let attributes = &mut method_info.attributes;
+    let code_attribute_index = attributes
+        .iter()
+        .position(|info| matches!(info.attribute, Attribute::Code(_)))
+        .ok_or_else(|| {
+            let kind = ClassFormatErrorKind::MissingAttribute {
+                expected: CODE_ATTRIBUTE_NAME,
+            };
+            ClassFormatError::new(method_info.offset, kind)
+        })?;
+
+    match attributes.swap_remove(code_attribute_index).attribute {
+        Attribute::Code(code) => Ok(code),
+        _ => unreachable!(),
+    }
 }
@@ -0,0 +1,440 @@
+use crate::class::descriptor_class_name;
+use crate::class_file::ReferenceKind;
+use crate::descriptor::{FieldDescriptor, MethodDescriptor};
+use crate::heap::Reference;
+use crate::r#type::Type;
+use crate::signature::{field_signature, method_description};
+use crate::throwable::{
+    Throwable, ILLEGAL_ACCESS_EXCEPTION, INCOMPATIBLE_CLASS_CHANGE_ERROR, NO_SUCH_FIELD_ERROR,
+    NO_SUCH_FIELD_EXCEPTION, NO_SUCH_METHOD_ERROR, NO_SUCH_METHOD_EXCEPTION,
+    WRONG_METHOD_TYPE_EXCEPTION,
+};
+use crate::vm::VirtualMachine;
+
+pub(crate) const METHOD_HANDLE: &str = "java/lang/invoke/MethodHandle";
+const METHOD_TYPE: &str = "java/lang/invoke/MethodType";
+const DIRECT_METHOD_HANDLE: &str = "java/lang/invoke/DirectMethodHandle";
+const BOUND_METHOD_HANDLE: &str = "java/lang/invoke/BoundMethodHandle";
+const ADAPTED_METHOD_HANDLE: &str = "java/lang/invoke/AdaptedMethodHandle";
+
+const TYPE: &str = "type:Ljava/lang/invoke/MethodType;";
+const RTYPE: &str = "rtype:Ljava/lang/Class;";
+const PTYPES: &str = "ptypes:[Ljava/lang/Class;";
+const KIND: &str = "kind:I";
+const CLAZZ: &str = "clazz:Ljava/lang/Class;";
+const NAME: &str = "name:Ljava/lang/String;";
+const DESCRIPTOR: &str = "descriptor:Ljava/lang/String;";
+const TARGET: &str = "target:Ljava/lang/invoke/MethodHandle;";
+const ARGUMENT: &str = "argument:Ljava/lang/Object;";
+
+/// How a method handle invokes what it refers to, by its class.
+pub(crate) enum Form {
+    /// Invokes the method or accesses the field of the class with the name
+    /// and descriptor, the way the bytecode for the kind of reference does.
+    Direct {
+        kind: ReferenceKind,
+        class_name: String,
+        name_and_type: String,
+    },
+    /// Invokes the target with the argument inserted before the others.
+    Bound { target: Reference, argument: Type },
+    /// Invokes the target, converting the arguments to its type and the
+    /// return value back.
+    Adapted { target: Reference },
+}
+
+/// Creates a `java.lang.invoke.MethodType` with the descriptor, resolving
+/// the classes it names (JVMS 5.4.3.5).
+pub(crate) fn method_type(
+    vm: &mut VirtualMachine,
+    descriptor: &MethodDescriptor,
+) -> Result<Reference, Throwable> {
+    let return_type = match &descriptor.return_type {
+        Some(return_type) => return_type.to_string(),
+        None => "V".to_string(),
+    };
+    // the mirrors are never collected, unlike the array before the method
+    // type refers to it
+    let rtype = vm.mirror(&return_type)?;
+    let class_array = vm.resolve_class("[Ljava/lang/Class;")?;
+    let ptypes = vm.allocate_array(class_array, descriptor.parameters.len())?;
+    vm.with_handles(&[Type::Reference(ptypes)], |vm| {
+        for (index, parameter) in descriptor.parameters.iter().enumerate() {
+            let ptype = vm.mirror(&parameter.to_string())?;
+            vm.heap.get_mut(ptypes).fields[index] = Type::Reference(ptype);
+        }
+
+        let class = vm.resolve_class(METHOD_TYPE)?;
+        let method_type = vm.allocate(class)?;
+        set_field(vm, method_type, RTYPE, Type::Reference(rtype));
+        set_field(vm, method_type, PTYPES, Type::Reference(ptypes));
+        Ok(method_type)
+    })
+}
+
+/// The descriptor of a `java.lang.invoke.MethodType`.
+pub(crate) fn descriptor(vm: &VirtualMachine, method_type: Reference) -> MethodDescriptor {
+    let mirror_descriptor = |mirror: Type| vm.mirror_descriptor(mirror.reference().unwrap());
+
+    let ptypes = field(vm, method_type, PTYPES).reference().unwrap();
+    let parameters = vm
+        .heap
+        .get(ptypes)
+        .fields
+        .iter()
+        .map(|&ptype| FieldDescriptor::parse(&mirror_descriptor(ptype)).unwrap())
+        .collect();
+    let return_type = FieldDescriptor::parse(&mirror_descriptor(field(vm, method_type, RTYPE)));
+    MethodDescriptor {
+        parameters,
+        return_type,
+    }
+}
+
+/// The type of the method handle.
+pub(crate) fn handle_type(vm: &VirtualMachine, handle: Reference) -> MethodDescriptor {
+    let method_type = field(vm, handle, TYPE).reference().unwrap();
+    descriptor(vm, method_type)
+}
+
+pub(crate) fn form(vm: &VirtualMachine, handle: Reference) -> Form {
+    let class_name = vm.heap.get(handle).class.this_class();
+    match class_name.as_str() {
+        DIRECT_METHOD_HANDLE => {
+            let kind = field(vm, handle, KIND).int() as u8;
+            let clazz = field(vm, handle, CLAZZ).reference().unwrap();
+            let name = field(vm, handle, NAME).reference().unwrap();
+            let descriptor = field(vm, handle, DESCRIPTOR).reference().unwrap();
+            Form::Direct {
+                kind: ReferenceKind::try_from(kind).unwrap(),
+                class_name: descriptor_class_name(&vm.mirror_descriptor(clazz))
+                    .unwrap()
+                    .to_string(),
+                name_and_type: format!("{}:{}", vm.string_value(name), vm.string_value(descriptor)),
+            }
+        }
+        BOUND_METHOD_HANDLE => Form::Bound {
+            target: field(vm, handle, TARGET).reference().unwrap(),
+            argument: field(vm, handle, ARGUMENT),
+        },
+        ADAPTED_METHOD_HANDLE => Form::Adapted {
+            target: field(vm, handle, TARGET).reference().unwrap(),
+        },
+        _ => unreachable!("unknown method handle class {}", class_name),
+    }
+}
+
+/// Creates a direct method handle for the field or method of the class with
+/// the name and type, resolving it (JVMS 5.4.3.5).
+pub(crate) fn direct(
+    vm: &mut VirtualMachine,
+    kind: ReferenceKind,
+    class_name: &str,
+    name_and_type: &str,
+) -> Result<Reference, Throwable> {
+    let class = vm.resolve_class(class_name)?;
+    let (name, descriptor) = name_and_type.split_once(':').unwrap();
+    let receiver = FieldDescriptor::parse(&class_descriptor(class_name)).unwrap();
+    let field_type = || FieldDescriptor::parse(descriptor).unwrap();
+
+    let handle_type = match kind {
+        ReferenceKind::GetField => {
+            vm.resolve_instance_field(class_name, name_and_type)?;
+            MethodDescriptor {
+                parameters: vec![receiver],
+                return_type: Some(field_type()),
+            }
+        }
+        ReferenceKind::GetStatic => {
+            vm.resolve_static_field(class_name, name_and_type)?;
+            MethodDescriptor {
+                parameters: vec![],
+                return_type: Some(field_type()),
+            }
+        }
+        ReferenceKind::PutField => {
+            vm.resolve_instance_field(class_name, name_and_type)?;
+            MethodDescriptor {
+                parameters: vec![receiver, field_type()],
+                return_type: None,
+            }
+        }
+        ReferenceKind::PutStatic => {
+            vm.resolve_static_field(class_name, name_and_type)?;
+            MethodDescriptor {
+                parameters: vec![field_type()],
+                return_type: None,
+            }
+        }
+        _ => {
+            let interface = match kind {
+                ReferenceKind::InvokeVirtual | ReferenceKind::NewInvokeSpecial => false,
+                ReferenceKind::InvokeInterface => true,
+                _ => class.is_interface(),
+            };
+            // a constructor is never inherited
+            let method = match kind {
+                ReferenceKind::NewInvokeSpecial => class.method(name_and_type).cloned(),
+                _ => Some(vm.resolve_method(class_name, name_and_type, interface)?.1),
+            };
+            let method = method.ok_or_else(|| {
+                let description = method_description(class_name, name_and_type);
+                Throwable::new(NO_SUCH_METHOD_ERROR, &description)
+            })?;
+            let static_expected = kind == ReferenceKind::InvokeStatic;
+            if method.is_static() != static_expected
+                || method.is_initializer() != (kind == ReferenceKind::NewInvokeSpecial)
+            {
+                let expected = if static_expected {
+                    "static"
+                } else {
+                    "non-static"
+                };
+                let message = format!("Expected {} method {}.{}", expected, class_name, name);
+                return Err(Throwable::new(INCOMPATIBLE_CLASS_CHANGE_ERROR, &message));
+            }
+
+            let mut handle_type = method.descriptor.clone();
+            match kind {
+                ReferenceKind::InvokeStatic => (),
+                ReferenceKind::NewInvokeSpecial => handle_type.return_type = Some(receiver),
+                _ => handle_type.parameters.insert(0, receiver),
+            }
+            handle_type
+        }
+    };
+
+    // the mirror and the interned strings are never collected, unlike the
+    // method type before the handle refers to it
+    let method_type = method_type(vm, &handle_type)?;
+    vm.with_handles(&[Type::Reference(method_type)], |vm| {
+        let clazz = vm.mirror(&class_descriptor(class_name))?;
+        let name = vm.intern_str(name)?;
+        let descriptor = vm.intern_str(descriptor)?;
+
+        let class = vm.resolve_class(DIRECT_METHOD_HANDLE)?;
+        let handle = vm.allocate(class)?;
+        set_field(vm, handle, TYPE, Type::Reference(method_type));
+        set_field(vm, handle, KIND, Type::Int(kind as i32));
+        set_field(vm, handle, CLAZZ, Type::Reference(clazz));
+        set_field(vm, handle, NAME, Type::Reference(name));
+        set_field(vm, handle, DESCRIPTOR, Type::Reference(descriptor));
+        Ok(handle)
+    })
+}
+
+/// Whether `asType` can adapt a method handle of the type to the new one,
+/// passing each argument of the new type to the handle and its return value
+/// back.
+pub(crate) fn adaptable(
+    vm: &mut VirtualMachine,
+    handle_type: &MethodDescriptor,
+    new_type: &MethodDescriptor,
+) -> bool {
+    if handle_type.parameters.len() != new_type.parameters.len() {
+        return false;
+    }
+
+    let parameters = new_type.parameters.iter().zip(&handle_type.parameters);
+    for (from, to) in parameters {
+        if !convertible(vm, from, to) {
+            return false;
+        }
+    }
+    match (&handle_type.return_type, &new_type.return_type) {
+        (Some(from), Some(to)) => convertible(vm, from, to),
+        // the value is dropped, or null or zero is introduced
+        _ => true,
+    }
+}
+
+/// Whether a value of the type converts to the other one, by widening,
+/// boxing, unboxing or casting it.
+fn convertible(vm: &mut VirtualMachine, from: &FieldDescriptor, to: &FieldDescriptor) -> bool {
+    match (from.wrapper(), to.wrapper()) {
+        (Some(_), Some(_)) => widens(from, to),
+        (Some((wrapper, _)), None) => vm.is_assignable(wrapper, &to.class_name().unwrap()),
+        (None, Some((wrapper, _))) => {
+            let class_name = from.class_name().unwrap();
+            match FieldDescriptor::unwrapped(&class_name) {
+                Some(primitive) => widens(&primitive, to),
+                // a supertype of the wrapper, which the value is cast to
+                None => vm.is_assignable(wrapper, &class_name),
+            }
+        }
+        (None, None) => true,
+    }
+}
+
+/// Whether an identity or widening primitive conversion (JLS 5.1.2) turns
+/// values of the primitive type into the other one.
+pub(crate) fn widens(from: &FieldDescriptor, to: &FieldDescriptor) -> bool {
+    use FieldDescriptor::*;
+
+    from == to
+        || matches!(
+            (from, to),
+            (Byte, Short | Int | Long | Float | Double)
+                | (Short | Char, Int | Long | Float | Double)
+                | (Int, Long | Float | Double)
+                | (Long, Float | Double)
+                | (Float, Double)
+        )
+}
+
+/// Widens the primitive value to the type.
+pub(crate) fn widen(value: Type, to: &FieldDescriptor) -> Type {
+    match (value, to) {
+        (Type::Int(x), FieldDescriptor::Long) => Type::Long(x as i64),
+        (Type::Int(x), FieldDescriptor::Float) => Type::Float(x as f32),
+        (Type::Int(x), FieldDescriptor::Double) => Type::Double(x as f64),
+        (Type::Long(x), FieldDescriptor::Float) => Type::Float(x as f32),
+        (Type::Long(x), FieldDescriptor::Double) => Type::Double(x as f64),
+        (Type::Float(x), FieldDescriptor::Double) => Type::Double(x as f64),
+        (value, _) => value,
+    }
+}
+
+/// The value a wrapper object boxes along with its primitive type, `None`
+/// if the object is not a wrapper.
+pub(crate) fn unbox(vm: &VirtualMachine, object: Reference) -> Option<(Type, FieldDescriptor)> {
+    let class_name = vm.heap.get(object).class.this_class();
+    let primitive = FieldDescriptor::unwrapped(&class_name)?;
+    let value = field(vm, object, &format!("value:{}", primitive));
+    Some((value, primitive))
+}
+
+pub(crate) fn wrong_method_type(message: &str) -> Throwable {
+    Throwable::new(WRONG_METHOD_TYPE_EXCEPTION, message)
+}
+
+/// Formats the type the way `MethodType.toString` does, e.g.
+/// `(int,String)void`.
+pub(crate) fn type_string(descriptor: &MethodDescriptor) -> String {
+    let parameters = descriptor
+        .parameters
+        .iter()
+        .map(|parameter| simple_name(&parameter.to_string()))
+        .collect::<Vec<_>>();
+    let return_type = match &descriptor.return_type {
+        Some(return_type) => simple_name(&return_type.to_string()),
+        None => "void".to_string(),
+    };
+    format!("({}){}", parameters.join(","), return_type)
+}
+
+/// The name of the type the way `Class.getSimpleName` returns it, e.g. `int`
+/// or `String[]`.
+pub(crate) fn simple_name(descriptor: &str) -> String {
+    let name = field_signature(descriptor, false).unwrap();
+    let start = name.rfind(['.', '$']).map_or(0, |index| index + 1);
+    name[start..].to_string()
+}
+
+/// The descriptor of the class with the name, such as `Ljava/lang/String;`
+/// or `[I`.
+pub(crate) fn class_descriptor(class_name: &str) -> String {
+    if class_name.starts_with('[') {
+        class_name.to_string()
+    } else {
+        format!("L{};", class_name)
+    }
+}
+
+fn field(vm: &VirtualMachine, object: Reference, name_and_type: &str) -> Type {
+    let object = vm.heap.get(object);
+    object.fields[object.class.instance_fields[name_and_type]]
+}
+
+fn set_field(vm: &mut VirtualMachine, object: Reference, name_and_type: &str, value: Type) {
+    let object = vm.heap.get_mut(object);
+    let slot = object.class.instance_fields[name_and_type];
+    object.fields[slot] = value;
+}
+
+pub(crate) fn method_type_to_string(
+    vm: &mut VirtualMachine,
+    args: &[Type],
+) -> Result<Type, Throwable> {
+    let descriptor = descriptor(vm, args[0].reference().unwrap());
+    let string = vm.new_string(&type_string(&descriptor))?;
+    Ok(Type::Reference(string))
+}
+
+pub(crate) fn to_method_descriptor_string(
+    vm: &mut VirtualMachine,
+    args: &[Type],
+) -> Result<Type, Throwable> {
+    let descriptor = descriptor(vm, args[0].reference().unwrap());
+    let string = vm.new_string(&descriptor.to_string())?;
+    Ok(Type::Reference(string))
+}
+
+pub(crate) fn can_convert(vm: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    let handle_type = descriptor(vm, args[0].reference().unwrap());
+    let new_type = descriptor(vm, args[1].reference().unwrap());
+    Ok(Type::Int(adaptable(vm, &handle_type, &new_type) as i32))
+}
+
+/// `DirectMethodHandle.make`, which the `Lookup.find` methods of methods
+/// call.
+pub(crate) fn make(vm: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    let descriptor = descriptor(vm, args[3].reference().unwrap());
+    make_direct(vm, args, &descriptor.to_string(), &type_string(&descriptor))
+}
+
+/// `DirectMethodHandle.makeField`, which the `Lookup.find` methods of fields
+/// call.
+pub(crate) fn make_field(vm: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    let descriptor = vm.mirror_descriptor(args[3].reference().unwrap());
+    let type_string = format!("/{}", simple_name(&descriptor));
+    make_direct(vm, args, &descriptor, &type_string)
+}
+
+/// Creates the direct method handle for a `Lookup`, which reports the
+/// member it does not find with an exception rather than an error.
+fn make_direct(
+    vm: &mut VirtualMachine,
+    args: &[Type],
+    descriptor: &str,
+    type_string: &str,
+) -> Result<Type, Throwable> {
+    let kind = ReferenceKind::try_from(args[0].int() as u8).unwrap();
+    let refc = vm.mirror_descriptor(args[1].reference().unwrap());
+    let name = vm.string_value(args[2].reference().unwrap());
+    let class_name = descriptor_class_name(&refc).unwrap_or(&refc).to_string();
+
+    let name_and_type = format!("{}:{}", name, descriptor);
+    let handle = direct(vm, kind, &class_name, &name_and_type).map_err(|error| {
+        let (exception, member) = match error.class_name.as_str() {
+            NO_SUCH_METHOD_ERROR => (NO_SUCH_METHOD_EXCEPTION, "no such method"),
+            NO_SUCH_FIELD_ERROR => (NO_SUCH_FIELD_EXCEPTION, "no such field"),
+            INCOMPATIBLE_CLASS_CHANGE_ERROR => (ILLEGAL_ACCESS_EXCEPTION, "no such member"),
+            _ => return error,
+        };
+        let message = format!(
+            "{}: {}.{}{}/{}",
+            member,
+            class_name.replace('/', "."),
+            name,
+            type_string,
+            kind_name(kind)
+        );
+        Throwable::new(exception, &message)
+    })?;
+    Ok(Type::Reference(handle))
+}
+
+fn kind_name(kind: ReferenceKind) -> &'static str {
+    match kind {
+        ReferenceKind::GetField => "getField",
+        ReferenceKind::GetStatic => "getStatic",
+        ReferenceKind::PutField => "putField",
+        ReferenceKind::PutStatic => "putStatic",
+        ReferenceKind::InvokeVirtual => "invokeVirtual",
+        ReferenceKind::InvokeStatic => "invokeStatic",
+        ReferenceKind::InvokeSpecial => "invokeSpecial",
+        ReferenceKind::NewInvokeSpecial => "newInvokeSpecial",
+        ReferenceKind::InvokeInterface => "invokeInterface",
+    }
+}
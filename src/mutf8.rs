@@ -0,0 +1,128 @@
+use std::char::{decode_utf16, REPLACEMENT_CHARACTER};
+
+/// Decodes the "modified UTF-8" of `CONSTANT_Utf8_info` (JVMS 4.4.7). Unpaired
+/// surrogates have no Rust representation and decode to U+FFFD, so callers that
+/// need to reproduce the input must keep the original bytes. On malformed input
+/// the offset of the offending byte is returned.
+pub fn decode(bytes: &[u8]) -> Result<String, usize> {
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let x = bytes[i];
+        let (unit, length) = match x {
+            0x01..=0x7f => (x as u16, 1),
+            0xc0..=0xdf => {
+                let y = continuation(bytes, i + 1)?;
+                (((x as u16 & 0x1f) << 6) | y, 2)
+            }
+            0xe0..=0xef => {
+                let y = continuation(bytes, i + 1)?;
+                let z = continuation(bytes, i + 2)?;
+                (((x as u16 & 0x0f) << 12) | (y << 6) | z, 3)
+            }
+            _ => return Err(i),
+        };
+
+        units.push(unit);
+        i += length;
+    }
+
+    Ok(decode_utf16(units)
+        .map(|c| c.unwrap_or(REPLACEMENT_CHARACTER))
+        .collect())
+}
+
+pub fn encode(value: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(value.len());
+
+    for unit in value.encode_utf16() {
+        match unit {
+            0x0001..=0x007f => bytes.push(unit as u8),
+            0x0000 | 0x0080..=0x07ff => {
+                bytes.push(0xc0 | (unit >> 6) as u8);
+                bytes.push(0x80 | (unit & 0x3f) as u8);
+            }
+            _ => {
+                bytes.push(0xe0 | (unit >> 12) as u8);
+                bytes.push(0x80 | ((unit >> 6) & 0x3f) as u8);
+                bytes.push(0x80 | (unit & 0x3f) as u8);
+            }
+        }
+    }
+
+    bytes
+}
+
+fn continuation(bytes: &[u8], i: usize) -> Result<u16, usize> {
+    match bytes.get(i) {
+        Some(&x) if x & 0xc0 == 0x80 => Ok(x as u16 & 0x3f),
+        Some(_) => Err(i),
+        None => Err(bytes.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_nul_as_two_bytes() {
+        assert_eq!(encode("a\0b"), [b'a', 0xc0, 0x80, b'b']);
+        assert_eq!(decode(&[b'a', 0xc0, 0x80, b'b']).unwrap(), "a\0b");
+    }
+
+    #[test]
+    fn rejects_raw_nul() {
+        assert_eq!(decode(&[b'a', 0x00]), Err(1));
+    }
+
+    #[test]
+    fn encodes_supplementary_characters_as_surrogate_pairs() {
+        let bytes = [0xed, 0xa0, 0xbd, 0xed, 0xb8, 0x80];
+        assert_eq!(encode("\u{1f600}"), bytes);
+        assert_eq!(decode(&bytes).unwrap(), "\u{1f600}");
+    }
+
+    #[test]
+    fn rejects_four_byte_utf8() {
+        assert_eq!(decode("\u{1f600}".as_bytes()), Err(0));
+    }
+
+    #[test]
+    fn round_trips_two_and_three_byte_characters() {
+        let value = "é Ω ∑ ёжик";
+        assert_eq!(decode(&encode(value)).unwrap(), value);
+        // the same as standard UTF-8 outside of NUL and supplementary characters
+        assert_eq!(encode(value), value.as_bytes());
+    }
+
+    #[test]
+    fn decodes_lone_surrogates_lossily() {
+        // a high surrogate without the low one, and a low one on its own
+        let bytes = [b'a', 0xed, 0xa0, 0xbd, b'b', 0xed, 0xb8, 0x80];
+        let value = decode(&bytes).unwrap();
+        assert_eq!(value, "a\u{fffd}b\u{fffd}");
+        assert_ne!(encode(&value), bytes);
+    }
+
+    #[test]
+    fn rejects_malformed_sequences() {
+        // a continuation byte on its own
+        assert_eq!(decode(&[b'a', 0x80]), Err(1));
+        // a lead byte followed by another lead byte
+        assert_eq!(decode(&[0xc3, 0xc3]), Err(1));
+        assert_eq!(decode(&[0xe2, 0x88, b'x']), Err(2));
+        // lead bytes of four-byte sequences and beyond
+        for x in 0xf0..=0xff {
+            assert_eq!(decode(&[x, 0x80, 0x80, 0x80]), Err(0));
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_sequences() {
+        assert_eq!(decode(&[b'a', 0xc3]), Err(2));
+        assert_eq!(decode(&[0xe2]), Err(1));
+        assert_eq!(decode(&[0xe2, 0x88]), Err(2));
+    }
+}
@@ -1,17 +1,20 @@
 use crate::class::Class;
 use crate::r#type::Type;
+use crate::throwable::Throwable;
+use crate::vm::VirtualMachine;
 use std::collections::HashMap;
+use std::rc::Rc;
 
-pub type NativeMethod = fn(&[Type]) -> Type;
+pub type NativeMethod = fn(&mut VirtualMachine, &[Type]) -> Result<Type, Throwable>;
 
 #[derive(Default)]
 pub struct Registry {
-    pub(crate) classes: HashMap<String, Class>,
+    pub(crate) classes: HashMap<String, Rc<Class>>,
     pub(crate) native_methods: HashMap<String, NativeMethod>,
 }
 
 impl Registry {
-    pub fn add_class(&mut self, class: Class) -> &mut Self {
+    pub fn add_class(&mut self, class: Rc<Class>) -> &mut Self {
         self.classes.insert(class.this_class(), class);
         self
     }
@@ -21,14 +24,11 @@ impl Registry {
         self
     }
 
-    pub fn class(&self, name: &str) -> &Class {
-        &self.classes.get(name).expect(&*format!(
-            "java.lang.ClassNotFoundException: {}",
-            name.replace("/", ".")
-        ))
+    pub fn class(&self, name: &str) -> Option<&Rc<Class>> {
+        self.classes.get(name)
     }
 
-    pub fn native_method(&self, name: &str) -> &NativeMethod {
-        &self.native_methods[name]
+    pub fn native_method(&self, name: &str) -> Option<&NativeMethod> {
+        self.native_methods.get(name)
     }
 }
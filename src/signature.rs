@@ -0,0 +1,242 @@
+pub(crate) struct ClassSignature {
+    pub(crate) type_parameters: String,
+    pub(crate) super_class: String,
+    pub(crate) interfaces: Vec<String>,
+}
+
+pub(crate) struct MethodSignature {
+    pub(crate) type_parameters: String,
+    pub(crate) parameters: Vec<String>,
+    pub(crate) return_type: String,
+    pub(crate) exceptions: Vec<String>,
+}
+
+pub(crate) fn class_signature(signature: &str, verbose: bool) -> Option<ClassSignature> {
+    let mut parser = SignatureParser::new(signature, verbose);
+    let type_parameters = parser.type_parameters()?;
+    let super_class = parser.reference_type()?;
+
+    let mut interfaces = vec![];
+    while !parser.is_empty() {
+        interfaces.push(parser.reference_type()?);
+    }
+
+    Some(ClassSignature {
+        type_parameters,
+        super_class,
+        interfaces,
+    })
+}
+
+pub(crate) fn method_signature(signature: &str, verbose: bool) -> Option<MethodSignature> {
+    let mut parser = SignatureParser::new(signature, verbose);
+    let type_parameters = parser.type_parameters()?;
+
+    parser.expect(b'(')?;
+    let mut parameters = vec![];
+    while !parser.eat(b')') {
+        parameters.push(parser.java_type()?);
+    }
+    let return_type = parser.java_type()?;
+
+    let mut exceptions = vec![];
+    while parser.eat(b'^') {
+        exceptions.push(parser.reference_type()?);
+    }
+
+    parser.is_empty().then_some(MethodSignature {
+        type_parameters,
+        parameters,
+        return_type,
+        exceptions,
+    })
+}
+
+pub(crate) fn field_signature(signature: &str, verbose: bool) -> Option<String> {
+    let mut parser = SignatureParser::new(signature, verbose);
+    let java_type = parser.java_type()?;
+    parser.is_empty().then_some(java_type)
+}
+
+/// Describes a method the way HotSpot error messages do, e.g.
+/// `'void java.lang.System.exit(int)'`.
+pub(crate) fn method_description(class_name: &str, name_and_type: &str) -> String {
+    let class_name = class_name.replace('/', ".");
+    let (name, descriptor) = name_and_type.split_once(':').unwrap_or((name_and_type, ""));
+    describe(&format!("{}.{}", class_name, name), descriptor)
+}
+
+/// Describes a method without its class, e.g. `'void exit(int)'`.
+pub(crate) fn simple_method_description(name_and_type: &str) -> String {
+    let (name, descriptor) = name_and_type.split_once(':').unwrap_or((name_and_type, ""));
+    describe(name, descriptor)
+}
+
+fn describe(name: &str, descriptor: &str) -> String {
+    match method_signature(descriptor, false) {
+        Some(signature) => format!(
+            "'{} {}({})'",
+            signature.return_type,
+            name,
+            signature.parameters.join(", ")
+        ),
+        None => format!("'{}{}'", name, descriptor),
+    }
+}
+
+struct SignatureParser<'a> {
+    signature: &'a str,
+    position: usize,
+    verbose: bool,
+}
+
+impl<'a> SignatureParser<'a> {
+    fn new(signature: &'a str, verbose: bool) -> SignatureParser<'a> {
+        SignatureParser {
+            signature,
+            position: 0,
+            verbose,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position == self.signature.len()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.signature.as_bytes().get(self.position).copied()
+    }
+
+    fn eat(&mut self, expected: u8) -> bool {
+        let matches = self.peek() == Some(expected);
+        if matches {
+            self.position += 1;
+        }
+        matches
+    }
+
+    fn expect(&mut self, expected: u8) -> Option<()> {
+        self.eat(expected).then_some(())
+    }
+
+    fn identifier(&mut self, delimiters: &[u8]) -> Option<&'a str> {
+        let start = self.position;
+        while !delimiters.contains(&self.peek()?) {
+            self.position += 1;
+        }
+        (self.position > start).then(|| &self.signature[start..self.position])
+    }
+
+    fn type_parameters(&mut self) -> Option<String> {
+        if !self.eat(b'<') {
+            return Some(String::new());
+        }
+
+        let mut type_parameters = vec![];
+        while !self.eat(b'>') {
+            let mut type_parameter = self.identifier(b":")?.to_string();
+            let mut separator = " extends ";
+
+            self.expect(b':')?;
+            if !matches!(self.peek(), Some(b':') | Some(b'>')) {
+                let class_bound = self.reference_type()?;
+                if self.verbose || class_bound != "java.lang.Object" {
+                    type_parameter.push_str(separator);
+                    type_parameter.push_str(&class_bound);
+                    separator = " & ";
+                }
+            }
+
+            while self.eat(b':') {
+                type_parameter.push_str(separator);
+                type_parameter.push_str(&self.reference_type()?);
+                separator = " & ";
+            }
+
+            type_parameters.push(type_parameter);
+        }
+
+        Some(format!("<{}>", type_parameters.join(", ")))
+    }
+
+    fn java_type(&mut self) -> Option<String> {
+        let base_type = match self.peek()? {
+            b'B' => "byte",
+            b'C' => "char",
+            b'D' => "double",
+            b'F' => "float",
+            b'I' => "int",
+            b'J' => "long",
+            b'S' => "short",
+            b'Z' => "boolean",
+            b'V' => "void",
+            _ => return self.reference_type(),
+        };
+
+        self.position += 1;
+        Some(base_type.to_string())
+    }
+
+    fn reference_type(&mut self) -> Option<String> {
+        match self.peek()? {
+            b'L' => {
+                self.position += 1;
+                self.class_type()
+            }
+            b'T' => {
+                self.position += 1;
+                let name = self.identifier(b";")?;
+                self.expect(b';')?;
+                Some(name.to_string())
+            }
+            b'[' => {
+                self.position += 1;
+                Some(format!("{}[]", self.java_type()?))
+            }
+            _ => None,
+        }
+    }
+
+    fn class_type(&mut self) -> Option<String> {
+        let mut class_type = String::new();
+
+        loop {
+            class_type.push_str(&self.identifier(b";<.")?.replace('/', "."));
+
+            if self.eat(b'<') {
+                let mut type_arguments = vec![];
+                while !self.eat(b'>') {
+                    type_arguments.push(self.type_argument()?);
+                }
+                class_type.push('<');
+                class_type.push_str(&type_arguments.join(", "));
+                class_type.push('>');
+            }
+
+            if self.eat(b';') {
+                return Some(class_type);
+            }
+
+            self.expect(b'.')?;
+            class_type.push('.');
+        }
+    }
+
+    fn type_argument(&mut self) -> Option<String> {
+        match self.peek()? {
+            b'*' => {
+                self.position += 1;
+                Some("?".to_string())
+            }
+            b'+' => {
+                self.position += 1;
+                Some(format!("? extends {}", self.reference_type()?))
+            }
+            b'-' => {
+                self.position += 1;
+                Some(format!("? super {}", self.reference_type()?))
+            }
+            _ => self.reference_type(),
+        }
+    }
+}
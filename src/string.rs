@@ -0,0 +1,55 @@
+use crate::descriptor::FieldDescriptor;
+use crate::floating_decimal::{double_to_string, float_to_string};
+use crate::r#type::Type;
+
+pub(crate) const STRING: &str = "java/lang/String";
+pub(crate) const VALUE: &str = "value:[B";
+pub(crate) const CODER: &str = "coder:B";
+
+const LATIN1: i32 = 0;
+const UTF16: i32 = 1;
+
+/// Encodes the characters the way `java.lang.String` keeps them with compact
+/// strings: one byte per character if all of them are Latin-1, two bytes per
+/// UTF-16 code unit, low byte first, otherwise. Returns the bytes along with
+/// the coder.
+pub(crate) fn encode(value: &str) -> (Vec<u8>, i32) {
+    if value.chars().all(|c| (c as u32) <= 0xff) {
+        let bytes = value.chars().map(|c| c as u8).collect();
+        return (bytes, LATIN1);
+    }
+
+    let bytes = value.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    (bytes, UTF16)
+}
+
+/// Decodes the bytes of a `java.lang.String` with the coder. Unpaired
+/// surrogates decode to U+FFFD.
+pub(crate) fn decode(bytes: &[u8], coder: i32) -> String {
+    if coder == LATIN1 {
+        return bytes.iter().map(|&byte| byte as char).collect();
+    }
+
+    let units = bytes
+        .chunks_exact(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+        .collect::<Vec<_>>();
+    String::from_utf16_lossy(&units)
+}
+
+/// Formats the primitive value the way `String.valueOf` does, `None` for a
+/// reference, which takes its `toString`.
+pub(crate) fn value_of(value: Type, descriptor: &FieldDescriptor) -> Option<String> {
+    let string = match descriptor {
+        FieldDescriptor::Boolean => (value.int() != 0).to_string(),
+        FieldDescriptor::Char => String::from_utf16_lossy(&[value.int() as u16]),
+        FieldDescriptor::Byte | FieldDescriptor::Short | FieldDescriptor::Int => {
+            value.int().to_string()
+        }
+        FieldDescriptor::Long => value.long().to_string(),
+        FieldDescriptor::Float => float_to_string(value.float()),
+        FieldDescriptor::Double => double_to_string(value.double()),
+        FieldDescriptor::Object(_) | FieldDescriptor::Array(_) => return None,
+    };
+    Some(string)
+}
@@ -1,270 +1,774 @@
-use crate::constant_pool::ConstantPool;
+use crate::call_site::{self, CallSite, Recipe};
+use crate::class::{descriptor_class_name, Class, InitializationState};
+use crate::class_file::ReferenceKind;
+use crate::descriptor::{FieldDescriptor, MethodDescriptor};
+use crate::dispatch;
 use crate::frame::Frame;
+use crate::heap::{Heap, Reference};
+use crate::instruction::{Instruction, MemberRef};
+use crate::method::Method;
+use crate::method_handle::{self, Form};
 use crate::r#type::Type;
-use crate::r#type::Type::{Double, Float, Int, Long, Void};
-use crate::registry::Registry;
-use regex::Regex;
-
-const NOP: u8 = 0x00;
-const ICONST_M1: u8 = 0x02;
-const ICONST_0: u8 = 0x03;
-const ICONST_1: u8 = 0x04;
-const ICONST_2: u8 = 0x05;
-const ICONST_3: u8 = 0x06;
-const ICONST_4: u8 = 0x07;
-const ICONST_5: u8 = 0x08;
-const LCONST_0: u8 = 0x09;
-const LCONST_1: u8 = 0x0a;
-const FCONST_0: u8 = 0x0b;
-const FCONST_1: u8 = 0x0c;
-const FCONST_2: u8 = 0x0d;
-const DCONST_0: u8 = 0x0e;
-const DCONST_1: u8 = 0x0f;
-const BIPUSH: u8 = 0x10;
-const SIPUSH: u8 = 0x11;
-const LDC: u8 = 0x12;
-const LDC_W: u8 = 0x13;
-const LDC2_W: u8 = 0x14;
-const ILOAD: u8 = 0x15;
-const LLOAD: u8 = 0x16;
-const FLOAD: u8 = 0x17;
-const DLOAD: u8 = 0x18;
-const ILOAD_0: u8 = 0x1a;
-const ILOAD_1: u8 = 0x1b;
-const ILOAD_2: u8 = 0x1c;
-const ILOAD_3: u8 = 0x1d;
-const LLOAD_0: u8 = 0x1e;
-const LLOAD_1: u8 = 0x1f;
-const LLOAD_2: u8 = 0x20;
-const LLOAD_3: u8 = 0x21;
-const FLOAD_0: u8 = 0x22;
-const FLOAD_1: u8 = 0x23;
-const FLOAD_2: u8 = 0x24;
-const FLOAD_3: u8 = 0x25;
-const DLOAD_0: u8 = 0x26;
-const DLOAD_1: u8 = 0x27;
-const DLOAD_2: u8 = 0x28;
-const DLOAD_3: u8 = 0x29;
-const ISTORE: u8 = 0x36;
-const LSTORE: u8 = 0x37;
-const FSTORE: u8 = 0x38;
-const DSTORE: u8 = 0x39;
-const ISTORE_0: u8 = 0x3b;
-const ISTORE_1: u8 = 0x3c;
-const ISTORE_2: u8 = 0x3d;
-const ISTORE_3: u8 = 0x3e;
-const LSTORE_0: u8 = 0x3f;
-const LSTORE_1: u8 = 0x40;
-const LSTORE_2: u8 = 0x41;
-const LSTORE_3: u8 = 0x42;
-const FSTORE_0: u8 = 0x43;
-const FSTORE_1: u8 = 0x44;
-const FSTORE_2: u8 = 0x45;
-const FSTORE_3: u8 = 0x46;
-const DSTORE_0: u8 = 0x47;
-const DSTORE_1: u8 = 0x48;
-const DSTORE_2: u8 = 0x49;
-const DSTORE_3: u8 = 0x4a;
-const IADD: u8 = 0x60;
-const LADD: u8 = 0x61;
-const FADD: u8 = 0x62;
-const DADD: u8 = 0x63;
-const ISUB: u8 = 0x64;
-const LSUB: u8 = 0x65;
-const FSUB: u8 = 0x66;
-const DSUB: u8 = 0x67;
-const IMUL: u8 = 0x68;
-const LMUL: u8 = 0x69;
-const FMUL: u8 = 0x6a;
-const DMUL: u8 = 0x6b;
-const IDIV: u8 = 0x6c;
-const LDIV: u8 = 0x6d;
-const FDIV: u8 = 0x6e;
-const DDIV: u8 = 0x6f;
-const IREM: u8 = 0x70;
-const LREM: u8 = 0x71;
-const FREM: u8 = 0x72;
-const DREM: u8 = 0x73;
-const IAND: u8 = 0x7e;
-const LAND: u8 = 0x7f;
-const IOR: u8 = 0x80;
-const LOR: u8 = 0x81;
-const I2F: u8 = 0x86;
-const IFEQ: u8 = 0x99;
-const IFNE: u8 = 0x9a;
-const IFLT: u8 = 0x9b;
-const IFGE: u8 = 0x9c;
-const IFGT: u8 = 0x9d;
-const IFLE: u8 = 0x9e;
-const IF_ICMPEQ: u8 = 0x9f;
-const IF_ICMPNE: u8 = 0xa0;
-const IF_ICMPLT: u8 = 0xa1;
-const IF_ICMPGE: u8 = 0xa2;
-const IF_ICMPGT: u8 = 0xa3;
-const IF_ICMPLE: u8 = 0xa4;
-const GOTO: u8 = 0xa7;
-const IRETURN: u8 = 0xac;
-const LRETURN: u8 = 0xad;
-const FRETURN: u8 = 0xae;
-const DRETURN: u8 = 0xaf;
-const RETURN: u8 = 0xb1;
-const INVOKESTATIC: u8 = 0xb8;
+use crate::r#type::Type::{Double, Float, Int, Long, Null, ReturnAddress, Void};
+use crate::signature::method_description;
+use crate::string::{self, STRING};
+use crate::throwable::{
+    Throwable, ARITHMETIC_EXCEPTION, ARRAY_INDEX_OUT_OF_BOUNDS_EXCEPTION, ARRAY_STORE_EXCEPTION,
+    CLASS_CAST_EXCEPTION, ERROR, EXCEPTION_IN_INITIALIZER_ERROR, INCOMPATIBLE_CLASS_CHANGE_ERROR,
+    INSTANTIATION_ERROR, NEGATIVE_ARRAY_SIZE_EXCEPTION, NO_CLASS_DEF_FOUND_ERROR,
+    NO_SUCH_METHOD_ERROR, NULL_POINTER_EXCEPTION, STACK_OVERFLOW_ERROR, UNSATISFIED_LINK_ERROR,
+};
+use crate::vm::VirtualMachine;
+use std::rc::Rc;
+
+pub(crate) const DEFAULT_STACK_SIZE: usize = 1024 * 1024;
+const CLINIT: &str = "<clinit>:()V";
+const THROWABLE_CAUSE: &str = "cause:Ljava/lang/Throwable;";
+const THROWABLE_DETAIL_MESSAGE: &str = "detailMessage:Ljava/lang/String;";
+const TO_STRING: &str = "toString:()Ljava/lang/String;";
+
+pub(crate) const NOP: u8 = 0x00;
+pub(crate) const ACONST_NULL: u8 = 0x01;
+pub(crate) const ICONST_M1: u8 = 0x02;
+pub(crate) const ICONST_0: u8 = 0x03;
+pub(crate) const ICONST_1: u8 = 0x04;
+pub(crate) const ICONST_2: u8 = 0x05;
+pub(crate) const ICONST_3: u8 = 0x06;
+pub(crate) const ICONST_4: u8 = 0x07;
+pub(crate) const ICONST_5: u8 = 0x08;
+pub(crate) const LCONST_0: u8 = 0x09;
+pub(crate) const LCONST_1: u8 = 0x0a;
+pub(crate) const FCONST_0: u8 = 0x0b;
+pub(crate) const FCONST_1: u8 = 0x0c;
+pub(crate) const FCONST_2: u8 = 0x0d;
+pub(crate) const DCONST_0: u8 = 0x0e;
+pub(crate) const DCONST_1: u8 = 0x0f;
+pub(crate) const BIPUSH: u8 = 0x10;
+pub(crate) const SIPUSH: u8 = 0x11;
+pub(crate) const LDC: u8 = 0x12;
+pub(crate) const LDC_W: u8 = 0x13;
+pub(crate) const LDC2_W: u8 = 0x14;
+pub(crate) const ILOAD: u8 = 0x15;
+pub(crate) const LLOAD: u8 = 0x16;
+pub(crate) const FLOAD: u8 = 0x17;
+pub(crate) const DLOAD: u8 = 0x18;
+pub(crate) const ALOAD: u8 = 0x19;
+pub(crate) const ILOAD_0: u8 = 0x1a;
+pub(crate) const ILOAD_1: u8 = 0x1b;
+pub(crate) const ILOAD_2: u8 = 0x1c;
+pub(crate) const ILOAD_3: u8 = 0x1d;
+pub(crate) const LLOAD_0: u8 = 0x1e;
+pub(crate) const LLOAD_1: u8 = 0x1f;
+pub(crate) const LLOAD_2: u8 = 0x20;
+pub(crate) const LLOAD_3: u8 = 0x21;
+pub(crate) const FLOAD_0: u8 = 0x22;
+pub(crate) const FLOAD_1: u8 = 0x23;
+pub(crate) const FLOAD_2: u8 = 0x24;
+pub(crate) const FLOAD_3: u8 = 0x25;
+pub(crate) const DLOAD_0: u8 = 0x26;
+pub(crate) const DLOAD_1: u8 = 0x27;
+pub(crate) const DLOAD_2: u8 = 0x28;
+pub(crate) const DLOAD_3: u8 = 0x29;
+pub(crate) const ALOAD_0: u8 = 0x2a;
+pub(crate) const ALOAD_1: u8 = 0x2b;
+pub(crate) const ALOAD_2: u8 = 0x2c;
+pub(crate) const ALOAD_3: u8 = 0x2d;
+pub(crate) const IALOAD: u8 = 0x2e;
+pub(crate) const LALOAD: u8 = 0x2f;
+pub(crate) const FALOAD: u8 = 0x30;
+pub(crate) const DALOAD: u8 = 0x31;
+pub(crate) const AALOAD: u8 = 0x32;
+pub(crate) const BALOAD: u8 = 0x33;
+pub(crate) const CALOAD: u8 = 0x34;
+pub(crate) const SALOAD: u8 = 0x35;
+pub(crate) const ISTORE: u8 = 0x36;
+pub(crate) const LSTORE: u8 = 0x37;
+pub(crate) const FSTORE: u8 = 0x38;
+pub(crate) const DSTORE: u8 = 0x39;
+pub(crate) const ASTORE: u8 = 0x3a;
+pub(crate) const ISTORE_0: u8 = 0x3b;
+pub(crate) const ISTORE_1: u8 = 0x3c;
+pub(crate) const ISTORE_2: u8 = 0x3d;
+pub(crate) const ISTORE_3: u8 = 0x3e;
+pub(crate) const LSTORE_0: u8 = 0x3f;
+pub(crate) const LSTORE_1: u8 = 0x40;
+pub(crate) const LSTORE_2: u8 = 0x41;
+pub(crate) const LSTORE_3: u8 = 0x42;
+pub(crate) const FSTORE_0: u8 = 0x43;
+pub(crate) const FSTORE_1: u8 = 0x44;
+pub(crate) const FSTORE_2: u8 = 0x45;
+pub(crate) const FSTORE_3: u8 = 0x46;
+pub(crate) const DSTORE_0: u8 = 0x47;
+pub(crate) const DSTORE_1: u8 = 0x48;
+pub(crate) const DSTORE_2: u8 = 0x49;
+pub(crate) const DSTORE_3: u8 = 0x4a;
+pub(crate) const ASTORE_0: u8 = 0x4b;
+pub(crate) const ASTORE_1: u8 = 0x4c;
+pub(crate) const ASTORE_2: u8 = 0x4d;
+pub(crate) const ASTORE_3: u8 = 0x4e;
+pub(crate) const IASTORE: u8 = 0x4f;
+pub(crate) const LASTORE: u8 = 0x50;
+pub(crate) const FASTORE: u8 = 0x51;
+pub(crate) const DASTORE: u8 = 0x52;
+pub(crate) const AASTORE: u8 = 0x53;
+pub(crate) const BASTORE: u8 = 0x54;
+pub(crate) const CASTORE: u8 = 0x55;
+pub(crate) const SASTORE: u8 = 0x56;
+pub(crate) const POP: u8 = 0x57;
+pub(crate) const POP2: u8 = 0x58;
+pub(crate) const DUP: u8 = 0x59;
+pub(crate) const DUP_X1: u8 = 0x5a;
+pub(crate) const DUP_X2: u8 = 0x5b;
+pub(crate) const DUP2: u8 = 0x5c;
+pub(crate) const DUP2_X1: u8 = 0x5d;
+pub(crate) const DUP2_X2: u8 = 0x5e;
+pub(crate) const SWAP: u8 = 0x5f;
+pub(crate) const IADD: u8 = 0x60;
+pub(crate) const LADD: u8 = 0x61;
+pub(crate) const FADD: u8 = 0x62;
+pub(crate) const DADD: u8 = 0x63;
+pub(crate) const ISUB: u8 = 0x64;
+pub(crate) const LSUB: u8 = 0x65;
+pub(crate) const FSUB: u8 = 0x66;
+pub(crate) const DSUB: u8 = 0x67;
+pub(crate) const IMUL: u8 = 0x68;
+pub(crate) const LMUL: u8 = 0x69;
+pub(crate) const FMUL: u8 = 0x6a;
+pub(crate) const DMUL: u8 = 0x6b;
+pub(crate) const IDIV: u8 = 0x6c;
+pub(crate) const LDIV: u8 = 0x6d;
+pub(crate) const FDIV: u8 = 0x6e;
+pub(crate) const DDIV: u8 = 0x6f;
+pub(crate) const IREM: u8 = 0x70;
+pub(crate) const LREM: u8 = 0x71;
+pub(crate) const FREM: u8 = 0x72;
+pub(crate) const DREM: u8 = 0x73;
+pub(crate) const INEG: u8 = 0x74;
+pub(crate) const LNEG: u8 = 0x75;
+pub(crate) const FNEG: u8 = 0x76;
+pub(crate) const DNEG: u8 = 0x77;
+pub(crate) const ISHL: u8 = 0x78;
+pub(crate) const LSHL: u8 = 0x79;
+pub(crate) const ISHR: u8 = 0x7a;
+pub(crate) const LSHR: u8 = 0x7b;
+pub(crate) const IUSHR: u8 = 0x7c;
+pub(crate) const LUSHR: u8 = 0x7d;
+pub(crate) const IAND: u8 = 0x7e;
+pub(crate) const LAND: u8 = 0x7f;
+pub(crate) const IOR: u8 = 0x80;
+pub(crate) const LOR: u8 = 0x81;
+pub(crate) const IXOR: u8 = 0x82;
+pub(crate) const LXOR: u8 = 0x83;
+pub(crate) const IINC: u8 = 0x84;
+pub(crate) const I2L: u8 = 0x85;
+pub(crate) const I2F: u8 = 0x86;
+pub(crate) const I2D: u8 = 0x87;
+pub(crate) const L2I: u8 = 0x88;
+pub(crate) const L2F: u8 = 0x89;
+pub(crate) const L2D: u8 = 0x8a;
+pub(crate) const F2I: u8 = 0x8b;
+pub(crate) const F2L: u8 = 0x8c;
+pub(crate) const F2D: u8 = 0x8d;
+pub(crate) const D2I: u8 = 0x8e;
+pub(crate) const D2L: u8 = 0x8f;
+pub(crate) const D2F: u8 = 0x90;
+pub(crate) const I2B: u8 = 0x91;
+pub(crate) const I2C: u8 = 0x92;
+pub(crate) const I2S: u8 = 0x93;
+pub(crate) const LCMP: u8 = 0x94;
+pub(crate) const FCMPL: u8 = 0x95;
+pub(crate) const FCMPG: u8 = 0x96;
+pub(crate) const DCMPL: u8 = 0x97;
+pub(crate) const DCMPG: u8 = 0x98;
+pub(crate) const IFEQ: u8 = 0x99;
+pub(crate) const IFNE: u8 = 0x9a;
+pub(crate) const IFLT: u8 = 0x9b;
+pub(crate) const IFGE: u8 = 0x9c;
+pub(crate) const IFGT: u8 = 0x9d;
+pub(crate) const IFLE: u8 = 0x9e;
+pub(crate) const IF_ICMPEQ: u8 = 0x9f;
+pub(crate) const IF_ICMPNE: u8 = 0xa0;
+pub(crate) const IF_ICMPLT: u8 = 0xa1;
+pub(crate) const IF_ICMPGE: u8 = 0xa2;
+pub(crate) const IF_ICMPGT: u8 = 0xa3;
+pub(crate) const IF_ICMPLE: u8 = 0xa4;
+pub(crate) const IF_ACMPEQ: u8 = 0xa5;
+pub(crate) const IF_ACMPNE: u8 = 0xa6;
+pub(crate) const GOTO: u8 = 0xa7;
+pub(crate) const JSR: u8 = 0xa8;
+pub(crate) const RET: u8 = 0xa9;
+pub(crate) const TABLESWITCH: u8 = 0xaa;
+pub(crate) const LOOKUPSWITCH: u8 = 0xab;
+pub(crate) const IRETURN: u8 = 0xac;
+pub(crate) const LRETURN: u8 = 0xad;
+pub(crate) const FRETURN: u8 = 0xae;
+pub(crate) const DRETURN: u8 = 0xaf;
+pub(crate) const ARETURN: u8 = 0xb0;
+pub(crate) const RETURN: u8 = 0xb1;
+pub(crate) const GETSTATIC: u8 = 0xb2;
+pub(crate) const PUTSTATIC: u8 = 0xb3;
+pub(crate) const GETFIELD: u8 = 0xb4;
+pub(crate) const PUTFIELD: u8 = 0xb5;
+pub(crate) const INVOKEVIRTUAL: u8 = 0xb6;
+pub(crate) const INVOKESPECIAL: u8 = 0xb7;
+pub(crate) const INVOKESTATIC: u8 = 0xb8;
+pub(crate) const INVOKEINTERFACE: u8 = 0xb9;
+pub(crate) const INVOKEDYNAMIC: u8 = 0xba;
+pub(crate) const NEW: u8 = 0xbb;
+pub(crate) const NEWARRAY: u8 = 0xbc;
+pub(crate) const ANEWARRAY: u8 = 0xbd;
+pub(crate) const ARRAYLENGTH: u8 = 0xbe;
+pub(crate) const ATHROW: u8 = 0xbf;
+pub(crate) const CHECKCAST: u8 = 0xc0;
+pub(crate) const INSTANCEOF: u8 = 0xc1;
+pub(crate) const MONITORENTER: u8 = 0xc2;
+pub(crate) const MONITOREXIT: u8 = 0xc3;
+pub(crate) const WIDE: u8 = 0xc4;
+pub(crate) const MULTIANEWARRAY: u8 = 0xc5;
+pub(crate) const IFNULL: u8 = 0xc6;
+pub(crate) const IFNONNULL: u8 = 0xc7;
+pub(crate) const GOTO_W: u8 = 0xc8;
+pub(crate) const JSR_W: u8 = 0xc9;
 
 pub struct Thread<'a> {
-    frame_stack: Vec<Frame<'a>>,
+    vm: &'a mut VirtualMachine,
     pc: usize,
-    registry: &'a Registry,
+    stack_usage: usize,
 }
 
-impl Thread<'_> {
-    pub(crate) fn run(mut self) -> Type {
-        // println!("code {:?}", self.code());
+impl<'a> Thread<'a> {
+    /// Invokes the method and runs it to completion on this thread.
+    pub(crate) fn invoke(
+        &mut self,
+        class: Rc<Class>,
+        method: Rc<Method>,
+        args: Vec<Type>,
+    ) -> Result<Type, Throwable> {
+        if method.is_native() {
+            // the arguments are in no frame while the native method allocates
+            return self.with_handles(&args, |thread| thread.invoke_native(&class, &method, &args));
+        }
+
+        let depth = self.vm.frame_stack.len();
+        self.enter(class, method, args)?;
+
+        let result = self.execute(depth);
+        if result.is_err() {
+            while self.vm.frame_stack.len() > depth {
+                self.leave();
+            }
+        }
+        result
+    }
+
+    /// Runs the frames above the depth until the bottom one returns, passing
+    /// the exceptions they throw to their handlers.
+    fn execute(&mut self, depth: usize) -> Result<Type, Throwable> {
         loop {
-            let instruction = self.code()[self.pc];
-            self.pc += 1;
-            // println!("op {} stack {:?}", instruction, self.operand_stack());
-            match instruction {
-                NOP => (),
-                ICONST_M1 => self.push(Int(-1)),
-                ICONST_0 => self.push(Int(0)),
-                ICONST_1 => self.push(Int(1)),
-                ICONST_2 => self.push(Int(2)),
-                ICONST_3 => self.push(Int(3)),
-                ICONST_4 => self.push(Int(4)),
-                ICONST_5 => self.push(Int(5)),
-                LCONST_0 => self.push(Long(0)),
-                LCONST_1 => self.push(Long(1)),
-                FCONST_0 => self.push(Float(0.0)),
-                FCONST_1 => self.push(Float(1.0)),
-                FCONST_2 => self.push(Float(2.0)),
-                DCONST_0 => self.push(Double(0.0)),
-                DCONST_1 => self.push(Double(1.0)),
-                BIPUSH => {
-                    let bytes = self.read_u8().to_be_bytes();
-                    self.push(Int(i8::from_be_bytes(bytes) as i32))
-                }
-                SIPUSH => {
-                    let bytes = self.read_u16().to_be_bytes();
-                    self.push(Int(i16::from_be_bytes(bytes) as i32))
-                }
-                LDC => {
-                    let index = self.read_u8();
-                    let constant = self.constant_pool().get(index as usize);
-                    self.push(constant)
-                }
-                LDC_W | LDC2_W => {
-                    let index = self.read_u16();
-                    let constant = self.constant_pool().get(index as usize);
-                    self.push(constant)
-                }
-                ILOAD | LLOAD | FLOAD | DLOAD => {
-                    let index = self.read_u8() as usize;
-                    let variable = self.locals()[index];
-                    self.push(variable)
-                }
-                ILOAD_0 | LLOAD_0 | FLOAD_0 | DLOAD_0 => self.load_n(0),
-                ILOAD_1 | LLOAD_1 | FLOAD_1 | DLOAD_1 => self.load_n(1),
-                ILOAD_2 | LLOAD_2 | FLOAD_2 | DLOAD_2 => self.load_n(2),
-                ILOAD_3 | LLOAD_3 | FLOAD_3 | DLOAD_3 => self.load_n(3),
-                ISTORE | LSTORE | FSTORE | DSTORE => {
-                    let index = self.read_u8() as usize;
-                    self.locals()[index] = self.pop()
-                }
-                ISTORE_0 | LSTORE_0 | FSTORE_0 | DSTORE_0 => self.locals()[0] = self.pop(),
-                ISTORE_1 | LSTORE_1 | FSTORE_1 | DSTORE_1 => self.locals()[1] = self.pop(),
-                ISTORE_2 | LSTORE_2 | FSTORE_2 | DSTORE_2 => self.locals()[2] = self.pop(),
-                ISTORE_3 | LSTORE_3 | FSTORE_3 | DSTORE_3 => self.locals()[3] = self.pop(),
-                IADD => self.fn2(|a, b| Int(a.int().wrapping_add(b.int()))),
-                LADD => self.fn2(|a, b| Long(a.long().wrapping_add(b.long()))),
-                FADD => self.fn2(|a, b| Float(a.float() + b.float())),
-                DADD => self.fn2(|a, b| Double(a.double() + b.double())),
-                ISUB => self.fn2(|a, b| Int(a.int().wrapping_sub(b.int()))),
-                LSUB => self.fn2(|a, b| Long(a.long().wrapping_sub(b.long()))),
-                FSUB => self.fn2(|a, b| Float(a.float() - b.float())),
-                DSUB => self.fn2(|a, b| Double(a.double() - b.double())),
-                IMUL => self.fn2(|a, b| Int(a.int().wrapping_mul(b.int()))),
-                LMUL => self.fn2(|a, b| Long(a.long().wrapping_mul(b.long()))),
-                FMUL => self.fn2(|a, b| Float(a.float() * b.float())),
-                DMUL => self.fn2(|a, b| Double(a.double() * b.double())),
-                IDIV => self.fn2(|a, b| Int(a.int().wrapping_div(b.int()))),
-                LDIV => self.fn2(|a, b| Long(a.long().wrapping_div(b.long()))),
-                FDIV => self.fn2(|a, b| Float(a.float() * b.float())),
-                DDIV => self.fn2(|a, b| Double(a.double() * b.double())),
-                IREM => self.fn2(|a, b| Int(a.int().wrapping_rem(b.int()))),
-                LREM => self.fn2(|a, b| Long(a.long().wrapping_rem(b.long()))),
-                FREM => self.fn2(|a, b| Float(a.float() % b.float())),
-                DREM => self.fn2(|a, b| Double(a.double() % b.double())),
-                IAND => self.fn2(|a, b| Int(a.int() & b.int())),
-                LAND => self.fn2(|a, b| Long(a.long() & b.long())),
-                IOR => self.fn2(|a, b| Int(a.int() | b.int())),
-                LOR => self.fn2(|a, b| Long(a.long() | b.long())),
-                I2F => self.cast(|x| Float(x.int() as f32)),
-                IFEQ => self.jmp_cmp1(|x| x.int() == 0),
-                IFNE => self.jmp_cmp1(|x| x.int() != 0),
-                IFLT => self.jmp_cmp1(|x| x.int() < 0),
-                IFGE => self.jmp_cmp1(|x| x.int() >= 0),
-                IFGT => self.jmp_cmp1(|x| x.int() > 0),
-                IFLE => self.jmp_cmp1(|x| x.int() <= 0),
-                IF_ICMPEQ => self.jmp_cmp2(|a, b| a.int() == b.int()),
-                IF_ICMPNE => self.jmp_cmp2(|a, b| a.int() != b.int()),
-                IF_ICMPLT => self.jmp_cmp2(|a, b| a.int() < b.int()),
-                IF_ICMPGE => self.jmp_cmp2(|a, b| a.int() >= b.int()),
-                IF_ICMPGT => self.jmp_cmp2(|a, b| a.int() > b.int()),
-                IF_ICMPLE => self.jmp_cmp2(|a, b| a.int() <= b.int()),
-                GOTO => self.jmp(),
-                IRETURN | LRETURN | FRETURN | DRETURN => {
-                    let return_address = self.frame_stack.last().unwrap().return_address;
-                    let return_value = self.pop();
-
-                    self.frame_stack.pop();
-                    if self.frame_stack.is_empty() {
-                        return return_value;
-                    }
-
-                    self.push(return_value);
-                    self.pc = return_address;
-                }
-                RETURN => {
-                    let return_address = self.frame_stack.last().unwrap().return_address;
+            match self.run(depth) {
+                Ok(return_value) => return Ok(return_value),
+                Err(throwable) => self.handle(throwable, depth)?,
+            }
+        }
+    }
 
-                    self.frame_stack.pop();
-                    if self.frame_stack.is_empty() {
-                        return Void;
+    fn run(&mut self, depth: usize) -> Result<Type, Throwable> {
+        loop {
+            // the current method only changes on invokes and returns, which
+            // break out to pick up the new one
+            let method = self.vm.frame_stack.last().unwrap().method.clone();
+            loop {
+                let instruction = &method.instructions[self.pc];
+                self.pc += 1;
+                match instruction {
+                    Instruction::Nop => (),
+                    &Instruction::Const(value) => self.push(value),
+                    &Instruction::Ldc(index) => {
+                        let class = self.vm.frame_stack.last().unwrap().class.clone();
+                        let constant = self.vm.resolve_constant(&class, index)?;
+                        self.push(Type::Reference(constant))
                     }
+                    &Instruction::Load(index) => self.load_n(index),
+                    &Instruction::Store(index) => self.locals()[index] = self.pop(),
+                    Instruction::ArrayLoad => {
+                        let index = self.pop().int();
+                        let array = self.pop().reference().ok_or_else(null_pointer_exception)?;
+                        let index = self.array_index(array, index)?;
 
-                    self.pc = return_address;
-                }
-                INVOKESTATIC => {
-                    let index = self.read_u16();
-                    let (class_name, name_and_type) = self.constant_pool().method_ref(index);
+                        let value = self.vm.heap.get(array).fields[index];
+                        self.push(value)
+                    }
+                    Instruction::ArrayStore => {
+                        let value = self.pop();
+                        let index = self.pop().int();
+                        let array = self.pop().reference().ok_or_else(null_pointer_exception)?;
+                        let index = self.array_index(array, index)?;
+
+                        let array = self.vm.heap.get_mut(array);
+                        let component_type = array.class.component_type().unwrap();
+                        array.fields[index] = narrow_component(value, &component_type);
+                    }
+                    Instruction::Aastore => {
+                        let value = self.pop();
+                        let index = self.pop().int();
+                        let array = self.pop().reference().ok_or_else(null_pointer_exception)?;
+                        let index = self.array_index(array, index)?;
+
+                        if let Some(object) = value.reference() {
+                            let class_name = self.vm.heap.get(object).class.this_class();
+                            let component_type = self.vm.heap.get(array).class.component_type();
+                            let component =
+                                component_type.as_deref().and_then(descriptor_class_name);
+                            if !self.vm.is_assignable(&class_name, component.unwrap()) {
+                                let name = class_name.replace('/', ".");
+                                return Err(Throwable::new(ARRAY_STORE_EXCEPTION, &name));
+                            }
+                        }
+                        self.vm.heap.get_mut(array).fields[index] = value;
+                    }
+                    Instruction::Pop => {
+                        self.pop();
+                    }
+                    Instruction::Pop2 => {
+                        self.pop_slots(2);
+                    }
+                    Instruction::Dup => {
+                        let value = self.pop();
+                        self.push_all(&[&[value], &[value]]);
+                    }
+                    Instruction::DupX1 => {
+                        let value = self.pop();
+                        let under = self.pop();
+                        self.push_all(&[&[value], &[under], &[value]]);
+                    }
+                    Instruction::DupX2 => {
+                        let value = self.pop_slots(1);
+                        let under = self.pop_slots(2);
+                        self.push_all(&[&value, &under, &value]);
+                    }
+                    Instruction::Dup2 => {
+                        let value = self.pop_slots(2);
+                        self.push_all(&[&value, &value]);
+                    }
+                    Instruction::Dup2X1 => {
+                        let value = self.pop_slots(2);
+                        let under = self.pop_slots(1);
+                        self.push_all(&[&value, &under, &value]);
+                    }
+                    Instruction::Dup2X2 => {
+                        let value = self.pop_slots(2);
+                        let under = self.pop_slots(2);
+                        self.push_all(&[&value, &under, &value]);
+                    }
+                    Instruction::Swap => {
+                        let (under, value) = self.pop2();
+                        self.push_all(&[&[value], &[under]]);
+                    }
+                    Instruction::Iadd => self.fn2(|a, b| Int(a.int().wrapping_add(b.int()))),
+                    Instruction::Ladd => self.fn2(|a, b| Long(a.long().wrapping_add(b.long()))),
+                    Instruction::Fadd => self.fn2(|a, b| Float(a.float() + b.float())),
+                    Instruction::Dadd => self.fn2(|a, b| Double(a.double() + b.double())),
+                    Instruction::Isub => self.fn2(|a, b| Int(a.int().wrapping_sub(b.int()))),
+                    Instruction::Lsub => self.fn2(|a, b| Long(a.long().wrapping_sub(b.long()))),
+                    Instruction::Fsub => self.fn2(|a, b| Float(a.float() - b.float())),
+                    Instruction::Dsub => self.fn2(|a, b| Double(a.double() - b.double())),
+                    Instruction::Imul => self.fn2(|a, b| Int(a.int().wrapping_mul(b.int()))),
+                    Instruction::Lmul => self.fn2(|a, b| Long(a.long().wrapping_mul(b.long()))),
+                    Instruction::Fmul => self.fn2(|a, b| Float(a.float() * b.float())),
+                    Instruction::Dmul => self.fn2(|a, b| Double(a.double() * b.double())),
+                    Instruction::Idiv => {
+                        self.check_divisor()?;
+                        self.fn2(|a, b| Int(a.int().wrapping_div(b.int())))
+                    }
+                    Instruction::Ldiv => {
+                        self.check_divisor()?;
+                        self.fn2(|a, b| Long(a.long().wrapping_div(b.long())))
+                    }
+                    Instruction::Fdiv => self.fn2(|a, b| Float(a.float() / b.float())),
+                    Instruction::Ddiv => self.fn2(|a, b| Double(a.double() / b.double())),
+                    Instruction::Irem => {
+                        self.check_divisor()?;
+                        self.fn2(|a, b| Int(a.int().wrapping_rem(b.int())))
+                    }
+                    Instruction::Lrem => {
+                        self.check_divisor()?;
+                        self.fn2(|a, b| Long(a.long().wrapping_rem(b.long())))
+                    }
+                    Instruction::Frem => self.fn2(|a, b| Float(a.float() % b.float())),
+                    Instruction::Drem => self.fn2(|a, b| Double(a.double() % b.double())),
+                    Instruction::Ineg => self.cast(|x| Int(x.int().wrapping_neg())),
+                    Instruction::Lneg => self.cast(|x| Long(x.long().wrapping_neg())),
+                    Instruction::Fneg => self.cast(|x| Float(-x.float())),
+                    Instruction::Dneg => self.cast(|x| Double(-x.double())),
+                    Instruction::Ishl => self.fn2(|a, b| Int(a.int().wrapping_shl(b.int() as u32))),
+                    Instruction::Lshl => {
+                        self.fn2(|a, b| Long(a.long().wrapping_shl(b.int() as u32)))
+                    }
+                    Instruction::Ishr => self.fn2(|a, b| Int(a.int().wrapping_shr(b.int() as u32))),
+                    Instruction::Lshr => {
+                        self.fn2(|a, b| Long(a.long().wrapping_shr(b.int() as u32)))
+                    }
+                    Instruction::Iushr => {
+                        self.fn2(|a, b| Int((a.int() as u32).wrapping_shr(b.int() as u32) as i32))
+                    }
+                    Instruction::Lushr => {
+                        self.fn2(|a, b| Long((a.long() as u64).wrapping_shr(b.int() as u32) as i64))
+                    }
+                    Instruction::Iand => self.fn2(|a, b| Int(a.int() & b.int())),
+                    Instruction::Land => self.fn2(|a, b| Long(a.long() & b.long())),
+                    Instruction::Ior => self.fn2(|a, b| Int(a.int() | b.int())),
+                    Instruction::Lor => self.fn2(|a, b| Long(a.long() | b.long())),
+                    Instruction::Ixor => self.fn2(|a, b| Int(a.int() ^ b.int())),
+                    Instruction::Lxor => self.fn2(|a, b| Long(a.long() ^ b.long())),
+                    &Instruction::Iinc(index, increment) => self.increment(index, increment),
+                    Instruction::I2l => self.cast(|x| Long(x.int() as i64)),
+                    Instruction::I2f => self.cast(|x| Float(x.int() as f32)),
+                    Instruction::I2d => self.cast(|x| Double(x.int() as f64)),
+                    Instruction::L2i => self.cast(|x| Int(x.long() as i32)),
+                    Instruction::L2f => self.cast(|x| Float(x.long() as f32)),
+                    Instruction::L2d => self.cast(|x| Double(x.long() as f64)),
+                    // Float-to-integer casts saturate and turn NaN into 0, as in JVMS 6.5 f2i.
+                    Instruction::F2i => self.cast(|x| Int(x.float() as i32)),
+                    Instruction::F2l => self.cast(|x| Long(x.float() as i64)),
+                    Instruction::F2d => self.cast(|x| Double(x.float() as f64)),
+                    Instruction::D2i => self.cast(|x| Int(x.double() as i32)),
+                    Instruction::D2l => self.cast(|x| Long(x.double() as i64)),
+                    Instruction::D2f => self.cast(|x| Float(x.double() as f32)),
+                    Instruction::I2b => self.cast(|x| Int(x.int() as i8 as i32)),
+                    Instruction::I2c => self.cast(|x| Int(x.int() as u16 as i32)),
+                    Instruction::I2s => self.cast(|x| Int(x.int() as i16 as i32)),
+                    Instruction::Lcmp => self.fn2(|a, b| compare(a.long(), b.long(), 0)),
+                    Instruction::Fcmpl => self.fn2(|a, b| compare(a.float(), b.float(), -1)),
+                    Instruction::Fcmpg => self.fn2(|a, b| compare(a.float(), b.float(), 1)),
+                    Instruction::Dcmpl => self.fn2(|a, b| compare(a.double(), b.double(), -1)),
+                    Instruction::Dcmpg => self.fn2(|a, b| compare(a.double(), b.double(), 1)),
+                    &Instruction::Ifeq(target) => self.jmp_cmp1(target, |x| x.int() == 0),
+                    &Instruction::Ifne(target) => self.jmp_cmp1(target, |x| x.int() != 0),
+                    &Instruction::Iflt(target) => self.jmp_cmp1(target, |x| x.int() < 0),
+                    &Instruction::Ifge(target) => self.jmp_cmp1(target, |x| x.int() >= 0),
+                    &Instruction::Ifgt(target) => self.jmp_cmp1(target, |x| x.int() > 0),
+                    &Instruction::Ifle(target) => self.jmp_cmp1(target, |x| x.int() <= 0),
+                    &Instruction::IfIcmpeq(target) => {
+                        self.jmp_cmp2(target, |a, b| a.int() == b.int())
+                    }
+                    &Instruction::IfIcmpne(target) => {
+                        self.jmp_cmp2(target, |a, b| a.int() != b.int())
+                    }
+                    &Instruction::IfIcmplt(target) => {
+                        self.jmp_cmp2(target, |a, b| a.int() < b.int())
+                    }
+                    &Instruction::IfIcmpge(target) => {
+                        self.jmp_cmp2(target, |a, b| a.int() >= b.int())
+                    }
+                    &Instruction::IfIcmpgt(target) => {
+                        self.jmp_cmp2(target, |a, b| a.int() > b.int())
+                    }
+                    &Instruction::IfIcmple(target) => {
+                        self.jmp_cmp2(target, |a, b| a.int() <= b.int())
+                    }
+                    &Instruction::IfAcmpeq(target) => {
+                        self.jmp_cmp2(target, |a, b| a.reference() == b.reference())
+                    }
+                    &Instruction::IfAcmpne(target) => {
+                        self.jmp_cmp2(target, |a, b| a.reference() != b.reference())
+                    }
+                    &Instruction::Goto(target) => self.pc = target,
+                    &Instruction::Jsr(target) => {
+                        self.push(ReturnAddress(self.pc));
+                        self.pc = target
+                    }
+                    &Instruction::Ret(index) => self.pc = self.locals()[index].return_address(),
+                    Instruction::TableSwitch {
+                        default,
+                        low,
+                        targets,
+                    } => {
+                        let key = self.pop().int();
+                        let offset = (key as i64 - *low as i64) as usize;
+                        self.pc = *targets.get(offset).unwrap_or(default)
+                    }
+                    Instruction::LookupSwitch { default, pairs } => {
+                        let key = self.pop().int();
+                        self.pc = match pairs.binary_search_by_key(&key, |&(key, _)| key) {
+                            Ok(index) => pairs[index].1,
+                            Err(_) => *default,
+                        }
+                    }
+                    Instruction::ReturnValue => {
+                        let return_value = self.pop();
+                        if self.leave() == depth {
+                            return Ok(return_value);
+                        }
+                        self.push(return_value);
+                        break;
+                    }
+                    Instruction::Return => {
+                        if self.leave() == depth {
+                            return Ok(Void);
+                        }
+                        break;
+                    }
+                    Instruction::InvokeVirtual(method_ref) => {
+                        let (class, method) = self.vm.resolve_method(
+                            &method_ref.class_name,
+                            &method_ref.name_and_type,
+                            false,
+                        )?;
+                        if method.is_static() {
+                            return Err(static_method_error(
+                                "Expecting non-static",
+                                &class,
+                                &method,
+                            ));
+                        }
 
-                    let class = &self.registry.class(class_name.as_str());
-                    let method = class.method(name_and_type.as_str()).unwrap();
+                        let (receiver, args) = self.pop_instance_args(&method.descriptor)?;
+                        let receiver = self.vm.heap.get(receiver).class.clone();
+                        let (class, method) = dispatch::select(&receiver, &class, &method)?;
+                        self.call(class, method, args)?;
+                        break;
+                    }
+                    Instruction::InvokeSpecial(method_ref) => {
+                        let MemberRef {
+                            class_name,
+                            name_and_type,
+                            interface,
+                        } = method_ref;
+                        let (class, method) =
+                            self.vm
+                                .resolve_method(class_name, name_and_type, *interface)?;
+                        if method.is_initializer() && class.this_class() != *class_name {
+                            let description = method_description(class_name, name_and_type);
+                            return Err(Throwable::new(NO_SUCH_METHOD_ERROR, &description));
+                        }
+                        if method.is_static() {
+                            return Err(static_method_error(
+                                "Expecting non-static",
+                                &class,
+                                &method,
+                            ));
+                        }
 
-                    if method.is_native() {
-                        let full_name = class_name + "." + &*name_and_type;
-                        let native_method = self.registry.native_method(full_name.as_str());
+                        let (_, args) = self.pop_instance_args(&method.descriptor)?;
+                        let (class, method) = if method.is_initializer() {
+                            (class, method)
+                        } else {
+                            let start = self.special_lookup_class(class_name)?;
+                            dispatch::select_special(&self.vm.registry, &start, &class, &method)?
+                        };
+                        self.call(class, method, args)?;
+                        break;
+                    }
+                    Instruction::InvokeStatic(method_ref) => {
+                        let MemberRef {
+                            class_name,
+                            name_and_type,
+                            interface,
+                        } = method_ref;
+                        let (class, method) =
+                            self.vm
+                                .resolve_method(class_name, name_and_type, *interface)?;
+                        if !method.is_static() {
+                            let description = method_description(class_name, name_and_type);
+                            let message = format!("Expected static method {}", description);
+                            return Err(Throwable::new(INCOMPATIBLE_CLASS_CHANGE_ERROR, &message));
+                        }
+                        self.initialize(&class)?;
 
-                        let args = self.pop_args(&*method.descriptor);
-                        match native_method(&args) {
+                        let args = self.pop_args(&method.descriptor);
+                        self.call(class, method, args)?;
+                        break;
+                    }
+                    Instruction::InvokeInterface(method_ref) => {
+                        let class_name = &method_ref.class_name;
+                        let (class, method) =
+                            self.vm
+                                .resolve_method(class_name, &method_ref.name_and_type, true)?;
+                        if method.is_static() {
+                            let error = "Expected instance not static";
+                            return Err(static_method_error(error, &class, &method));
+                        }
+
+                        let (receiver, args) = self.pop_instance_args(&method.descriptor)?;
+                        let receiver = self.vm.heap.get(receiver).class.clone();
+                        let interface = self.vm.resolve_class(class_name)?;
+                        let implements = receiver
+                            .superinterfaces
+                            .iter()
+                            .any(|superinterface| Rc::ptr_eq(superinterface, &interface));
+                        if !implements {
+                            let message = format!(
+                                "Class {} does not implement the requested interface {}",
+                                receiver.this_class().replace('/', "."),
+                                class_name.replace('/', ".")
+                            );
+                            return Err(Throwable::new(INCOMPATIBLE_CLASS_CHANGE_ERROR, &message));
+                        }
+
+                        let (class, method) = dispatch::select(&receiver, &class, &method)?;
+                        self.call(class, method, args)?;
+                        break;
+                    }
+                    Instruction::InvokeHandle { descriptor, exact } => {
+                        let args = self.pop_args(descriptor);
+                        let handle = self.pop().reference().ok_or_else(null_pointer_exception)?;
+                        let handles = [&[Type::Reference(handle)], &args[..]].concat();
+                        let return_value = self.with_handles(&handles, |thread| {
+                            thread.invoke_handle(handle, descriptor, args, *exact)
+                        })?;
+                        match return_value {
                             Void => (),
                             return_value => self.push(return_value),
                         }
+                    }
+                    Instruction::InvokeDynamic {
+                        index,
+                        descriptor,
+                        call_site,
+                    } => {
+                        let call_site = match call_site.get() {
+                            Some(call_site) => call_site,
+                            None => {
+                                let caller = self.vm.frame_stack.last().unwrap().class.clone();
+                                let linked = call_site::link(self.vm, &caller, *index, descriptor)?;
+                                if let CallSite::Lambda { class, .. } = &linked {
+                                    self.initialize(class)?;
+                                }
+                                call_site.get_or_init(|| linked)
+                            }
+                        };
+
+                        let size = match call_site {
+                            CallSite::Lambda { class, .. } => Heap::instance_size(class),
+                            CallSite::StringConcat(_) => 0,
+                        };
+                        self.safepoint(size)?;
+                        let args = self.pop_args(descriptor);
+                        let object = match call_site {
+                            CallSite::Lambda { class, slots } => {
+                                let object = self.vm.heap.allocate(class.clone());
+                                let fields = &mut self.vm.heap.get_mut(object).fields;
+                                for (&slot, arg) in slots.iter().zip(args) {
+                                    fields[slot] = arg;
+                                }
+                                object
+                            }
+                            CallSite::StringConcat(recipes) => self.concat(recipes, &args)?,
+                        };
+                        self.push(Type::Reference(object))
+                    }
+                    Instruction::GetStatic(field_ref) => {
+                        let (class, slot) = self.vm.resolve_static_field(
+                            &field_ref.class_name,
+                            &field_ref.name_and_type,
+                        )?;
+                        self.initialize(&class)?;
+
+                        let value = class.static_values.borrow()[slot];
+                        self.push(value)
+                    }
+                    Instruction::PutStatic(field_ref) => {
+                        let (class, slot) = self.vm.resolve_static_field(
+                            &field_ref.class_name,
+                            &field_ref.name_and_type,
+                        )?;
+                        self.initialize(&class)?;
+
+                        let value = narrow(self.pop(), &field_ref.name_and_type);
+                        class.static_values.borrow_mut()[slot] = value;
+                    }
+                    Instruction::GetField(field_ref) => {
+                        let slot = self.vm.resolve_instance_field(
+                            &field_ref.class_name,
+                            &field_ref.name_and_type,
+                        )?;
+
+                        let object = self.pop().reference().ok_or_else(null_pointer_exception)?;
+                        let value = self.vm.heap.get(object).fields[slot];
+                        self.push(value)
+                    }
+                    Instruction::PutField(field_ref) => {
+                        let slot = self.vm.resolve_instance_field(
+                            &field_ref.class_name,
+                            &field_ref.name_and_type,
+                        )?;
+
+                        let value = narrow(self.pop(), &field_ref.name_and_type);
+                        let object = self.pop().reference().ok_or_else(null_pointer_exception)?;
+                        self.vm.heap.get_mut(object).fields[slot] = value;
+                    }
+                    Instruction::New(class_name) => {
+                        let class = self.vm.resolve_class(class_name)?;
+                        if class.is_interface() || class.is_abstract() {
+                            let name = class_name.replace('/', ".");
+                            return Err(Throwable::new(INSTANTIATION_ERROR, &name));
+                        }
+                        self.initialize(&class)?;
+
+                        self.safepoint(Heap::instance_size(&class))?;
+                        let object = self.vm.heap.allocate(class);
+                        self.push(Type::Reference(object))
+                    }
+                    Instruction::NewArray(class_name) => {
+                        let class = self.vm.resolve_class(class_name)?;
 
-                        continue;
+                        let length = self.pop().int();
+                        let array = self.allocate_array(class, &[length])?;
+                        self.push(Type::Reference(array))
+                    }
+                    Instruction::ArrayLength => {
+                        let array = self.pop().reference().ok_or_else(null_pointer_exception)?;
+                        let length = self.vm.heap.get(array).fields.len();
+                        self.push(Int(length as i32))
                     }
+                    Instruction::MultiANewArray(class_name, dimensions) => {
+                        let class = self.vm.resolve_class(class_name)?;
 
-                    let mut frame = Frame::from(method, &class.constant_pool);
-                    self.pop_args(&*method.descriptor)
-                        .iter()
-                        .enumerate()
-                        .for_each(|(i, arg)| frame.locals[i] = *arg);
-                    frame.return_address = self.pc;
+                        let mut lengths = (0..*dimensions)
+                            .map(|_| self.pop().int())
+                            .collect::<Vec<_>>();
+                        lengths.reverse();
+                        let array = self.allocate_array(class, &lengths)?;
+                        self.push(Type::Reference(array))
+                    }
+                    Instruction::Athrow => {
+                        let exception =
+                            self.pop().reference().ok_or_else(null_pointer_exception)?;
+                        return Err(self.throwable(exception));
+                    }
+                    Instruction::Monitor => {
+                        self.pop().reference().ok_or_else(null_pointer_exception)?;
+                    }
+                    Instruction::CheckCast(class_name) => {
+                        let value = *self.operand_stack().last().unwrap();
+                        if let Some(object) = value.reference() {
+                            let target = self.vm.resolve_class(class_name)?;
 
-                    self.frame_stack.push(frame);
-                    self.pc = 0;
+                            let class = self.vm.heap.get(object).class.clone();
+                            if !self.vm.is_assignable(&class.this_class(), class_name) {
+                                return Err(class_cast_exception(&class, &target));
+                            }
+                        }
+                    }
+                    Instruction::InstanceOf(class_name) => {
+                        let instance = match self.pop().reference() {
+                            Some(object) => {
+                                self.vm.resolve_class(class_name)?;
+
+                                let object_class = self.vm.heap.get(object).class.this_class();
+                                self.vm.is_assignable(&object_class, class_name)
+                            }
+                            None => false,
+                        };
+                        self.push(Int(instance as i32))
+                    }
+                    &Instruction::IfNull(target) => {
+                        self.jmp_cmp1(target, |x| x.reference().is_none())
+                    }
+                    &Instruction::IfNonNull(target) => {
+                        self.jmp_cmp1(target, |x| x.reference().is_some())
+                    }
                 }
-                op => unimplemented!("instruction {:#x} is not supported", op),
             }
         }
     }
@@ -285,33 +789,22 @@ impl Thread<'_> {
         self.push(op(lhs, rhs))
     }
 
-    fn jmp(&mut self) {
-        let (from, to) = (self.pc, self.pc + 2);
-        let bytes = self.code()[from..to].try_into().unwrap();
-        let address = u16::from_be_bytes(bytes);
-        self.pc += address as usize - 1
-    }
-
-    fn jmp_cmp1<F>(&mut self, cond_fn: F)
+    fn jmp_cmp1<F>(&mut self, target: usize, cond_fn: F)
     where
         F: FnOnce(Type) -> bool,
     {
         if cond_fn(self.pop()) {
-            self.jmp()
-        } else {
-            self.read_u16();
+            self.pc = target
         }
     }
 
-    fn jmp_cmp2<F>(&mut self, cond_fn: F)
+    fn jmp_cmp2<F>(&mut self, target: usize, cond_fn: F)
     where
         F: FnOnce(Type, Type) -> bool,
     {
         let (lhs, rhs) = self.pop2();
         if cond_fn(lhs, rhs) {
-            self.jmp()
-        } else {
-            self.read_u16();
+            self.pc = target
         }
     }
 
@@ -320,6 +813,11 @@ impl Thread<'_> {
         self.push(variable)
     }
 
+    fn increment(&mut self, index: usize, increment: i32) {
+        let variable = self.locals()[index].int();
+        self.locals()[index] = Int(variable.wrapping_add(increment))
+    }
+
     fn pop(&mut self) -> Type {
         self.operand_stack().pop().unwrap()
     }
@@ -334,67 +832,776 @@ impl Thread<'_> {
         self.operand_stack().push(value)
     }
 
-    fn read_u8(&mut self) -> u8 {
-        let index = self.pc;
-        self.pc += 1;
-        self.code()[index]
+    /// Pops the values taking up the slots on top of the operand stack,
+    /// deepest first, where a long or double takes up two (JVMS 2.11.1).
+    fn pop_slots(&mut self, slots: usize) -> Vec<Type> {
+        let mut values = vec![];
+        let mut popped = 0;
+        while popped < slots {
+            let value = self.pop();
+            popped += value.category();
+            values.insert(0, value);
+        }
+        values
     }
 
-    fn read_u16(&mut self) -> u16 {
-        let (from, to) = (self.pc, self.pc + 2);
-        self.pc += 2;
-        u16::from_be_bytes(self.code()[from..to].try_into().unwrap())
+    fn push_all(&mut self, groups: &[&[Type]]) {
+        for value in groups.concat() {
+            self.push(value)
+        }
     }
 
-    fn code(&self) -> &Vec<u8> {
-        self.frame_stack.last().unwrap().code
+    /// Passes the exception to the innermost handler for it in the frames
+    /// above the depth, popping the frames that have none (JVMS 2.10).
+    fn handle(&mut self, mut throwable: Throwable, depth: usize) -> Result<(), Throwable> {
+        let exception = match throwable.object {
+            Some(exception) => exception,
+            None => {
+                let exception = self.instantiate(&throwable)?;
+                throwable.object = Some(exception);
+                exception
+            }
+        };
+        let class_name = self.vm.heap.get(exception).class.this_class();
+
+        loop {
+            if let Some(handler_pc) = self.find_handler(&class_name)? {
+                let operand_stack = self.operand_stack();
+                operand_stack.clear();
+                operand_stack.push(Type::Reference(exception));
+                self.pc = handler_pc;
+                return Ok(());
+            }
+            if self.leave() == depth {
+                return Err(throwable);
+            }
+        }
     }
 
-    fn constant_pool(&self) -> &ConstantPool {
-        self.frame_stack.last().unwrap().constant_pool
+    /// Looks the exception table of the current method up for a handler
+    /// covering the instruction being executed.
+    fn find_handler(&mut self, class_name: &str) -> Result<Option<usize>, Throwable> {
+        // the pc is past the opcode of the instruction or, in a caller, past
+        // the whole invoke instruction
+        let pc = self.pc - 1;
+        let frame = self.vm.frame_stack.last().unwrap();
+        let (class, method) = (frame.class.clone(), frame.method.clone());
+
+        for entry in &method.exception_table {
+            if pc < entry.start_pc || pc >= entry.end_pc {
+                continue;
+            }
+            if entry.catch_type == 0 {
+                return Ok(Some(entry.handler_pc));
+            }
+
+            let catch_type = class.constant_pool.class(entry.catch_type);
+            self.vm.resolve_class(&catch_type)?;
+            if self.vm.is_assignable(class_name, &catch_type) {
+                return Ok(Some(entry.handler_pc));
+            }
+        }
+        Ok(None)
     }
 
-    fn locals(&mut self) -> &mut Vec<Type> {
-        &mut self.frame_stack.last_mut().unwrap().locals
+    /// Creates the instance of an exception raised by the VM, along with the
+    /// ones of its causes.
+    fn instantiate(&mut self, throwable: &Throwable) -> Result<Reference, Throwable> {
+        let cause = match &throwable.cause {
+            Some(cause) => match cause.object {
+                Some(cause) => Some(cause),
+                None => Some(self.instantiate(cause)?),
+            },
+            None => None,
+        };
+
+        let class = self.vm.resolve_class(&throwable.class_name)?;
+        let cause_value = cause.map_or(Null, Type::Reference);
+        let cause_slot = class.instance_fields[THROWABLE_CAUSE];
+        let message_slot = class.instance_fields[THROWABLE_DETAIL_MESSAGE];
+        // the cause, and then the message, are only held here until the
+        // exception refers to them
+        let (exception, message) = self.with_handles(&[cause_value], |thread| {
+            thread.initialize(&class)?;
+            let message = match &throwable.message {
+                Some(message) => Type::Reference(thread.vm.new_string(message)?),
+                None => Null,
+            };
+            let exception = thread
+                .vm
+                .with_handles(&[message], |vm| vm.allocate(class))?;
+            Ok::<_, Throwable>((exception, message))
+        })?;
+
+        let fields = &mut self.vm.heap.get_mut(exception).fields;
+        fields[cause_slot] = Type::Reference(cause.unwrap_or(exception));
+        fields[message_slot] = message;
+        Ok(exception)
     }
 
-    fn operand_stack(&mut self) -> &mut Vec<Type> {
-        &mut self.frame_stack.last_mut().unwrap().operand_stack
+    /// Describes an exception thrown by Java code, along with its causes.
+    fn throwable(&self, exception: Reference) -> Throwable {
+        let mut causes = vec![exception];
+        let mut current = exception;
+        loop {
+            let object = self.vm.heap.get(current);
+            let slot = object.class.instance_fields[THROWABLE_CAUSE];
+            match object.fields[slot].reference() {
+                Some(cause) if !causes.contains(&cause) => {
+                    causes.push(cause);
+                    current = cause;
+                }
+                _ => break,
+            }
+        }
+
+        causes
+            .iter()
+            .rev()
+            .fold(None, |cause, &exception| {
+                let object = self.vm.heap.get(exception);
+                let slot = object.class.instance_fields[THROWABLE_DETAIL_MESSAGE];
+                let mut throwable = Throwable::without_message(&object.class.this_class());
+                throwable.message = object.fields[slot]
+                    .reference()
+                    .map(|message| self.vm.string_value(message));
+                throwable.cause = cause.map(Box::new);
+                throwable.object = Some(exception);
+                Some(throwable)
+            })
+            .unwrap()
+    }
+
+    /// Raises ArithmeticException for an integer division by zero.
+    fn check_divisor(&mut self) -> Result<(), Throwable> {
+        match self.operand_stack().last() {
+            Some(Int(0)) | Some(Long(0)) => Err(Throwable::new(ARITHMETIC_EXCEPTION, "/ by zero")),
+            _ => Ok(()),
+        }
     }
 
-    pub fn new<'a>(
-        class_name: &'a str,
-        name_and_type: &'a str,
-        registry: &'a Registry,
-    ) -> Thread<'a> {
-        let class = registry.class(class_name);
-        let method = class.method(name_and_type).unwrap();
-        let constant_pool = &class.constant_pool;
+    /// Allocates an array of the class with the lengths of its dimensions,
+    /// the outermost first, creating the nested arrays as well.
+    fn allocate_array(
+        &mut self,
+        class: Rc<Class>,
+        lengths: &[i32],
+    ) -> Result<Reference, Throwable> {
+        if let Some(length) = lengths.iter().find(|&&length| length < 0) {
+            let message = length.to_string();
+            return Err(Throwable::new(NEGATIVE_ARRAY_SIZE_EXCEPTION, &message));
+        }
 
-        Thread {
-            frame_stack: vec![Frame::from(method, constant_pool)],
-            pc: 0,
-            registry,
+        // the arrays of each dimension, as many as the product of the lengths
+        // of the outer ones
+        let mut size = 0usize;
+        let mut count = 1usize;
+        for &length in lengths {
+            size = size.saturating_add(count.saturating_mul(Heap::array_size(length as usize)));
+            count = count.saturating_mul(length as usize);
+        }
+        self.safepoint(size)?;
+        self.allocate_arrays(class, lengths)
+    }
+
+    fn allocate_arrays(
+        &mut self,
+        class: Rc<Class>,
+        lengths: &[i32],
+    ) -> Result<Reference, Throwable> {
+        let (&length, nested_lengths) = lengths.split_first().unwrap();
+        let component_type = class.component_type().unwrap();
+        let array = self.vm.heap.allocate_array(class, length as usize);
+        if !nested_lengths.is_empty() {
+            let component = self.vm.resolve_class(&component_type)?;
+            for index in 0..length as usize {
+                let nested = self.allocate_arrays(component.clone(), nested_lengths)?;
+                self.vm.heap.get_mut(array).fields[index] = Type::Reference(nested);
+            }
         }
+        Ok(array)
     }
 
-    fn pop_args(&mut self, descriptor: &str) -> Vec<Type> {
-        let (args_types, _) = descriptor.split_once(")").unwrap();
-        let mut args = vec![];
-        let re = Regex::new(r"(I)").unwrap();
+    /// Checks the index against the bounds of the array.
+    fn array_index(&self, array: Reference, index: i32) -> Result<usize, Throwable> {
+        let length = self.vm.heap.get(array).fields.len();
+        if index < 0 || index as usize >= length {
+            let message = format!("Index {} out of bounds for length {}", index, length);
+            return Err(Throwable::new(
+                ARRAY_INDEX_OUT_OF_BOUNDS_EXCEPTION,
+                &message,
+            ));
+        }
+        Ok(index as usize)
+    }
+
+    /// Initializes the class on its first active use, following JVMS 5.5.
+    pub(crate) fn initialize(&mut self, class: &Rc<Class>) -> Result<(), Throwable> {
+        match class.initialization_state.get() {
+            InitializationState::Linked => (),
+            // the recursive request of a class being initialized by this thread
+            InitializationState::BeingInitialized | InitializationState::Initialized => {
+                return Ok(())
+            }
+            InitializationState::Erroneous => {
+                let name = class.this_class().replace('/', ".");
+                let message = format!("Could not initialize class {}", name);
+                return Err(Throwable::new(NO_CLASS_DEF_FOUND_ERROR, &message));
+            }
+        }
+        class
+            .initialization_state
+            .set(InitializationState::BeingInitialized);
+
+        let prepared = class
+            .initialize_constant_fields(self.vm)
+            .and_then(|()| self.initialize_supertypes(class));
+        if let Err(throwable) = prepared {
+            class
+                .initialization_state
+                .set(InitializationState::Erroneous);
+            return Err(throwable);
+        }
+
+        if let Some(method) = class.method(CLINIT).cloned() {
+            if let Err(throwable) = self.invoke(class.clone(), method, vec![]) {
+                class
+                    .initialization_state
+                    .set(InitializationState::Erroneous);
+                if self.vm.is_subclass_of(&throwable.class_name, ERROR) {
+                    return Err(throwable);
+                }
+                let error = Throwable::without_message(EXCEPTION_IN_INITIALIZER_ERROR);
+                return Err(error.with_cause(throwable));
+            }
+        }
+
+        class
+            .initialization_state
+            .set(InitializationState::Initialized);
+        Ok(())
+    }
+
+    fn initialize_supertypes(&mut self, class: &Class) -> Result<(), Throwable> {
+        if class.is_interface() {
+            return Ok(());
+        }
+
+        if let Some(super_class) = class.super_class() {
+            let super_class = self.vm.resolve_class(&super_class)?;
+            self.initialize(&super_class)?;
+        }
+        self.initialize_superinterfaces(class.interfaces())
+    }
+
+    fn initialize_superinterfaces(&mut self, interfaces: Vec<String>) -> Result<(), Throwable> {
+        for interface in interfaces {
+            let interface = self.vm.resolve_class(&interface)?;
+            self.initialize_superinterfaces(interface.interfaces())?;
+            if interface.declares_default_methods() {
+                self.initialize(&interface)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn invoke_native(
+        &mut self,
+        class: &Class,
+        method: &Method,
+        args: &[Type],
+    ) -> Result<Type, Throwable> {
+        let class_name = class.this_class();
+        let name_and_type = method.name_and_type();
+        let full_name = format!("{}.{}", class_name, name_and_type);
+
+        match self.vm.registry.native_method(&full_name).copied() {
+            Some(native_method) => native_method(self.vm, args),
+            None => {
+                let description = method_description(&class_name, &name_and_type);
+                Err(Throwable::new(UNSATISFIED_LINK_ERROR, &description))
+            }
+        }
+    }
+
+    /// Invokes the method from the current frame: a native method runs to
+    /// completion, any other one gets a new frame.
+    fn call(
+        &mut self,
+        class: Rc<Class>,
+        method: Rc<Method>,
+        args: Vec<Type>,
+    ) -> Result<(), Throwable> {
+        if !method.is_native() {
+            return self.enter(class, method, args);
+        }
+
+        let return_value = self.with_handles(&args, |thread| {
+            thread.safepoint(0)?;
+            thread.invoke_native(&class, &method, &args)
+        })?;
+        match return_value {
+            Void => (),
+            return_value => self.push(return_value),
+        }
+        Ok(())
+    }
+
+    /// Invokes the method handle with the arguments of a call site of the
+    /// type, which has to be the type of the handle for `invokeExact` and
+    /// which `invoke` adapts the handle to the way `asType` does.
+    fn invoke_handle(
+        &mut self,
+        handle: Reference,
+        call_type: &MethodDescriptor,
+        mut args: Vec<Type>,
+        exact: bool,
+    ) -> Result<Type, Throwable> {
+        let handle_type = method_handle::handle_type(self.vm, handle);
+        if handle_type != *call_type {
+            let handle_type_string = method_handle::type_string(&handle_type);
+            let call_type_string = method_handle::type_string(call_type);
+            if exact {
+                let message = format!(
+                    "expected {} but found {}",
+                    handle_type_string, call_type_string
+                );
+                return Err(method_handle::wrong_method_type(&message));
+            }
+            if !method_handle::adaptable(self.vm, &handle_type, call_type) {
+                let message = format!(
+                    "cannot convert MethodHandle{} to {}",
+                    handle_type_string, call_type_string
+                );
+                return Err(method_handle::wrong_method_type(&message));
+            }
+            return self.invoke_adapted(handle, &handle_type, call_type, args);
+        }
 
-        for cap in re.captures_iter(args_types) {
-            match &cap[0] {
-                "I" => {
-                    let arg = self.pop();
-                    matches!(arg, Type::Int(_));
-                    args.push(arg);
+        match method_handle::form(self.vm, handle) {
+            Form::Direct {
+                kind,
+                class_name,
+                name_and_type,
+            } => self.invoke_direct(kind, &class_name, &name_and_type, args),
+            Form::Bound { target, argument } => {
+                args.insert(0, argument);
+                let target_type = method_handle::handle_type(self.vm, target);
+                self.invoke_handle(target, &target_type, args, true)
+            }
+            Form::Adapted { target } => {
+                let target_type = method_handle::handle_type(self.vm, target);
+                self.invoke_adapted(target, &target_type, &handle_type, args)
+            }
+        }
+    }
+
+    /// Invokes the method handle of the type with arguments of the other
+    /// one, converting them and then the return value back.
+    fn invoke_adapted(
+        &mut self,
+        handle: Reference,
+        handle_type: &MethodDescriptor,
+        call_type: &MethodDescriptor,
+        args: Vec<Type>,
+    ) -> Result<Type, Throwable> {
+        // boxing an argument runs Java code, which may collect the ones
+        // boxed before, so they are held in handles until the invokeExact or
+        // invoke instruction completes
+        let mut converted = vec![];
+        let parameters = call_type.parameters.iter().zip(&handle_type.parameters);
+        for (arg, (from, to)) in args.into_iter().zip(parameters) {
+            let value = self.convert(arg, Some(from), Some(to))?;
+            self.vm.push_handles(&[value]);
+            converted.push(value);
+        }
+
+        let return_value = self.invoke_handle(handle, handle_type, converted, true)?;
+        let (from, to) = (&handle_type.return_type, &call_type.return_type);
+        self.convert(return_value, from.as_ref(), to.as_ref())
+    }
+
+    /// Invokes the method or accesses the field of a direct method handle, the
+    /// way the bytecode for the kind of reference does.
+    fn invoke_direct(
+        &mut self,
+        kind: ReferenceKind,
+        class_name: &str,
+        name_and_type: &str,
+        mut args: Vec<Type>,
+    ) -> Result<Type, Throwable> {
+        let receiver = |args: &[Type]| args[0].reference().ok_or_else(null_pointer_exception);
+
+        match kind {
+            ReferenceKind::GetField => {
+                let slot = self.vm.resolve_instance_field(class_name, name_and_type)?;
+                Ok(self.vm.heap.get(receiver(&args)?).fields[slot])
+            }
+            ReferenceKind::PutField => {
+                let slot = self.vm.resolve_instance_field(class_name, name_and_type)?;
+                let value = narrow(args[1], name_and_type);
+                self.vm.heap.get_mut(receiver(&args)?).fields[slot] = value;
+                Ok(Void)
+            }
+            ReferenceKind::GetStatic => {
+                let (class, slot) = self.vm.resolve_static_field(class_name, name_and_type)?;
+                self.initialize(&class)?;
+                let value = class.static_values.borrow()[slot];
+                Ok(value)
+            }
+            ReferenceKind::PutStatic => {
+                let (class, slot) = self.vm.resolve_static_field(class_name, name_and_type)?;
+                self.initialize(&class)?;
+                class.static_values.borrow_mut()[slot] = narrow(args[0], name_and_type);
+                Ok(Void)
+            }
+            ReferenceKind::NewInvokeSpecial => {
+                let class = self.vm.resolve_class(class_name)?;
+                if class.is_interface() || class.is_abstract() {
+                    let name = class_name.replace('/', ".");
+                    return Err(Throwable::new(INSTANTIATION_ERROR, &name));
                 }
-                _ => {}
+                let method = class.method(name_and_type).cloned().ok_or_else(|| {
+                    let description = method_description(class_name, name_and_type);
+                    Throwable::new(NO_SUCH_METHOD_ERROR, &description)
+                })?;
+                self.initialize(&class)?;
+
+                self.safepoint(Heap::instance_size(&class))?;
+                let object = self.vm.heap.allocate(class.clone());
+                args.insert(0, Type::Reference(object));
+                self.invoke(class, method, args)?;
+                Ok(Type::Reference(object))
+            }
+            _ => {
+                let interface = match kind {
+                    ReferenceKind::InvokeVirtual => false,
+                    ReferenceKind::InvokeInterface => true,
+                    _ => self.vm.resolve_class(class_name)?.is_interface(),
+                };
+                let (class, method) =
+                    self.vm
+                        .resolve_method(class_name, name_and_type, interface)?;
+                let (class, method) = match kind {
+                    ReferenceKind::InvokeStatic => {
+                        self.initialize(&class)?;
+                        (class, method)
+                    }
+                    ReferenceKind::InvokeVirtual | ReferenceKind::InvokeInterface => {
+                        let receiver = self.vm.heap.get(receiver(&args)?).class.clone();
+                        dispatch::select(&receiver, &class, &method)?
+                    }
+                    _ => {
+                        receiver(&args)?;
+                        (class, method)
+                    }
+                };
+                self.invoke(class, method, args)
             }
         }
+    }
+
+    /// Converts the value of the type to the other one for `asType`, by
+    /// widening, boxing, unboxing or casting it, `None` standing for `void`.
+    fn convert(
+        &mut self,
+        value: Type,
+        from: Option<&FieldDescriptor>,
+        to: Option<&FieldDescriptor>,
+    ) -> Result<Type, Throwable> {
+        let (from, to) = match (from, to) {
+            (_, None) => return Ok(Void),
+            (None, Some(to)) => return Ok(Type::zero(&to.to_string())),
+            (Some(from), Some(to)) if from == to => return Ok(value),
+            (Some(from), Some(to)) => (from, to),
+        };
 
+        match (from.wrapper(), to.class_name()) {
+            (Some(_), None) => Ok(method_handle::widen(value, to)),
+            (Some((wrapper, _)), Some(_)) => {
+                let class = self.vm.resolve_class(wrapper)?;
+                let descriptor = format!("({})L{};", from, wrapper);
+                let value_of = format!("valueOf:{}", descriptor);
+                let method = class.method(&value_of).cloned().ok_or_else(|| {
+                    let description = method_description(wrapper, &value_of);
+                    Throwable::new(NO_SUCH_METHOD_ERROR, &description)
+                })?;
+                self.initialize(&class)?;
+                self.invoke(class, method, vec![value])
+            }
+            (None, None) => {
+                let object = value.reference().ok_or_else(null_pointer_exception)?;
+                match method_handle::unbox(self.vm, object) {
+                    Some((value, primitive)) if method_handle::widens(&primitive, to) => {
+                        Ok(method_handle::widen(value, to))
+                    }
+                    _ => {
+                        let class = self.vm.heap.get(object).class.clone();
+                        let (wrapper, _) = to.wrapper().unwrap();
+                        let target = self.vm.resolve_class(wrapper)?;
+                        Err(class_cast_exception(&class, &target))
+                    }
+                }
+            }
+            (None, Some(class_name)) => {
+                if let Some(object) = value.reference() {
+                    let target = self.vm.resolve_class(&class_name)?;
+                    let class = self.vm.heap.get(object).class.clone();
+                    if !self.vm.is_assignable(&class.this_class(), &class_name) {
+                        return Err(class_cast_exception(&class, &target));
+                    }
+                }
+                Ok(value)
+            }
+        }
+    }
+
+    /// Concatenates the constants and the arguments of a string
+    /// concatenation call site into a new string.
+    fn concat(&mut self, recipes: &[Recipe], args: &[Type]) -> Result<Reference, Throwable> {
+        // toString may collect the arguments that come after
+        self.with_handles(args, |thread| thread.concat_strings(recipes, args))
+    }
+
+    fn concat_strings(
+        &mut self,
+        recipes: &[Recipe],
+        args: &[Type],
+    ) -> Result<Reference, Throwable> {
+        let mut args = args.iter();
+        let mut string = String::new();
+        for recipe in recipes {
+            match recipe {
+                Recipe::Constant(constant) => string.push_str(constant),
+                Recipe::Argument(descriptor) => {
+                    let arg = *args.next().unwrap();
+                    match string::value_of(arg, descriptor) {
+                        Some(value) => string.push_str(&value),
+                        None => string.push_str(&self.string_of(arg)?),
+                    }
+                }
+            }
+        }
+        self.vm.new_string(&string)
+    }
+
+    /// The string `String.valueOf` returns for the reference: `null`, the
+    /// string itself or what its `toString` returns.
+    fn string_of(&mut self, value: Type) -> Result<String, Throwable> {
+        let object = match value.reference() {
+            Some(object) => object,
+            None => return Ok("null".to_string()),
+        };
+        let class = self.vm.heap.get(object).class.clone();
+        if class.this_class() == STRING {
+            return Ok(self.vm.string_value(object));
+        }
+
+        let (class, method) = dispatch::lookup(&self.vm.registry, &class, TO_STRING).unwrap();
+        match self.invoke(class, method, vec![value])?.reference() {
+            Some(string) => Ok(self.vm.string_value(string)),
+            None => Ok("null".to_string()),
+        }
+    }
+
+    /// The class `invokespecial` looks the method up from: the superclass of
+    /// the current class for a method of one of its superclasses, as every
+    /// class has ACC_SUPER set since Java SE 8, or else the referenced class.
+    fn special_lookup_class(&mut self, class_name: &str) -> Result<Rc<Class>, Throwable> {
+        let referenced = self.vm.resolve_class(class_name)?;
+        let current = self.vm.frame_stack.last().unwrap().class.clone();
+        let current_name = current.this_class();
+
+        let is_super_class = !referenced.is_interface()
+            && current_name != class_name
+            && self.vm.is_subclass_of(&current_name, class_name);
+        match current.super_class() {
+            Some(super_class) if is_super_class => self.vm.resolve_class(&super_class),
+            _ => Ok(referenced),
+        }
+    }
+
+    /// A point where every reference the thread holds is in its frames or
+    /// in handles, so that garbage can be collected to make room for an
+    /// allocation of the size.
+    fn safepoint(&mut self, size: usize) -> Result<(), Throwable> {
+        self.vm.reserve(size)
+    }
+
+    /// Runs the closure with the references among the values held in
+    /// handles, for the Java code it runs not to collect them.
+    fn with_handles<T>(&mut self, values: &[Type], f: impl FnOnce(&mut Self) -> T) -> T {
+        let mark = self.vm.push_handles(values);
+        let result = f(self);
+        self.vm.pop_handles(mark);
+        result
+    }
+
+    fn enter(
+        &mut self,
+        class: Rc<Class>,
+        method: Rc<Method>,
+        args: Vec<Type>,
+    ) -> Result<(), Throwable> {
+        let mut frame = Frame::from(method, class);
+        let mut slot = 0;
+        for arg in args {
+            frame.locals[slot] = arg;
+            slot += arg.category();
+        }
+        frame.return_address = self.pc;
+
+        self.stack_usage += frame.size();
+        self.vm.frame_stack.push(frame);
+        if self.stack_usage > self.vm.stack_size {
+            self.leave();
+            return Err(Throwable::without_message(STACK_OVERFLOW_ERROR));
+        }
+        // a safepoint, where the arguments are already in the locals
+        if let Err(throwable) = self.vm.reserve(0) {
+            self.leave();
+            return Err(throwable);
+        }
+
+        self.pc = 0;
+        Ok(())
+    }
+
+    /// Pops the current frame, returning to the caller, and reports the new
+    /// frame stack depth.
+    fn leave(&mut self) -> usize {
+        let frame = self.vm.frame_stack.pop().unwrap();
+        self.stack_usage -= frame.size();
+        self.pc = frame.return_address;
+        self.vm.frame_stack.len()
+    }
+
+    fn locals(&mut self) -> &mut Vec<Type> {
+        &mut self.vm.frame_stack.last_mut().unwrap().locals
+    }
+
+    fn operand_stack(&mut self) -> &mut Vec<Type> {
+        &mut self.vm.frame_stack.last_mut().unwrap().operand_stack
+    }
+
+    pub fn new(vm: &'a mut VirtualMachine) -> Thread<'a> {
+        Thread {
+            vm,
+            pc: 0,
+            stack_usage: 0,
+        }
+    }
+
+    /// Pops the arguments of an instance method, the receiver being the first.
+    fn pop_instance_args(
+        &mut self,
+        descriptor: &MethodDescriptor,
+    ) -> Result<(Reference, Vec<Type>), Throwable> {
+        let mut args = self.pop_args(descriptor);
+        let receiver = self.pop().reference().ok_or_else(null_pointer_exception)?;
+        args.insert(0, Type::Reference(receiver));
+        Ok((receiver, args))
+    }
+
+    fn pop_args(&mut self, descriptor: &MethodDescriptor) -> Vec<Type> {
+        let mut args = descriptor
+            .parameters
+            .iter()
+            .map(|_| self.pop())
+            .collect::<Vec<_>>();
         args.reverse();
         args
     }
 }
+
+/// Compares two values to -1, 0 or 1, or to the result for NaN when they are
+/// unordered (JVMS 6.5 fcmp<op>).
+fn compare<T: PartialOrd>(lhs: T, rhs: T, unordered: i32) -> Type {
+    match lhs.partial_cmp(&rhs) {
+        Some(ordering) => Int(ordering as i32),
+        None => Int(unordered),
+    }
+}
+
+/// Narrows a value stored into a `boolean` field (JVMS 6.5 putfield, putstatic).
+fn narrow(value: Type, name_and_type: &str) -> Type {
+    match value {
+        Int(x) if name_and_type.ends_with(":Z") => Int(x & 1),
+        value => value,
+    }
+}
+
+/// Narrows a value stored into an array component (JVMS 6.5 bastore, castore, sastore).
+fn narrow_component(value: Type, component_type: &str) -> Type {
+    match (value, component_type) {
+        (Int(x), "Z") => Int(x & 1),
+        (Int(x), "B") => Int(x as i8 as i32),
+        (Int(x), "C") => Int(x as u16 as i32),
+        (Int(x), "S") => Int(x as i16 as i32),
+        (value, _) => value,
+    }
+}
+
+fn class_cast_exception(class: &Class, target: &Class) -> Throwable {
+    let module = |class: &Class| {
+        if class.bootstrap {
+            "module java.base of loader 'bootstrap'"
+        } else {
+            "unnamed module of loader 'app'"
+        }
+    };
+    let class_name = class.this_class().replace('/', ".");
+    let target_name = target.this_class().replace('/', ".");
+
+    let modules = if class.bootstrap == target.bootstrap {
+        format!(
+            "{} and {} are in {}",
+            class_name,
+            target_name,
+            module(class)
+        )
+    } else {
+        format!(
+            "{} is in {}; {} is in {}",
+            class_name,
+            module(class),
+            target_name,
+            module(target)
+        )
+    };
+    let message = format!(
+        "class {} cannot be cast to class {} ({})",
+        class_name, target_name, modules
+    );
+    Throwable::new(CLASS_CAST_EXCEPTION, &message)
+}
+
+fn static_method_error(expected: &str, class: &Class, method: &Method) -> Throwable {
+    let description = method_description(&class.this_class(), &method.name_and_type());
+    let message = format!("{} method {}", expected, description);
+    Throwable::new(INCOMPATIBLE_CLASS_CHANGE_ERROR, &message)
+}
+
+fn null_pointer_exception() -> Throwable {
+    Throwable::without_message(NULL_POINTER_EXCEPTION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throws_no_such_method_error_for_a_missing_constructor() {
+        let class_path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/classes");
+        let mut vm = VirtualMachine::new(class_path, "ru.khlevnov.Strings");
+        let mut thread = Thread::new(&mut vm);
+
+        let kind = ReferenceKind::NewInvokeSpecial;
+        let throwable = thread
+            .invoke_direct(kind, "ru/khlevnov/Strings", "<init>:(I)V", vec![Int(1)])
+            .unwrap_err();
+        assert_eq!(throwable.class_name, NO_SUCH_METHOD_ERROR);
+        let message = throwable.message.as_deref();
+        assert_eq!(message, Some("'void ru.khlevnov.Strings.<init>(int)'"));
+    }
+}
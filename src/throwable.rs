@@ -0,0 +1,75 @@
+use crate::heap::Reference;
+use std::fmt;
+
+pub(crate) const ABSTRACT_METHOD_ERROR: &str = "java/lang/AbstractMethodError";
+pub(crate) const ARITHMETIC_EXCEPTION: &str = "java/lang/ArithmeticException";
+pub(crate) const ARRAY_INDEX_OUT_OF_BOUNDS_EXCEPTION: &str =
+    "java/lang/ArrayIndexOutOfBoundsException";
+pub(crate) const ARRAY_STORE_EXCEPTION: &str = "java/lang/ArrayStoreException";
+pub(crate) const BOOTSTRAP_METHOD_ERROR: &str = "java/lang/BootstrapMethodError";
+pub(crate) const CLASS_CAST_EXCEPTION: &str = "java/lang/ClassCastException";
+pub(crate) const CLONE_NOT_SUPPORTED_EXCEPTION: &str = "java/lang/CloneNotSupportedException";
+pub(crate) const CLASS_FORMAT_ERROR: &str = "java/lang/ClassFormatError";
+pub(crate) const ERROR: &str = "java/lang/Error";
+pub(crate) const EXCEPTION_IN_INITIALIZER_ERROR: &str = "java/lang/ExceptionInInitializerError";
+pub(crate) const ILLEGAL_ACCESS_EXCEPTION: &str = "java/lang/IllegalAccessException";
+pub(crate) const ILLEGAL_ARGUMENT_EXCEPTION: &str = "java/lang/IllegalArgumentException";
+pub(crate) const INSTANTIATION_ERROR: &str = "java/lang/InstantiationError";
+pub(crate) const INCOMPATIBLE_CLASS_CHANGE_ERROR: &str = "java/lang/IncompatibleClassChangeError";
+pub(crate) const NEGATIVE_ARRAY_SIZE_EXCEPTION: &str = "java/lang/NegativeArraySizeException";
+pub(crate) const NO_CLASS_DEF_FOUND_ERROR: &str = "java/lang/NoClassDefFoundError";
+pub(crate) const NO_SUCH_FIELD_ERROR: &str = "java/lang/NoSuchFieldError";
+pub(crate) const NO_SUCH_FIELD_EXCEPTION: &str = "java/lang/NoSuchFieldException";
+pub(crate) const NO_SUCH_METHOD_ERROR: &str = "java/lang/NoSuchMethodError";
+pub(crate) const NO_SUCH_METHOD_EXCEPTION: &str = "java/lang/NoSuchMethodException";
+pub(crate) const NULL_POINTER_EXCEPTION: &str = "java/lang/NullPointerException";
+pub(crate) const OUT_OF_MEMORY_ERROR: &str = "java/lang/OutOfMemoryError";
+pub(crate) const STACK_OVERFLOW_ERROR: &str = "java/lang/StackOverflowError";
+pub(crate) const UNSATISFIED_LINK_ERROR: &str = "java/lang/UnsatisfiedLinkError";
+pub(crate) const UNSUPPORTED_CLASS_VERSION_ERROR: &str = "java/lang/UnsupportedClassVersionError";
+pub(crate) const VERIFY_ERROR: &str = "java/lang/VerifyError";
+pub(crate) const WRONG_METHOD_TYPE_EXCEPTION: &str = "java/lang/invoke/WrongMethodTypeException";
+
+#[derive(Debug)]
+pub struct Throwable {
+    pub(crate) class_name: String,
+    pub(crate) message: Option<String>,
+    pub(crate) cause: Option<Box<Throwable>>,
+    /// The instance of the throwable class on the heap, once there is one.
+    pub(crate) object: Option<Reference>,
+}
+
+impl Throwable {
+    pub fn new(class_name: &str, message: &str) -> Throwable {
+        Throwable {
+            class_name: class_name.to_string(),
+            message: Some(message.to_string()),
+            cause: None,
+            object: None,
+        }
+    }
+
+    pub fn without_message(class_name: &str) -> Throwable {
+        Throwable {
+            class_name: class_name.to_string(),
+            message: None,
+            cause: None,
+            object: None,
+        }
+    }
+
+    pub fn with_cause(mut self, cause: Throwable) -> Throwable {
+        self.cause = Some(Box::new(cause));
+        self
+    }
+}
+
+impl fmt::Display for Throwable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.class_name.replace('/', "."))?;
+        match &self.message {
+            Some(message) => write!(f, ": {}", message),
+            None => Ok(()),
+        }
+    }
+}
@@ -1,14 +1,39 @@
+use crate::heap::Reference;
+
 #[derive(Clone, Copy, Debug)]
 pub enum Type {
     Int(i32),
     Float(f32),
     Long(i64),
     Double(f64),
+    Reference(Reference),
     Null,
+    /// The address of the instruction after a `jsr`, which `ret` returns to.
+    ReturnAddress(usize),
     Void,
 }
 
 impl Type {
+    /// The default value of a field or array component with the descriptor (JVMS 2.3, 2.4).
+    pub(crate) fn zero(descriptor: &str) -> Type {
+        match descriptor.as_bytes().first() {
+            Some(b'J') => Type::Long(0),
+            Some(b'F') => Type::Float(0.0),
+            Some(b'D') => Type::Double(0.0),
+            Some(b'L') | Some(b'[') => Type::Null,
+            _ => Type::Int(0),
+        }
+    }
+
+    /// The number of local variable or operand stack slots the value takes
+    /// up, its computational type category (JVMS 2.11.1).
+    pub(crate) fn category(self) -> usize {
+        match self {
+            Type::Long(_) | Type::Double(_) => 2,
+            _ => 1,
+        }
+    }
+
     pub(crate) fn int(self) -> i32 {
         match self {
             Type::Int(x) => x,
@@ -36,4 +61,28 @@ impl Type {
             _ => panic!(),
         }
     }
+
+    pub(crate) fn return_address(self) -> usize {
+        match self {
+            Type::ReturnAddress(address) => address,
+            _ => panic!(),
+        }
+    }
+
+    /// Returns the reference, or `None` for `null`.
+    pub(crate) fn reference(self) -> Option<Reference> {
+        match self {
+            Type::Reference(reference) => Some(reference),
+            Type::Null => None,
+            _ => panic!(),
+        }
+    }
+
+    /// Returns the reference, or `None` for `null` or any other value.
+    pub(crate) fn as_reference(self) -> Option<Reference> {
+        match self {
+            Type::Reference(reference) => Some(reference),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,314 @@
+use crate::bytecode::{instructions, mnemonic};
+use crate::class_file::{
+    ClassFile, CONSTANT_DYNAMIC, CONSTANT_INVOKE_DYNAMIC, CONSTANT_METHOD_HANDLE,
+    CONSTANT_METHOD_TYPE, CONSTANT_MODULE, CONSTANT_PACKAGE,
+};
+use crate::descriptor::MethodDescriptor;
+use crate::thread::{
+    GOTO_W, IFEQ, IFNONNULL, IFNULL, JSR, JSR_W, LOOKUPSWITCH, RET, TABLESWITCH, WIDE,
+};
+use crate::throwable::{Throwable, UNSUPPORTED_CLASS_VERSION_ERROR, VERIFY_ERROR};
+use std::fmt;
+
+pub(crate) const JAVA_1_1: u16 = 45;
+pub(crate) const JAVA_6: u16 = 50;
+pub(crate) const JAVA_7: u16 = 51;
+#[cfg(test)]
+pub(crate) const JAVA_8: u16 = 52;
+pub(crate) const JAVA_9: u16 = 53;
+pub(crate) const JAVA_11: u16 = 55;
+pub(crate) const JAVA_12: u16 = 56;
+pub(crate) const JAVA_17: u16 = 61;
+
+const PREVIEW_MINOR_VERSION: u16 = 0xffff;
+const ACC_STATIC: u16 = 0x0008;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ClassVersion {
+    pub(crate) major: u16,
+    pub(crate) minor: u16,
+}
+
+impl ClassVersion {
+    pub fn is_preview(self) -> bool {
+        self.major >= JAVA_12 && self.minor == PREVIEW_MINOR_VERSION
+    }
+
+    pub(crate) fn allows_jsr(self) -> bool {
+        self.major < JAVA_7
+    }
+
+    pub(crate) fn requires_stack_map_table(self) -> bool {
+        self.major >= JAVA_6
+    }
+
+    pub(crate) fn supports_constant_tag(self, tag: u8) -> bool {
+        match tag {
+            CONSTANT_METHOD_HANDLE | CONSTANT_METHOD_TYPE | CONSTANT_INVOKE_DYNAMIC => {
+                self.major >= JAVA_7
+            }
+            CONSTANT_MODULE | CONSTANT_PACKAGE => self.major >= JAVA_9,
+            CONSTANT_DYNAMIC => self.major >= JAVA_11,
+            _ => true,
+        }
+    }
+}
+
+impl fmt::Display for ClassVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// The class file versions the VM loads: the major versions from `oldest` to
+/// `latest`, those of Java 1.1 to 17 by default, and the preview features of
+/// the latest one when enabled.
+#[derive(Clone, Debug)]
+pub struct SupportedVersions {
+    pub(crate) oldest: u16,
+    pub(crate) latest: u16,
+    pub(crate) enable_preview: bool,
+}
+
+impl Default for SupportedVersions {
+    fn default() -> Self {
+        SupportedVersions {
+            oldest: JAVA_1_1,
+            latest: JAVA_17,
+            enable_preview: false,
+        }
+    }
+}
+
+impl SupportedVersions {
+    pub fn new(enable_preview: bool) -> SupportedVersions {
+        SupportedVersions {
+            enable_preview,
+            ..SupportedVersions::default()
+        }
+    }
+
+    pub(crate) fn check(&self, class_name: &str, version: ClassVersion) -> Result<(), Throwable> {
+        let (oldest, latest) = (self.oldest, self.latest);
+
+        let message = if version.major > latest {
+            format!(
+                "{} has been compiled by a more recent version of the Java Runtime \
+                 (class file version {}), this version of the Java Runtime only recognizes \
+                 class file versions up to {}.0",
+                class_name, version, latest
+            )
+        } else if version.major < oldest {
+            format!(
+                "{} (class file version {}) is older than the oldest class file version \
+                 {}.0 this version of the Java Runtime recognizes",
+                class_name, version, oldest
+            )
+        } else if version.is_preview() && version.major != latest {
+            format!(
+                "{} (class file version {}) was compiled with preview features that are \
+                 unsupported. This version of the Java Runtime only recognizes preview \
+                 features for class file version {}.{}",
+                class_name, version, latest, PREVIEW_MINOR_VERSION
+            )
+        } else if version.is_preview() && !self.enable_preview {
+            format!(
+                "Preview features are not enabled for {} (class file version {}). \
+                 Try running with '--enable-preview'",
+                class_name, version
+            )
+        } else if version.major >= JAVA_12 && version.minor != 0 && !version.is_preview() {
+            format!(
+                "{} has a non-zero minor version {} with major version {}",
+                class_name, version.minor, version.major
+            )
+        } else {
+            return Ok(());
+        };
+
+        Err(Throwable::new(UNSUPPORTED_CLASS_VERSION_ERROR, &message))
+    }
+}
+
+pub(crate) fn check_features(class_name: &str, class_file: &ClassFile) -> Result<(), Throwable> {
+    let version = class_file.version();
+
+    for method in &class_file.methods {
+        let code = match method.code() {
+            Some(code) => code,
+            None => continue,
+        };
+
+        let method_name = || {
+            format!(
+                "{}.{}{}",
+                class_name,
+                class_file.utf8(method.name_index).unwrap_or_default(),
+                class_file.utf8(method.descriptor_index).unwrap_or_default()
+            )
+        };
+
+        let descriptor = class_file.utf8(method.descriptor_index).unwrap_or_default();
+        if let Some(descriptor) = MethodDescriptor::parse(descriptor) {
+            let receiver_slots = usize::from(method.access_flags & ACC_STATIC == 0);
+            if descriptor.parameter_slots() + receiver_slots > code.max_locals {
+                let message = format!(
+                    "Arguments can't fit into locals in method {}",
+                    method_name()
+                );
+                return Err(Throwable::new(VERIFY_ERROR, &message));
+            }
+        }
+
+        let mut has_branches = !code.exception_table.is_empty();
+        for (pc, opcode) in instructions(&code.code) {
+            let opcode = match opcode {
+                WIDE => code.code.get(pc + 1).copied().unwrap_or(WIDE),
+                opcode => opcode,
+            };
+
+            if mnemonic(opcode).is_none() {
+                let message = format!(
+                    "Bad instruction {:#04x} at {} in method {}",
+                    opcode,
+                    pc,
+                    method_name()
+                );
+                return Err(Throwable::new(VERIFY_ERROR, &message));
+            }
+            if !version.allows_jsr() && matches!(opcode, JSR | JSR_W | RET) {
+                let message = format!(
+                    "Bad instruction {:#04x} at {} in method {} of class file version {}",
+                    opcode,
+                    pc,
+                    method_name(),
+                    version
+                );
+                return Err(Throwable::new(VERIFY_ERROR, &message));
+            }
+
+            has_branches |= matches!(
+                opcode,
+                IFEQ..=JSR | TABLESWITCH | LOOKUPSWITCH | IFNULL | IFNONNULL | GOTO_W | JSR_W
+            );
+        }
+
+        if has_branches && version.requires_stack_map_table() && code.stack_map_table().is_none() {
+            let message = format!(
+                "Expecting a stackmap frame in method {} of class file version {}",
+                method_name(),
+                version
+            );
+            return Err(Throwable::new(VERIFY_ERROR, &message));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::class_file::CONSTANT_INTERFACE_METHOD_REF;
+
+    fn check(major: u16, minor: u16, enable_preview: bool) -> Result<(), String> {
+        check_in(&SupportedVersions::new(enable_preview), major, minor)
+    }
+
+    fn check_in(versions: &SupportedVersions, major: u16, minor: u16) -> Result<(), String> {
+        let version = ClassVersion { major, minor };
+        versions.check("Foo", version).map_err(|throwable| {
+            assert_eq!(throwable.class_name, UNSUPPORTED_CLASS_VERSION_ERROR);
+            throwable.message.unwrap()
+        })
+    }
+
+    #[test]
+    fn accepts_supported_versions() {
+        assert!(check(JAVA_1_1, 3, false).is_ok());
+        assert!(check(JAVA_8, 0, false).is_ok());
+        assert!(check(JAVA_17, 0, false).is_ok());
+        // minor versions are free before Java 12
+        assert!(check(JAVA_11, 7, false).is_ok());
+    }
+
+    #[test]
+    fn rejects_too_recent_major_version() {
+        let message = check(JAVA_17 + 1, 0, false).unwrap_err();
+        assert!(message.contains("more recent version"), "{}", message);
+        assert!(message.contains("up to 61.0"), "{}", message);
+        assert!(check(JAVA_17 + 1, 0, true).is_err());
+    }
+
+    #[test]
+    fn rejects_too_old_major_version() {
+        let message = check(JAVA_1_1 - 1, 0, false).unwrap_err();
+        assert!(message.contains("older than the oldest"), "{}", message);
+    }
+
+    #[test]
+    fn checks_configured_range() {
+        let versions = SupportedVersions {
+            oldest: JAVA_8,
+            latest: JAVA_11,
+            enable_preview: true,
+        };
+        assert!(check_in(&versions, JAVA_8, 0).is_ok());
+        assert!(check_in(&versions, JAVA_11, 0).is_ok());
+
+        let message = check_in(&versions, JAVA_8 - 1, 0).unwrap_err();
+        assert!(message.contains("older than the oldest"), "{}", message);
+        assert!(message.contains("version 52.0"), "{}", message);
+        let message = check_in(&versions, JAVA_11 + 1, 0).unwrap_err();
+        assert!(message.contains("more recent version"), "{}", message);
+        assert!(message.contains("up to 55.0"), "{}", message);
+    }
+
+    #[test]
+    fn allows_preview_features_of_configured_latest_version() {
+        let versions = SupportedVersions {
+            latest: JAVA_12,
+            enable_preview: true,
+            ..SupportedVersions::default()
+        };
+        assert!(check_in(&versions, JAVA_12, PREVIEW_MINOR_VERSION).is_ok());
+        let message = check_in(&versions, JAVA_17, 0).unwrap_err();
+        assert!(message.contains("up to 56.0"), "{}", message);
+    }
+
+    #[test]
+    fn gates_constant_tags_by_version() {
+        let version = |major| ClassVersion { major, minor: 0 };
+        assert!(!version(JAVA_6).supports_constant_tag(CONSTANT_METHOD_HANDLE));
+        assert!(version(JAVA_7).supports_constant_tag(CONSTANT_INVOKE_DYNAMIC));
+        assert!(!version(JAVA_8).supports_constant_tag(CONSTANT_MODULE));
+        assert!(version(JAVA_9).supports_constant_tag(CONSTANT_PACKAGE));
+        assert!(!version(JAVA_9).supports_constant_tag(CONSTANT_DYNAMIC));
+        assert!(version(JAVA_11).supports_constant_tag(CONSTANT_DYNAMIC));
+        assert!(version(JAVA_1_1).supports_constant_tag(CONSTANT_INTERFACE_METHOD_REF));
+    }
+
+    #[test]
+    fn requires_preview_features_to_be_enabled() {
+        let message = check(JAVA_17, PREVIEW_MINOR_VERSION, false).unwrap_err();
+        assert!(message.contains("--enable-preview"), "{}", message);
+        assert!(check(JAVA_17, PREVIEW_MINOR_VERSION, true).is_ok());
+    }
+
+    #[test]
+    fn rejects_preview_features_of_older_releases() {
+        let message = check(JAVA_17 - 1, PREVIEW_MINOR_VERSION, true).unwrap_err();
+        assert!(
+            message.contains("preview features that are unsupported"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn rejects_non_zero_minor_version_since_java_12() {
+        let message = check(JAVA_17, 1, true).unwrap_err();
+        assert!(message.contains("non-zero minor version 1"), "{}", message);
+        assert!(check(JAVA_12, 3, false).is_err());
+    }
+}
@@ -1,80 +1,957 @@
+use crate::call_site;
+use crate::class::{
+    array_class_name, descriptor_class_name, Class, CLASS, CLONEABLE, OBJECT, SERIALIZABLE,
+};
 use crate::class_loader::ClassLoader;
+use crate::descriptor::MethodDescriptor;
+use crate::dispatch;
+use crate::floating_decimal::{double_to_string, float_to_string};
+use crate::frame::Frame;
+use crate::heap::{Heap, Reference};
+use crate::method::Method;
+use crate::method_handle::{self, class_descriptor, simple_name};
 use crate::r#type::Type;
 use crate::registry::Registry;
-use crate::thread::Thread;
+use crate::signature::{field_signature, method_description};
+use crate::string::{self, CODER, STRING, VALUE};
+use crate::thread::{Thread, DEFAULT_STACK_SIZE};
+use crate::throwable::{
+    Throwable, CLASS_FORMAT_ERROR, CLONE_NOT_SUPPORTED_EXCEPTION, ILLEGAL_ARGUMENT_EXCEPTION,
+    INCOMPATIBLE_CLASS_CHANGE_ERROR, NO_CLASS_DEF_FOUND_ERROR, NO_SUCH_FIELD_ERROR,
+    NO_SUCH_METHOD_ERROR, OUT_OF_MEMORY_ERROR,
+};
+use crate::version::SupportedVersions;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::process;
+use std::rc::Rc;
+use std::time::Instant;
+
+const MAIN_METHOD: &str = "main:([Ljava/lang/String;)V";
+const CLASS_DESCRIPTOR: &str = "descriptor:Ljava/lang/String;";
+pub(crate) const DEFAULT_MAX_HEAP_SIZE: usize = 256 * 1024 * 1024;
 
 pub struct VirtualMachine {
     class_loader: ClassLoader,
     main_class: String,
-    registry: Registry,
-    initialization_queue: Vec<String>,
+    pub(crate) registry: Registry,
+    pub(crate) heap: Heap,
+    /// The frames of the running thread, the current one last.
+    pub(crate) frame_stack: Vec<Frame>,
+    /// The canonical instances of strings, the ones literals resolve to.
+    interned_strings: HashMap<String, Reference>,
+    /// The `java.lang.Class` instances by the descriptor of their type.
+    mirrors: HashMap<String, Reference>,
+    /// The number of lambda classes spun so far, which numbers their names.
+    pub(crate) lambda_classes: usize,
+    /// JNI-style handles: the references native code holds onto while Java
+    /// code runs, which keep the objects alive across collections.
+    handles: Vec<Reference>,
+    /// The number of garbage collections so far.
+    collections: usize,
+    properties: HashMap<String, String>,
+    assertion_status: AssertionStatus,
+    pub(crate) stack_size: usize,
+    max_heap_size: usize,
+    verbose_class: bool,
+    verbose_gc: bool,
+    start: Instant,
 }
 
 impl VirtualMachine {
     pub fn new(class_path: &str, main_class: &str) -> VirtualMachine {
+        let mut registry = Registry::default();
+        registry
+            .add_native("java/lang/Object.getClass:()Ljava/lang/Class;", get_class)
+            .add_native("java/lang/Object.hashCode:()I", hash_code)
+            .add_native("java/lang/Object.clone:()Ljava/lang/Object;", clone)
+            .add_native("java/lang/Object.toString:()Ljava/lang/String;", to_string)
+            .add_native("java/lang/Class.getName:()Ljava/lang/String;", get_name)
+            .add_native(
+                "java/lang/Class.getSimpleName:()Ljava/lang/String;",
+                get_simple_name,
+            )
+            .add_native(
+                "java/lang/Class.getCanonicalName:()Ljava/lang/String;",
+                get_canonical_name,
+            )
+            .add_native(
+                "java/lang/Class.getSuperclass:()Ljava/lang/Class;",
+                get_superclass,
+            )
+            .add_native("java/lang/Class.isInterface:()Z", is_interface)
+            .add_native("java/lang/Class.isInstance:(Ljava/lang/Object;)Z", is_instance)
+            .add_native("java/lang/Class.isEnum:()Z", is_enum)
+            .add_native("java/lang/Class.arrayType:()Ljava/lang/Class;", array_type)
+            .add_native(
+                "java/lang/Class.desiredAssertionStatus:()Z",
+                desired_assertion_status,
+            )
+            .add_native(
+                "java/lang/Class.getPrimitiveClass:(Ljava/lang/String;)Ljava/lang/Class;",
+                get_primitive_class,
+            )
+            .add_native("java/lang/String.intern:()Ljava/lang/String;", intern)
+            .add_native("java/lang/System.exit:(I)V", exit)
+            .add_native(
+                "java/lang/System.getProperty0:(Ljava/lang/String;)Ljava/lang/String;",
+                get_property,
+            )
+            .add_native(
+                "java/lang/invoke/MethodType.toString:()Ljava/lang/String;",
+                method_handle::method_type_to_string,
+            )
+            .add_native(
+                "java/lang/invoke/MethodType.toMethodDescriptorString:()Ljava/lang/String;",
+                method_handle::to_method_descriptor_string,
+            )
+            .add_native(
+                "java/lang/invoke/MethodHandle.canConvert:(Ljava/lang/invoke/MethodType;Ljava/lang/invoke/MethodType;)Z",
+                method_handle::can_convert,
+            )
+            .add_native(
+                "java/lang/invoke/DirectMethodHandle.make:(ILjava/lang/Class;Ljava/lang/String;Ljava/lang/invoke/MethodType;)Ljava/lang/invoke/MethodHandle;",
+                method_handle::make,
+            )
+            .add_native(
+                "java/lang/invoke/DirectMethodHandle.makeField:(ILjava/lang/Class;Ljava/lang/String;Ljava/lang/Class;)Ljava/lang/invoke/MethodHandle;",
+                method_handle::make_field,
+            )
+            .add_native("ru/khlevnov/PrintStream.print:(I)V", print_int)
+            .add_native("ru/khlevnov/PrintStream.print:(J)V", print_long)
+            .add_native("ru/khlevnov/PrintStream.print:(F)V", print_float)
+            .add_native("ru/khlevnov/PrintStream.print:(D)V", print_double)
+            .add_native(
+                "ru/khlevnov/PrintStream.print:(Ljava/lang/String;)V",
+                print_string,
+            );
+
         VirtualMachine {
             class_loader: ClassLoader::new(class_path),
             main_class: main_class.replace(".", "/"),
-            registry: Registry::default(),
-            initialization_queue: vec![],
+            registry,
+            heap: Heap::default(),
+            frame_stack: vec![],
+            interned_strings: HashMap::new(),
+            mirrors: HashMap::new(),
+            lambda_classes: 0,
+            handles: vec![],
+            collections: 0,
+            properties: HashMap::new(),
+            assertion_status: AssertionStatus::default(),
+            stack_size: DEFAULT_STACK_SIZE,
+            max_heap_size: DEFAULT_MAX_HEAP_SIZE,
+            verbose_class: false,
+            verbose_gc: false,
+            start: Instant::now(),
         }
     }
 
-    pub fn run(&mut self, _args: &[&str]) {
-        self.registry
-            .add_native("ru/khlevnov/PrintStream.print:(I)V", print_int)
-            .add_native("ru/khlevnov/PrintStream.print:(D)V", print_double);
+    pub fn with_property(mut self, name: &str, value: &str) -> VirtualMachine {
+        self.properties.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    pub fn with_assertions(mut self, target: &str, enabled: bool) -> VirtualMachine {
+        self.assertion_status.set(target, enabled);
+        self
+    }
+
+    pub fn with_stack_size(mut self, stack_size: usize) -> VirtualMachine {
+        self.stack_size = stack_size;
+        self
+    }
+
+    pub fn with_max_heap_size(mut self, max_heap_size: usize) -> VirtualMachine {
+        self.max_heap_size = max_heap_size;
+        self
+    }
+
+    pub fn with_verbose_class(mut self, verbose: bool) -> VirtualMachine {
+        self.verbose_class = verbose;
+        self
+    }
+
+    pub fn with_verbose_gc(mut self, verbose: bool) -> VirtualMachine {
+        self.verbose_gc = verbose;
+        self
+    }
+
+    pub fn with_supported_versions(
+        mut self,
+        supported_versions: SupportedVersions,
+    ) -> VirtualMachine {
+        self.class_loader = self
+            .class_loader
+            .with_supported_versions(supported_versions);
+        self
+    }
+
+    pub fn property(&self, name: &str) -> Option<&str> {
+        self.properties.get(name).map(String::as_str)
+    }
+
+    pub(crate) fn desired_assertion_status(&self, class: &Class) -> bool {
+        self.assertion_status
+            .desired(&class.this_class(), class.bootstrap)
+    }
+
+    pub fn load_main_class(&mut self) -> Result<Rc<Class>, Throwable> {
+        self.resolve_class(&self.main_class.to_owned())
+    }
+
+    pub(crate) fn main_method(&self) -> Option<&Rc<Method>> {
+        self.registry.class(&self.main_class)?.method(MAIN_METHOD)
+    }
+
+    pub fn run(&mut self, args: &[String]) -> Result<(), Throwable> {
+        let class = self.load_main_class()?;
+        let method = class.method(MAIN_METHOD).cloned().ok_or_else(|| {
+            let description = method_description(&self.main_class, MAIN_METHOD);
+            Throwable::new(NO_SUCH_METHOD_ERROR, &description)
+        })?;
+
+        let string_array = self.resolve_class(&array_class_name(STRING))?;
+        let array = self.allocate_array(string_array, args.len())?;
+        // only the handle keeps the arguments alive while they are created
+        // and the main class is initialized
+        self.push_handles(&[Type::Reference(array)]);
+        for (index, arg) in args.iter().enumerate() {
+            let arg = self.new_string(arg)?;
+            self.heap.get_mut(array).fields[index] = Type::Reference(arg);
+        }
+
+        let mut thread = Thread::new(self);
+        thread.initialize(&class)?;
+        thread.invoke(class, method, vec![Type::Reference(array)])?;
+        Ok(())
+    }
+
+    /// Holds onto the references among the values until the handles are
+    /// released, returning the mark to release them with.
+    pub(crate) fn push_handles(&mut self, values: &[Type]) -> usize {
+        let mark = self.handles.len();
+        self.handles
+            .extend(values.iter().filter_map(|value| value.as_reference()));
+        mark
+    }
+
+    /// Releases the handles pushed since the mark.
+    pub(crate) fn pop_handles(&mut self, mark: usize) {
+        self.handles.truncate(mark);
+    }
+
+    /// Runs the closure with the references among the values held in
+    /// handles, for the allocations it makes not to collect them.
+    pub(crate) fn with_handles<T>(
+        &mut self,
+        values: &[Type],
+        f: impl FnOnce(&mut VirtualMachine) -> T,
+    ) -> T {
+        let mark = self.push_handles(values);
+        let result = f(self);
+        self.pop_handles(mark);
+        result
+    }
+
+    /// Makes room for an allocation of the size, collecting garbage when it
+    /// would exceed the maximum heap size. Any reference that is not in the
+    /// frames, a handle or another root may be collected.
+    pub(crate) fn reserve(&mut self, size: usize) -> Result<(), Throwable> {
+        if self.heap.used().saturating_add(size) <= self.max_heap_size {
+            return Ok(());
+        }
+
+        self.collect_garbage();
+        if self.heap.used().saturating_add(size) > self.max_heap_size {
+            return Err(Throwable::new(OUT_OF_MEMORY_ERROR, "Java heap space"));
+        }
+        Ok(())
+    }
+
+    /// Reclaims the objects that are unreachable from the locals and operand
+    /// stacks of the frames, the static fields and resolved constants of the
+    /// classes, the interned strings, the mirrors and the handles.
+    pub(crate) fn collect_garbage(&mut self) {
+        let start = Instant::now();
+        let used = self.heap.used();
+
+        let values = self
+            .frame_stack
+            .iter()
+            .flat_map(|frame| frame.locals.iter().chain(&frame.operand_stack))
+            .filter_map(|value| value.as_reference());
+        let mut roots = values.collect::<Vec<_>>();
+        for class in self.registry.classes.values() {
+            let static_values = class.static_values.borrow();
+            roots.extend(
+                static_values
+                    .iter()
+                    .filter_map(|value| value.as_reference()),
+            );
+            roots.extend(class.resolved_constants.borrow().values());
+        }
+        roots.extend(self.interned_strings.values());
+        roots.extend(self.mirrors.values());
+        roots.extend(&self.handles);
+        let freed = self.heap.collect(roots);
+
+        if self.verbose_gc {
+            let uptime = self.start.elapsed().as_secs_f64();
+            let pause = start.elapsed().as_secs_f64() * 1000.0;
+            println!(
+                "[{:.3}s][info][gc] GC({}) Pause Full (Allocation Failure) {}K->{}K({}K) freed {}K {:.3}ms",
+                uptime,
+                self.collections,
+                used / 1024,
+                (used - freed) / 1024,
+                self.max_heap_size / 1024,
+                freed / 1024,
+                pause
+            );
+        }
+        self.collections += 1;
+    }
 
-        self.eager_load_recursively(&self.main_class.to_owned());
-        self.initialize();
+    /// Allocates an instance of the class, making room for it first.
+    pub(crate) fn allocate(&mut self, class: Rc<Class>) -> Result<Reference, Throwable> {
+        self.reserve(Heap::instance_size(&class))?;
+        Ok(self.heap.allocate(class))
+    }
 
-        let thread = Thread::new(
-            "ru/khlevnov/Main",
-            "main:([Ljava/lang/String;)V",
-            &self.registry,
-        );
-        thread.run();
+    /// Allocates an array of the class, making room for it first.
+    pub(crate) fn allocate_array(
+        &mut self,
+        class: Rc<Class>,
+        length: usize,
+    ) -> Result<Reference, Throwable> {
+        self.reserve(Heap::array_size(length))?;
+        Ok(self.heap.allocate_array(class, length))
     }
 
-    fn eager_load_recursively(&mut self, class_name: &str) {
-        if class_name == "java/lang/Object" {
-            return; // TODO should be loaded from rt.jar before
+    /// Creates a `java.lang.String` with the characters.
+    pub(crate) fn new_string(&mut self, value: &str) -> Result<Reference, Throwable> {
+        let class = self.resolve_class(STRING)?;
+        let byte_array = self.resolve_class("[B")?;
+        let (value_slot, coder_slot) = (class.instance_fields[VALUE], class.instance_fields[CODER]);
+
+        let (bytes, coder) = string::encode(value);
+        // room for both, so that the array is not collected before the
+        // string refers to it
+        let size = Heap::array_size(bytes.len()).saturating_add(Heap::instance_size(&class));
+        self.reserve(size)?;
+        let array = self.heap.allocate_array(byte_array, bytes.len());
+        for (component, byte) in self.heap.get_mut(array).fields.iter_mut().zip(bytes) {
+            *component = Type::Int(byte as i8 as i32);
         }
 
-        if self.registry.classes.contains_key(class_name) {
-            return; // TODO hide into registry
+        let string = self.heap.allocate(class);
+        let fields = &mut self.heap.get_mut(string).fields;
+        fields[value_slot] = Type::Reference(array);
+        fields[coder_slot] = Type::Int(coder);
+        Ok(string)
+    }
+
+    /// The characters of a `java.lang.String`.
+    pub(crate) fn string_value(&self, string: Reference) -> String {
+        let object = self.heap.get(string);
+        let value = object.fields[object.class.instance_fields[VALUE]];
+        let coder = object.fields[object.class.instance_fields[CODER]].int();
+
+        let array = self.heap.get(value.reference().unwrap());
+        let bytes = array
+            .fields
+            .iter()
+            .map(|byte| byte.int() as u8)
+            .collect::<Vec<_>>();
+        string::decode(&bytes, coder)
+    }
+
+    /// The canonical instance of the string with the characters, the one
+    /// `String.intern` returns, created on the first request.
+    pub(crate) fn intern_str(&mut self, value: &str) -> Result<Reference, Throwable> {
+        if let Some(&string) = self.interned_strings.get(value) {
+            return Ok(string);
         }
 
-        let class = self.class_loader.find_class(class_name);
-        let class_name = class.this_class();
+        let string = self.new_string(value)?;
+        self.interned_strings.insert(value.to_string(), string);
+        Ok(string)
+    }
 
-        self.eager_load_recursively(&*class.super_class());
-        self.registry.add_class(class);
-        self.initialization_queue.push(class_name.clone());
+    /// The canonical instance of the string, the string itself if there is
+    /// none yet.
+    pub(crate) fn intern(&mut self, string: Reference) -> Reference {
+        let value = self.string_value(string);
+        *self.interned_strings.entry(value).or_insert(string)
+    }
 
-        let class = self.registry.class(&*class_name);
-        let classes_names = class.constant_pool.classes();
+    /// The `java.lang.Class` instance of the type with the descriptor, such as
+    /// `I`, `V` or `Ljava/lang/String;`, created on the first request along
+    /// with the class.
+    pub(crate) fn mirror(&mut self, descriptor: &str) -> Result<Reference, Throwable> {
+        if let Some(&mirror) = self.mirrors.get(descriptor) {
+            return Ok(mirror);
+        }
+        if let Some(class_name) = descriptor_class_name(descriptor) {
+            self.resolve_class(class_name)?;
+        }
+
+        let class = self.resolve_class(CLASS)?;
+        let descriptor_string = self.new_string(descriptor)?;
+        let mirror = self.with_handles(&[Type::Reference(descriptor_string)], |vm| {
+            vm.allocate(class.clone())
+        })?;
+        let slot = class.instance_fields[CLASS_DESCRIPTOR];
+        self.heap.get_mut(mirror).fields[slot] = Type::Reference(descriptor_string);
+        self.mirrors.insert(descriptor.to_string(), mirror);
+        Ok(mirror)
+    }
 
-        for class_name in classes_names {
-            self.eager_load_recursively(&*class_name);
+    /// The descriptor of the type of a `java.lang.Class` instance.
+    pub(crate) fn mirror_descriptor(&self, mirror: Reference) -> String {
+        let object = self.heap.get(mirror);
+        let descriptor = object.fields[object.class.instance_fields[CLASS_DESCRIPTOR]];
+        self.string_value(descriptor.reference().unwrap())
+    }
+
+    /// Resolves a `CONSTANT_String`, `CONSTANT_Class`, `CONSTANT_MethodType`,
+    /// `CONSTANT_MethodHandle` or `CONSTANT_Dynamic` entry of the class to the
+    /// object it stands for, once per entry (JVMS 5.1, 5.4.3).
+    pub(crate) fn resolve_constant(
+        &mut self,
+        class: &Class,
+        index: u16,
+    ) -> Result<Reference, Throwable> {
+        if let Some(&constant) = class.resolved_constants.borrow().get(&index) {
+            return Ok(constant);
         }
+
+        let constant_pool = &class.constant_pool;
+        let constant = if constant_pool.is_string(index as usize) {
+            self.intern_str(&constant_pool.string(index))?
+        } else if constant_pool.is_class(index as usize) {
+            self.mirror(&class_descriptor(&constant_pool.class(index)))?
+        } else if constant_pool.is_method_type(index as usize) {
+            let descriptor = constant_pool.method_type(index);
+            let descriptor = MethodDescriptor::parse(&descriptor).ok_or_else(|| {
+                let message = format!(
+                    "Illegal method type descriptor {} in class file {}",
+                    descriptor,
+                    class.this_class()
+                );
+                Throwable::new(CLASS_FORMAT_ERROR, &message)
+            })?;
+            method_handle::method_type(self, &descriptor)?
+        } else if constant_pool.is_dynamic(index as usize) {
+            call_site::resolve_dynamic(class, index)?
+        } else {
+            let (kind, class_name, name_and_type) = constant_pool.method_handle(index);
+            method_handle::direct(self, kind, &class_name, &name_and_type)?
+        };
+        class
+            .resolved_constants
+            .borrow_mut()
+            .insert(index, constant);
+        Ok(constant)
     }
 
-    pub fn initialize(&mut self) {
-        for class_name in &self.initialization_queue {
-            if let Some(_) = self.registry.class(class_name).method("<clinit>:()V") {
-                Thread::new(class_name, "<clinit>:()V", &self.registry).run();
+    /// Loads the class on its first resolution, along with its superclass and
+    /// superinterfaces (JVMS 5.3.5), or creates the array class along with
+    /// its component class (JVMS 5.3.3).
+    pub(crate) fn resolve_class(&mut self, name: &str) -> Result<Rc<Class>, Throwable> {
+        if let Some(class) = self.registry.class(name) {
+            return Ok(class.clone());
+        }
+
+        let (class, source) = match name.strip_prefix('[') {
+            Some(component_type) => {
+                let bootstrap = match descriptor_class_name(component_type) {
+                    Some(component) => self.resolve_class(component)?.bootstrap,
+                    None => true,
+                };
+                (self.class_loader.array_class(name, bootstrap), None)
+            }
+            None => {
+                let (class, source) = self.class_loader.find_class(name)?;
+                if class.this_class() != name {
+                    let message = format!("{} (wrong name: {})", name, class.this_class());
+                    return Err(Throwable::new(NO_CLASS_DEF_FOUND_ERROR, &message));
+                }
+                (class, Some(source))
             }
+        };
+        self.define_class(class, source)
+    }
+
+    /// Links the class with its superclass and superinterfaces, loading them
+    /// if needed, and registers it.
+    pub(crate) fn define_class(
+        &mut self,
+        mut class: Class,
+        source: Option<String>,
+    ) -> Result<Rc<Class>, Throwable> {
+        let super_class = match class.super_class() {
+            Some(super_class) => Some(self.resolve_class(&super_class)?),
+            None => None,
+        };
+        let interfaces = class
+            .interfaces()
+            .iter()
+            .map(|interface| self.resolve_class(interface))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if let (true, Some(source)) = (self.verbose_class, source) {
+            let uptime = self.start.elapsed().as_secs_f64();
+            let name = class.this_class().replace('/', ".");
+            println!(
+                "[{:.3}s][info][class,load] {} source: {}",
+                uptime, name, source
+            );
+        }
+
+        let class = Rc::new_cyclic(|this| {
+            class.link(this, super_class.as_ref(), &interfaces);
+            class
+        });
+        self.registry.add_class(class.clone());
+        Ok(class)
+    }
+
+    /// Resolves a method of a class (JVMS 5.4.3.3) or an interface (JVMS
+    /// 5.4.3.4), returning it along with the class that declares it.
+    pub(crate) fn resolve_method(
+        &mut self,
+        class_name: &str,
+        name_and_type: &str,
+        interface: bool,
+    ) -> Result<(Rc<Class>, Rc<Method>), Throwable> {
+        let class = self.resolve_class(class_name)?;
+        if class.is_interface() != interface {
+            let (found, expected) = if interface {
+                ("class", "interface")
+            } else {
+                ("interface", "class")
+            };
+            let message = format!(
+                "Found {} {}, but {} was expected",
+                found,
+                class_name.replace('/', "."),
+                expected
+            );
+            return Err(Throwable::new(INCOMPATIBLE_CLASS_CHANGE_ERROR, &message));
+        }
+
+        let declared = if interface {
+            let declared = class.method(name_and_type);
+            let declared = declared.map(|method| (class.clone(), method.clone()));
+            declared.or_else(|| dispatch::object_method(&self.registry, name_and_type))
+        } else {
+            dispatch::lookup(&self.registry, &class, name_and_type)
+        };
+        if let Some(declared) = declared {
+            return Ok(declared);
+        }
+
+        // any of the maximally-specific superinterface methods is fine unless
+        // exactly one of them is not abstract
+        let candidates = dispatch::maximally_specific(&class.superinterfaces, name_and_type);
+        let mut defaults = candidates.iter().filter(|c| !c.method.is_abstract());
+        let selected = match (defaults.next(), defaults.next()) {
+            (Some(default), None) => Some(default),
+            _ => candidates.first(),
+        };
+        selected
+            .map(|selected| (selected.class(), selected.method.clone()))
+            .ok_or_else(|| {
+                let description = method_description(class_name, name_and_type);
+                Throwable::new(NO_SUCH_METHOD_ERROR, &description)
+            })
+    }
+
+    /// Looks the field up in the class, its superinterfaces and then its
+    /// superclass (JVMS 5.4.3.2), returning the class that declares it.
+    pub(crate) fn resolve_field(
+        &mut self,
+        class_name: &str,
+        name_and_type: &str,
+    ) -> Result<Rc<Class>, Throwable> {
+        let class = self.resolve_class(class_name)?;
+        self.find_field(&class, name_and_type).ok_or_else(|| {
+            let (name, _) = name_and_type.split_once(':').unwrap_or_default();
+            Throwable::new(NO_SUCH_FIELD_ERROR, name)
+        })
+    }
+
+    /// Resolves an instance field to its slot in the objects of the class.
+    pub(crate) fn resolve_instance_field(
+        &mut self,
+        class_name: &str,
+        name_and_type: &str,
+    ) -> Result<usize, Throwable> {
+        let class = self.resolve_field(class_name, name_and_type)?;
+        match class.instance_fields.get(name_and_type) {
+            Some(&slot) => Ok(slot),
+            None => Err(field_change_error("non-static", &class, name_and_type)),
+        }
+    }
+
+    /// Resolves a static field to the class that declares it and its slot there.
+    pub(crate) fn resolve_static_field(
+        &mut self,
+        class_name: &str,
+        name_and_type: &str,
+    ) -> Result<(Rc<Class>, usize), Throwable> {
+        let class = self.resolve_field(class_name, name_and_type)?;
+        match class.static_fields.get(name_and_type) {
+            Some(&slot) => Ok((class, slot)),
+            None => Err(field_change_error("static", &class, name_and_type)),
         }
     }
+
+    fn find_field(&self, class: &Rc<Class>, name_and_type: &str) -> Option<Rc<Class>> {
+        if class.field(name_and_type).is_some() {
+            return Some(class.clone());
+        }
+
+        for interface in class.interfaces() {
+            let interface = self.registry.class(&interface)?;
+            if let Some(class) = self.find_field(interface, name_and_type) {
+                return Some(class);
+            }
+        }
+
+        let super_class = self.registry.class(&class.super_class()?)?;
+        self.find_field(super_class, name_and_type)
+    }
+
+    /// Whether a value of the class can be assigned to the type (JVMS 6.5
+    /// aastore, checkcast).
+    pub(crate) fn is_assignable(&mut self, class_name: &str, target: &str) -> bool {
+        if class_name == target {
+            return true;
+        }
+
+        match (class_name.strip_prefix('['), target.strip_prefix('[')) {
+            (Some(component_type), Some(target_component_type)) => {
+                // primitive component types are only assignable to themselves
+                match (
+                    descriptor_class_name(component_type),
+                    descriptor_class_name(target_component_type),
+                ) {
+                    (Some(component), Some(target_component)) => {
+                        self.is_assignable(component, target_component)
+                    }
+                    _ => false,
+                }
+            }
+            (Some(_), None) => [OBJECT, CLONEABLE, SERIALIZABLE].contains(&target),
+            (None, Some(_)) => false,
+            (None, None) => {
+                let (class, target_class) =
+                    match (self.resolve_class(class_name), self.resolve_class(target)) {
+                        (Ok(class), Ok(target_class)) => (class, target_class),
+                        _ => return false,
+                    };
+                if target_class.is_interface() {
+                    class
+                        .superinterfaces
+                        .iter()
+                        .any(|superinterface| Rc::ptr_eq(superinterface, &target_class))
+                } else {
+                    self.is_subclass_of(class_name, target)
+                }
+            }
+        }
+    }
+
+    pub(crate) fn is_subclass_of(&mut self, class_name: &str, super_class: &str) -> bool {
+        let mut current = Some(class_name.to_string());
+        while let Some(class_name) = current {
+            if class_name == super_class {
+                return true;
+            }
+            current = self
+                .resolve_class(&class_name)
+                .ok()
+                .and_then(|class| class.super_class());
+        }
+        false
+    }
 }
 
-fn print_int(args: &[Type]) -> Type {
+fn field_change_error(expected: &str, class: &Class, name_and_type: &str) -> Throwable {
+    let (name, _) = name_and_type.split_once(':').unwrap_or_default();
+    let class_name = class.this_class().replace('/', ".");
+    let message = format!("Expected {} field {}.{}", expected, class_name, name);
+    Throwable::new(INCOMPATIBLE_CLASS_CHANGE_ERROR, &message)
+}
+
+/// Assertion settings from `-ea`/`-da`, resolved the way
+/// `ClassLoader.desiredAssertionStatus` does: the class itself first, then
+/// its packages from the innermost out, then the default. The default leaves
+/// out system classes, as there is no `-esa`.
+#[derive(Debug, Default)]
+struct AssertionStatus {
+    default: bool,
+    packages: HashMap<String, bool>,
+    classes: HashMap<String, bool>,
+}
+
+impl AssertionStatus {
+    fn set(&mut self, target: &str, enabled: bool) {
+        if target.is_empty() {
+            self.default = enabled;
+        } else if let Some(package) = target.strip_suffix("...") {
+            self.packages.insert(package.replace('.', "/"), enabled);
+        } else {
+            self.classes.insert(target.replace('.', "/"), enabled);
+        }
+    }
+
+    fn desired(&self, class_name: &str, system: bool) -> bool {
+        if let Some(&enabled) = self.classes.get(class_name) {
+            return enabled;
+        }
+
+        let mut package = class_name;
+        while let Some((outer, _)) = package.rsplit_once('/') {
+            if let Some(&enabled) = self.packages.get(outer) {
+                return enabled;
+            }
+            package = outer;
+        }
+
+        match self.packages.get("") {
+            Some(&enabled) if !class_name.contains('/') => enabled,
+            _ => self.default && !system,
+        }
+    }
+}
+
+fn get_class(vm: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    let object = args[0].reference().unwrap();
+    let class_name = vm.heap.get(object).class.this_class();
+    let mirror = vm.mirror(&class_descriptor(&class_name))?;
+    Ok(Type::Reference(mirror))
+}
+
+fn hash_code(vm: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    let object = args[0].reference().unwrap();
+    Ok(Type::Int(vm.heap.identity_hash_code(object)))
+}
+
+fn clone(vm: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    let object = args[0].reference().unwrap();
+    let class = vm.heap.get(object).class.clone();
+    if !class.is_array() && !vm.is_assignable(&class.this_class(), CLONEABLE) {
+        let name = class.this_class().replace('/', ".");
+        return Err(Throwable::new(CLONE_NOT_SUPPORTED_EXCEPTION, &name));
+    }
+    vm.reserve(vm.heap.get(object).size())?;
+    Ok(Type::Reference(vm.heap.copy(object)))
+}
+
+fn to_string(vm: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    let object = args[0].reference().unwrap();
+    let class_name = vm.heap.get(object).class.this_class().replace('/', ".");
+    let hash_code = vm.heap.identity_hash_code(object);
+    let string = vm.new_string(&format!("{}@{:x}", class_name, hash_code))?;
+    Ok(Type::Reference(string))
+}
+
+fn get_name(vm: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    let descriptor = vm.mirror_descriptor(args[0].reference().unwrap());
+    let name = match descriptor.as_bytes()[0] {
+        b'L' => descriptor_class_name(&descriptor)
+            .unwrap()
+            .replace('/', "."),
+        b'[' => descriptor.replace('/', "."),
+        _ => field_signature(&descriptor, false).unwrap(),
+    };
+    Ok(Type::Reference(vm.intern_str(&name)?))
+}
+
+fn get_simple_name(vm: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    let descriptor = vm.mirror_descriptor(args[0].reference().unwrap());
+    let string = vm.new_string(&simple_name(&descriptor))?;
+    Ok(Type::Reference(string))
+}
+
+/// The name as in the source, for which a nested class is taken to be one
+/// with a `$` in its name, the same as for the simple name.
+fn get_canonical_name(vm: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    let descriptor = vm.mirror_descriptor(args[0].reference().unwrap());
+    let name = field_signature(&descriptor, false)
+        .unwrap()
+        .replace('$', ".");
+    Ok(Type::Reference(vm.new_string(&name)?))
+}
+
+fn get_superclass(vm: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    let descriptor = vm.mirror_descriptor(args[0].reference().unwrap());
+    let class = match descriptor_class_name(&descriptor) {
+        Some(class_name) => vm.resolve_class(class_name)?,
+        None => return Ok(Type::Null),
+    };
+    match class.super_class() {
+        Some(super_class) if !class.is_interface() => {
+            Ok(Type::Reference(vm.mirror(&class_descriptor(&super_class))?))
+        }
+        _ => Ok(Type::Null),
+    }
+}
+
+fn is_interface(vm: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    let descriptor = vm.mirror_descriptor(args[0].reference().unwrap());
+    let interface = match descriptor_class_name(&descriptor) {
+        Some(class_name) => vm.resolve_class(class_name)?.is_interface(),
+        None => false,
+    };
+    Ok(Type::Int(interface as i32))
+}
+
+fn is_instance(vm: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    let descriptor = vm.mirror_descriptor(args[0].reference().unwrap());
+    let instance = match (args[1].reference(), descriptor_class_name(&descriptor)) {
+        (Some(object), Some(class_name)) => {
+            let object_class = vm.heap.get(object).class.this_class();
+            vm.is_assignable(&object_class, class_name)
+        }
+        _ => false,
+    };
+    Ok(Type::Int(instance as i32))
+}
+
+fn is_enum(vm: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    let descriptor = vm.mirror_descriptor(args[0].reference().unwrap());
+    let enum_class = match descriptor_class_name(&descriptor) {
+        Some(class_name) => vm.resolve_class(class_name)?.is_enum(),
+        None => false,
+    };
+    Ok(Type::Int(enum_class as i32))
+}
+
+fn array_type(vm: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    let descriptor = vm.mirror_descriptor(args[0].reference().unwrap());
+    if descriptor == "V" {
+        return Err(Throwable::without_message(ILLEGAL_ARGUMENT_EXCEPTION));
+    }
+    Ok(Type::Reference(vm.mirror(&format!("[{}", descriptor))?))
+}
+
+fn desired_assertion_status(vm: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    let descriptor = vm.mirror_descriptor(args[0].reference().unwrap());
+    let enabled = match descriptor_class_name(&descriptor) {
+        Some(class_name) => {
+            let class = vm.resolve_class(class_name)?;
+            vm.desired_assertion_status(&class)
+        }
+        None => false,
+    };
+    Ok(Type::Int(enabled as i32))
+}
+
+fn get_primitive_class(vm: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    let descriptor = match vm.string_value(args[0].reference().unwrap()).as_str() {
+        "boolean" => "Z",
+        "byte" => "B",
+        "char" => "C",
+        "short" => "S",
+        "int" => "I",
+        "long" => "J",
+        "float" => "F",
+        "double" => "D",
+        _ => "V",
+    };
+    Ok(Type::Reference(vm.mirror(descriptor)?))
+}
+
+fn intern(vm: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    let string = args[0].reference().unwrap();
+    Ok(Type::Reference(vm.intern(string)))
+}
+
+fn exit(_: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    io::stdout().flush().ok();
+    process::exit(args[0].int())
+}
+
+fn get_property(vm: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    let name = vm.string_value(args[0].reference().unwrap());
+    match vm.property(&name).map(str::to_string) {
+        Some(value) => Ok(Type::Reference(vm.new_string(&value)?)),
+        None => Ok(Type::Null),
+    }
+}
+
+fn print_int(_: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
     println!("{}", args[0].int());
-    return Type::Void;
+    Ok(Type::Void)
+}
+
+fn print_long(_: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    println!("{}", args[0].long());
+    Ok(Type::Void)
+}
+
+fn print_float(_: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    println!("{}", float_to_string(args[0].float()));
+    Ok(Type::Void)
+}
+
+fn print_double(_: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    println!("{}", double_to_string(args[0].double()));
+    Ok(Type::Void)
+}
+
+fn print_string(vm: &mut VirtualMachine, args: &[Type]) -> Result<Type, Throwable> {
+    match args[0].reference() {
+        Some(string) => println!("{}", vm.string_value(string)),
+        None => println!("null"),
+    }
+    Ok(Type::Void)
 }
 
-fn print_double(args: &[Type]) -> Type {
-    println!("{}", args[0].double());
-    return Type::Void;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_HEAP_SIZE: usize = 64 * 1024;
+
+    #[test]
+    fn collects_garbage_for_new_strings() {
+        let mut vm = VirtualMachine::new(".", "Main").with_max_heap_size(MAX_HEAP_SIZE);
+        let kept = vm.new_string("kept").unwrap();
+        vm.push_handles(&[Type::Reference(kept)]);
+
+        for i in 0..10_000 {
+            vm.new_string(&format!("garbage {}", i)).unwrap();
+            assert!(vm.heap.used() <= MAX_HEAP_SIZE);
+        }
+        assert!(vm.collections > 0);
+        assert_eq!(vm.string_value(kept), "kept");
+    }
+
+    #[test]
+    fn keeps_mirrors_and_method_types_across_collections() {
+        let mut vm = VirtualMachine::new(".", "Main").with_max_heap_size(MAX_HEAP_SIZE);
+        let descriptor = MethodDescriptor::parse("(I[JLjava/lang/String;)V").unwrap();
+
+        for _ in 0..1_000 {
+            let method_type = method_handle::method_type(&mut vm, &descriptor).unwrap();
+            assert_eq!(method_handle::descriptor(&vm, method_type), descriptor);
+            vm.new_string(&"garbage".repeat(16)).unwrap();
+        }
+        assert!(vm.collections > 0);
+        let mirror = vm.mirror("[J").unwrap();
+        assert_eq!(vm.mirror_descriptor(mirror), "[J");
+    }
+
+    #[test]
+    fn runs_out_of_memory_for_a_string_too_large() {
+        let mut vm = VirtualMachine::new(".", "Main").with_max_heap_size(MAX_HEAP_SIZE);
+        let throwable = vm.new_string(&"x".repeat(MAX_HEAP_SIZE)).unwrap_err();
+        assert_eq!(throwable.class_name, OUT_OF_MEMORY_ERROR);
+    }
 }
@@ -0,0 +1,343 @@
+use crate::inflate::inflate;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::path::Path;
+
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x06054b50;
+const CENTRAL_DIRECTORY_HEADER: u32 = 0x02014b50;
+const LOCAL_FILE_HEADER: u32 = 0x04034b50;
+
+const STORED: u16 = 0;
+const DEFLATED: u16 = 8;
+
+#[derive(Debug)]
+struct ZipEntry {
+    method: u16,
+    crc32: u32,
+    compressed_size: usize,
+    size: usize,
+    local_header_offset: usize,
+}
+
+#[derive(Debug)]
+pub struct ZipArchive {
+    bytes: Vec<u8>,
+    entries: HashMap<String, ZipEntry>,
+}
+
+impl ZipArchive {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<ZipArchive> {
+        ZipArchive::new(fs::read(path)?)
+    }
+
+    pub fn new(bytes: Vec<u8>) -> Result<ZipArchive> {
+        let end = end_of_central_directory(&bytes)?;
+        let count = u16_at(&bytes, end + 10)? as usize;
+        let mut offset = u32_at(&bytes, end + 16)? as usize;
+
+        let mut entries = HashMap::with_capacity(count);
+        for _ in 0..count {
+            if u32_at(&bytes, offset)? != CENTRAL_DIRECTORY_HEADER {
+                return Err(invalid("invalid central directory header"));
+            }
+
+            let name_length = u16_at(&bytes, offset + 28)? as usize;
+            let extra_length = u16_at(&bytes, offset + 30)? as usize;
+            let comment_length = u16_at(&bytes, offset + 32)? as usize;
+            let name = bytes
+                .get(offset + 46..offset + 46 + name_length)
+                .ok_or_else(|| invalid("truncated central directory"))?;
+
+            let entry = ZipEntry {
+                method: u16_at(&bytes, offset + 10)?,
+                crc32: u32_at(&bytes, offset + 16)?,
+                compressed_size: u32_at(&bytes, offset + 20)? as usize,
+                size: u32_at(&bytes, offset + 24)? as usize,
+                local_header_offset: u32_at(&bytes, offset + 42)? as usize,
+            };
+            entries.insert(String::from_utf8_lossy(name).into_owned(), entry);
+
+            offset += 46 + name_length + extra_length + comment_length;
+        }
+
+        Ok(ZipArchive { bytes, entries })
+    }
+
+    #[cfg(test)]
+    pub(crate) fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    pub fn read(&self, name: &str) -> Option<Result<Vec<u8>>> {
+        self.entries.get(name).map(|entry| self.read_entry(entry))
+    }
+
+    fn read_entry(&self, entry: &ZipEntry) -> Result<Vec<u8>> {
+        let offset = entry.local_header_offset;
+        if u32_at(&self.bytes, offset)? != LOCAL_FILE_HEADER {
+            return Err(invalid("invalid local file header"));
+        }
+
+        let name_length = u16_at(&self.bytes, offset + 26)? as usize;
+        let extra_length = u16_at(&self.bytes, offset + 28)? as usize;
+        let start = offset + 30 + name_length + extra_length;
+        let data = self
+            .bytes
+            .get(start..start + entry.compressed_size)
+            .ok_or_else(|| invalid("truncated entry"))?;
+
+        let bytes = match entry.method {
+            STORED => data.to_vec(),
+            DEFLATED => inflate(data, entry.size)?,
+            method => {
+                let message = format!("unsupported compression method {}", method);
+                return Err(Error::new(ErrorKind::InvalidData, message));
+            }
+        };
+
+        if bytes.len() != entry.size || crc32(&bytes) != entry.crc32 {
+            return Err(invalid("invalid entry size or CRC"));
+        }
+        Ok(bytes)
+    }
+}
+
+fn end_of_central_directory(bytes: &[u8]) -> Result<usize> {
+    let last = bytes
+        .len()
+        .checked_sub(22)
+        .ok_or_else(|| invalid("zip file is empty"))?;
+    let first = last.saturating_sub(u16::MAX as usize);
+
+    (first..=last)
+        .rev()
+        .find(|&offset| u32_at(bytes, offset).ok() == Some(END_OF_CENTRAL_DIRECTORY))
+        .ok_or_else(|| invalid("end of central directory not found"))
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> Result<u16> {
+    bytes
+        .get(offset..offset + 2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        .ok_or_else(|| invalid("unexpected end of zip file"))
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> Result<u32> {
+    bytes
+        .get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .ok_or_else(|| invalid("unexpected end of zip file"))
+}
+
+fn invalid(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message.to_string())
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb88320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// "hello hello hello hello\n" compressed with zlib's raw deflate.
+    const DEFLATED_HELLO: &[u8] = &[
+        0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x57, 0xc8, 0x40, 0x27, 0xb9, 0x00,
+    ];
+    const HELLO: &[u8] = b"hello hello hello hello\n";
+
+    pub(crate) struct Entry<'a> {
+        pub(crate) name: &'a str,
+        pub(crate) method: u16,
+        pub(crate) data: &'a [u8],
+        pub(crate) content: &'a [u8],
+    }
+
+    pub(crate) fn stored<'a>(name: &'a str, content: &'a [u8]) -> Entry<'a> {
+        Entry {
+            name,
+            method: STORED,
+            data: content,
+            content,
+        }
+    }
+
+    /// Builds an archive of the entries, with the local file headers followed
+    /// by the central directory. The fields the reader ignores are zero.
+    pub(crate) fn archive(entries: &[Entry]) -> Vec<u8> {
+        let mut bytes = vec![];
+        let mut central_directory = vec![];
+        for entry in entries {
+            let offset = bytes.len() as u32;
+            let sizes = [
+                crc32(entry.content),
+                entry.data.len() as u32,
+                entry.content.len() as u32,
+            ];
+
+            bytes.extend(LOCAL_FILE_HEADER.to_le_bytes());
+            bytes.extend([0; 4]);
+            bytes.extend(entry.method.to_le_bytes());
+            bytes.extend([0; 4]);
+            sizes.iter().for_each(|x| bytes.extend(x.to_le_bytes()));
+            bytes.extend((entry.name.len() as u16).to_le_bytes());
+            bytes.extend([0; 2]);
+            bytes.extend(entry.name.as_bytes());
+            bytes.extend(entry.data);
+
+            central_directory.extend(CENTRAL_DIRECTORY_HEADER.to_le_bytes());
+            central_directory.extend([0; 6]);
+            central_directory.extend(entry.method.to_le_bytes());
+            central_directory.extend([0; 4]);
+            sizes
+                .iter()
+                .for_each(|x| central_directory.extend(x.to_le_bytes()));
+            central_directory.extend((entry.name.len() as u16).to_le_bytes());
+            central_directory.extend([0; 12]);
+            central_directory.extend(offset.to_le_bytes());
+            central_directory.extend(entry.name.as_bytes());
+        }
+
+        let offset = bytes.len() as u32;
+        let size = central_directory.len() as u32;
+        bytes.extend(central_directory);
+        bytes.extend(END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+        bytes.extend([0; 4]);
+        bytes.extend((entries.len() as u16).to_le_bytes());
+        bytes.extend((entries.len() as u16).to_le_bytes());
+        bytes.extend(size.to_le_bytes());
+        bytes.extend(offset.to_le_bytes());
+        bytes.extend(0u16.to_le_bytes());
+        bytes
+    }
+
+    fn hello_archive() -> Vec<u8> {
+        archive(&[
+            stored("stored.txt", HELLO),
+            Entry {
+                name: "deflated.txt",
+                method: DEFLATED,
+                data: DEFLATED_HELLO,
+                content: HELLO,
+            },
+        ])
+    }
+
+    fn error(bytes: Vec<u8>) -> String {
+        ZipArchive::new(bytes).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn reads_stored_and_deflated_entries() {
+        let archive = ZipArchive::new(hello_archive()).unwrap();
+        assert_eq!(archive.read("stored.txt").unwrap().unwrap(), HELLO);
+        assert_eq!(archive.read("deflated.txt").unwrap().unwrap(), HELLO);
+        assert!(archive.read("missing.txt").is_none());
+    }
+
+    #[test]
+    fn reads_archive_with_trailing_comment() {
+        let mut bytes = hello_archive();
+        let length = bytes.len();
+        bytes[length - 2..].copy_from_slice(&5u16.to_le_bytes());
+        bytes.extend(b"hello");
+        let archive = ZipArchive::new(bytes).unwrap();
+        assert_eq!(archive.read("deflated.txt").unwrap().unwrap(), HELLO);
+    }
+
+    #[test]
+    fn rejects_missing_end_of_central_directory() {
+        assert_eq!(error(vec![]), "zip file is empty");
+        assert_eq!(error(vec![0; 100]), "end of central directory not found");
+    }
+
+    #[test]
+    fn rejects_corrupt_central_directory() {
+        let bytes = hello_archive();
+        let end = bytes.len() - 22;
+        let offset = u32_at(&bytes, end + 16).unwrap() as usize;
+
+        let mut corrupt = bytes.clone();
+        corrupt[offset] ^= 0xff;
+        assert_eq!(error(corrupt), "invalid central directory header");
+
+        // more entries than there are records
+        let mut corrupt = bytes.clone();
+        corrupt[end + 10] = 3;
+        assert_eq!(error(corrupt), "invalid central directory header");
+
+        // the directory starting past the end of the file
+        let mut corrupt = bytes.clone();
+        corrupt[end + 16..end + 20].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert_eq!(error(corrupt), "unexpected end of zip file");
+
+        // a name running past the end of the file
+        let mut corrupt = bytes;
+        corrupt[offset + 28..offset + 30].copy_from_slice(&u16::MAX.to_le_bytes());
+        assert!(ZipArchive::new(corrupt).is_err());
+    }
+
+    #[test]
+    fn rejects_corrupt_entries() {
+        let read = |bytes: Vec<u8>, name: &str| {
+            let archive = ZipArchive::new(bytes).unwrap();
+            archive.read(name).unwrap().unwrap_err().to_string()
+        };
+
+        // a bad local file header signature
+        let mut bytes = hello_archive();
+        bytes[0] ^= 0xff;
+        assert_eq!(read(bytes, "stored.txt"), "invalid local file header");
+
+        // the contents changed
+        let mut bytes = hello_archive();
+        bytes[30 + "stored.txt".len()] ^= 0xff;
+        assert_eq!(read(bytes, "stored.txt"), "invalid entry size or CRC");
+
+        // an unsupported compression method
+        let bytes = archive(&[Entry {
+            name: "bzip2.txt",
+            method: 12,
+            data: HELLO,
+            content: HELLO,
+        }]);
+        assert_eq!(
+            read(bytes, "bzip2.txt"),
+            "unsupported compression method 12"
+        );
+
+        // a truncated deflate stream
+        let bytes = archive(&[Entry {
+            name: "deflated.txt",
+            method: DEFLATED,
+            data: &DEFLATED_HELLO[..5],
+            content: HELLO,
+        }]);
+        assert_eq!(read(bytes, "deflated.txt"), "unexpected end of stream");
+    }
+
+    #[test]
+    fn rejects_single_byte_mutations_without_panicking() {
+        let bytes = hello_archive();
+        for offset in 0..bytes.len() {
+            for mask in [0x01, 0x80, 0xff] {
+                let mut mutated = bytes.clone();
+                mutated[offset] ^= mask;
+                if let Ok(archive) = ZipArchive::new(mutated) {
+                    for name in ["stored.txt", "deflated.txt"] {
+                        let _ = archive.read(name);
+                    }
+                }
+            }
+        }
+    }
+}
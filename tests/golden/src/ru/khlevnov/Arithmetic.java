@@ -0,0 +1,101 @@
+package ru.khlevnov;
+
+public class Arithmetic {
+    static void ints(int a, int b) {
+        PrintStream.print(a + b);
+        PrintStream.print(a - b);
+        PrintStream.print(a * b);
+        PrintStream.print(a / b);
+        PrintStream.print(a % b);
+        PrintStream.print(-a);
+        PrintStream.print(a & b);
+        PrintStream.print(a | b);
+        PrintStream.print(a ^ b);
+        PrintStream.print(a << b);
+        PrintStream.print(a >> b);
+        PrintStream.print(a >>> b);
+    }
+
+    static void longs(long a, long b, int shift) {
+        PrintStream.print(a + b);
+        PrintStream.print(a - b);
+        PrintStream.print(a * b);
+        PrintStream.print(a / b);
+        PrintStream.print(a % b);
+        PrintStream.print(-a);
+        PrintStream.print(a & b);
+        PrintStream.print(a | b);
+        PrintStream.print(a ^ b);
+        PrintStream.print(a << shift);
+        PrintStream.print(a >> shift);
+        PrintStream.print(a >>> shift);
+    }
+
+    static void floats(float a, float b) {
+        PrintStream.print(a + b);
+        PrintStream.print(a - b);
+        PrintStream.print(a * b);
+        PrintStream.print(a / b);
+        PrintStream.print(a % b);
+        PrintStream.print(-a);
+    }
+
+    static void doubles(double a, double b) {
+        PrintStream.print(a + b);
+        PrintStream.print(a - b);
+        PrintStream.print(a * b);
+        PrintStream.print(a / b);
+        PrintStream.print(a % b);
+        PrintStream.print(-a);
+    }
+
+    static void increments(int a) {
+        a++;
+        PrintStream.print(a);
+        a += 127;
+        PrintStream.print(a);
+        a -= 128;
+        PrintStream.print(a);
+        a--;
+        PrintStream.print(a);
+    }
+
+    public static void main(String[] args) {
+        ints(7, 3);
+        ints(-7, 3);
+        ints(Integer.MIN_VALUE, -1);
+        ints(Integer.MAX_VALUE, 33);
+        ints(-1, 31);
+        ints(-123456, -2);
+
+        longs(7L, 3L, 3);
+        longs(-7L, 3L, 64);
+        longs(Long.MIN_VALUE, -1L, 63);
+        longs(Long.MAX_VALUE, 2L, 65);
+        longs(-1L, 1L << 40, -1);
+
+        floats(7.5f, 2f);
+        floats(-7.5f, 2f);
+        floats(1f, 0f);
+        floats(-1f, 0f);
+        floats(0f, 0f);
+        floats(0.1f, 3f);
+        floats(3.4e38f, 10f);
+        floats(Float.NaN, 1f);
+        floats(Float.POSITIVE_INFINITY, Float.NEGATIVE_INFINITY);
+
+        doubles(7.5, 2);
+        doubles(-7.5, 2);
+        doubles(1, 0);
+        doubles(-1, 0);
+        doubles(0, 0);
+        doubles(0.1, 0.2);
+        doubles(1e300, 1e-300);
+        doubles(-5.5, 0.75);
+        doubles(Double.NaN, 1);
+        doubles(Double.POSITIVE_INFINITY, 1e308);
+
+        increments(0);
+        increments(Integer.MAX_VALUE);
+    }
+}
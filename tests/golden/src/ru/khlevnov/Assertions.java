@@ -0,0 +1,45 @@
+package ru.khlevnov;
+
+import static ru.khlevnov.PrintStream.print;
+
+public class Assertions {
+    static class Nested {
+        static void check(int x) {
+            assert x > 0 : x;
+            print("checked " + x);
+        }
+    }
+
+    static void check(String value) {
+        try {
+            assert value != null : "value is null";
+            print("checked " + value);
+        } catch (AssertionError e) {
+            print("caught " + e.getMessage());
+        }
+    }
+
+    public static void main(String[] args) {
+        print(Assertions.class.desiredAssertionStatus() ? 1 : 0);
+        print(Nested.class.desiredAssertionStatus() ? 1 : 0);
+        print(PrintStream.class.desiredAssertionStatus() ? 1 : 0);
+        print(String.class.desiredAssertionStatus() ? 1 : 0);
+
+        check("value");
+        check(null);
+        try {
+            Nested.check(1);
+            Nested.check(-1);
+        } catch (AssertionError e) {
+            print("caught " + e.getMessage());
+        }
+
+        print(System.getProperty("greeting"));
+        print(System.getProperty("missing", "default"));
+        try {
+            System.getProperty("");
+        } catch (IllegalArgumentException e) {
+            print(e.getMessage());
+        }
+    }
+}
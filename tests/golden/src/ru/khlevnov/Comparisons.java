@@ -0,0 +1,55 @@
+package ru.khlevnov;
+
+public class Comparisons {
+    static void longs(long a, long b) {
+        PrintStream.print(a < b ? 1 : 0);
+        PrintStream.print(a <= b ? 1 : 0);
+        PrintStream.print(a > b ? 1 : 0);
+        PrintStream.print(a >= b ? 1 : 0);
+        PrintStream.print(a == b ? 1 : 0);
+        PrintStream.print(a != b ? 1 : 0);
+    }
+
+    static void floats(float a, float b) {
+        PrintStream.print(a < b ? 1 : 0);
+        PrintStream.print(a <= b ? 1 : 0);
+        PrintStream.print(a > b ? 1 : 0);
+        PrintStream.print(a >= b ? 1 : 0);
+        PrintStream.print(a == b ? 1 : 0);
+        PrintStream.print(a != b ? 1 : 0);
+    }
+
+    static void doubles(double a, double b) {
+        PrintStream.print(a < b ? 1 : 0);
+        PrintStream.print(a <= b ? 1 : 0);
+        PrintStream.print(a > b ? 1 : 0);
+        PrintStream.print(a >= b ? 1 : 0);
+        PrintStream.print(a == b ? 1 : 0);
+        PrintStream.print(a != b ? 1 : 0);
+    }
+
+    public static void main(String[] args) {
+        longs(1L, 2L);
+        longs(2L, 1L);
+        longs(3L, 3L);
+        longs(Long.MIN_VALUE, Long.MAX_VALUE);
+
+        floats(1f, 2f);
+        floats(2f, 1f);
+        floats(3f, 3f);
+        floats(0f, -0f);
+        floats(Float.NaN, 1f);
+        floats(1f, Float.NaN);
+        floats(Float.NaN, Float.NaN);
+        floats(Float.NEGATIVE_INFINITY, Float.POSITIVE_INFINITY);
+
+        doubles(1, 2);
+        doubles(2, 1);
+        doubles(3, 3);
+        doubles(0.0, -0.0);
+        doubles(Double.NaN, 1);
+        doubles(1, Double.NaN);
+        doubles(Double.NaN, Double.NaN);
+        doubles(Double.NEGATIVE_INFINITY, Double.POSITIVE_INFINITY);
+    }
+}
@@ -0,0 +1,78 @@
+package ru.khlevnov;
+
+public class Conversions {
+    static void fromInt(int x) {
+        PrintStream.print((long) x);
+        PrintStream.print((double) (float) x);
+        PrintStream.print((double) x);
+        PrintStream.print((byte) x);
+        PrintStream.print((int) (char) x);
+        PrintStream.print((short) x);
+    }
+
+    static void fromLong(long x) {
+        PrintStream.print((int) x);
+        PrintStream.print((double) (float) x);
+        PrintStream.print((double) x);
+    }
+
+    static void fromFloat(float x) {
+        PrintStream.print((int) x);
+        PrintStream.print((long) x);
+        PrintStream.print((double) x);
+    }
+
+    static void fromDouble(double x) {
+        PrintStream.print((int) x);
+        PrintStream.print((long) x);
+        PrintStream.print((float) x);
+    }
+
+    public static void main(String[] args) {
+        fromInt(0);
+        fromInt(-1);
+        fromInt(200);
+        fromInt(40000);
+        fromInt(-40000);
+        fromInt(Integer.MAX_VALUE);
+        fromInt(Integer.MIN_VALUE);
+        fromInt(16777217);
+
+        fromLong(0L);
+        fromLong(-1L);
+        fromLong(1L << 32);
+        fromLong((1L << 32) + 5);
+        fromLong(Long.MAX_VALUE);
+        fromLong(Long.MIN_VALUE);
+        fromLong(9007199254740993L);
+
+        fromFloat(0f);
+        fromFloat(-0f);
+        fromFloat(2.9f);
+        fromFloat(-2.9f);
+        fromFloat(1e10f);
+        fromFloat(-1e10f);
+        fromFloat(1e20f);
+        fromFloat(0.1f);
+        fromFloat(Float.NaN);
+        fromFloat(Float.POSITIVE_INFINITY);
+        fromFloat(Float.NEGATIVE_INFINITY);
+        fromFloat(Float.MIN_VALUE);
+
+        fromDouble(0);
+        fromDouble(-0.0);
+        fromDouble(2.9);
+        fromDouble(-2.9);
+        fromDouble(1e10);
+        fromDouble(-1e10);
+        fromDouble(1e20);
+        fromDouble(-1e20);
+        fromDouble(0.1);
+        fromDouble(1e-50);
+        fromDouble(1e300);
+        fromDouble(Double.NaN);
+        fromDouble(Double.POSITIVE_INFINITY);
+        fromDouble(Double.NEGATIVE_INFINITY);
+        fromDouble(123456.789);
+    }
+}
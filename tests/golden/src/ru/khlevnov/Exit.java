@@ -0,0 +1,12 @@
+package ru.khlevnov;
+
+public class Exit {
+    public static void main(String[] args) {
+        try {
+            PrintStream.print("exiting");
+            System.exit(3);
+        } finally {
+            PrintStream.print("not reached");
+        }
+    }
+}
@@ -0,0 +1,55 @@
+package ru.khlevnov;
+
+public class FloatingPoint {
+    static void floats() {
+        PrintStream.print(Float.MIN_VALUE);
+        PrintStream.print(-Float.MIN_VALUE);
+        PrintStream.print(0x1p-145f);
+        PrintStream.print(0x0.02468ap-126f);
+        PrintStream.print(0x0.fffffep-126f);
+        PrintStream.print(Float.MIN_NORMAL);
+        PrintStream.print(Float.MAX_VALUE);
+        PrintStream.print(0.1f);
+        PrintStream.print(1f / 3);
+        PrintStream.print(2.2856919e9f);
+        PrintStream.print(0.001f);
+        PrintStream.print(1e-4f);
+        PrintStream.print(9999999f);
+        PrintStream.print(1e7f);
+        PrintStream.print(123456.7f);
+        PrintStream.print(100f);
+        PrintStream.print(-0f);
+        PrintStream.print(Float.NaN);
+        PrintStream.print(Float.NEGATIVE_INFINITY);
+    }
+
+    static void doubles() {
+        PrintStream.print(Double.MIN_VALUE);
+        PrintStream.print(0x0.0000123456789p-1022);
+        PrintStream.print(Double.MIN_NORMAL);
+        PrintStream.print(Double.MAX_VALUE);
+        PrintStream.print(0.1 + 0.2);
+        PrintStream.print(2.0 / 3);
+        PrintStream.print(1e23);
+        PrintStream.print(0.002);
+        PrintStream.print(1e-4);
+        PrintStream.print(1e7);
+        PrintStream.print(-1234.5);
+        PrintStream.print(Double.POSITIVE_INFINITY);
+    }
+
+    static void concatenated(float f, double d) {
+        PrintStream.print("f=" + f + ", d=" + d);
+        PrintStream.print(new StringBuilder().append(f).append(' ').append(d).toString());
+    }
+
+    public static void main(String[] args) {
+        floats();
+        doubles();
+        concatenated(Float.MIN_VALUE, Double.MIN_VALUE);
+        concatenated(0x1p-145f, 1e23);
+        concatenated(2.2856919e9f, 0.1 + 0.2);
+        concatenated(-0f, 1e-4);
+        concatenated(Float.NaN, Double.NEGATIVE_INFINITY);
+    }
+}
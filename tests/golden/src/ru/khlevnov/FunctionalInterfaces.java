@@ -0,0 +1,86 @@
+package ru.khlevnov;
+
+import static ru.khlevnov.PrintStream.print;
+
+import java.util.function.BiFunction;
+import java.util.function.BinaryOperator;
+import java.util.function.Consumer;
+import java.util.function.Function;
+import java.util.function.IntBinaryOperator;
+import java.util.function.IntPredicate;
+import java.util.function.IntUnaryOperator;
+import java.util.function.Predicate;
+import java.util.function.Supplier;
+import java.util.function.ToIntFunction;
+import java.util.function.UnaryOperator;
+
+public class FunctionalInterfaces {
+    static int counter;
+
+    static <T, R> R applyTwice(Function<T, T> function, Function<T, R> last, T value) {
+        return last.apply(function.apply(function.apply(value)));
+    }
+
+    public static void main(String[] args) {
+        Function<Integer, Integer> square = x -> x * x;
+        Function<Integer, Integer> increment = x -> x + 1;
+        print(square.apply(7));
+        print(square.andThen(increment).apply(7));
+        print(square.compose(increment).apply(7));
+        print(Function.<Integer>identity().apply(-3));
+        print(applyTwice(square, x -> "squared twice " + x, 3));
+
+        BiFunction<Integer, Long, Long> multiply = (a, b) -> a * b;
+        print(multiply.apply(1 << 20, 1L << 20));
+        print(multiply.andThen(x -> x / 2.0).apply(3, 5L));
+        BinaryOperator<String> join = (a, b) -> a + "-" + b;
+        print(join.apply("left", "right"));
+        UnaryOperator<String> shout = s -> s + "!";
+        print(shout.apply("hey"));
+
+        Supplier<Character> letter = () -> 'x';
+        print("" + letter.get());
+        Supplier<Double> half = () -> 0.5;
+        print(half.get() * 3);
+
+        Predicate<String> empty = String::isEmpty;
+        print(empty.test("") ? 1 : 0);
+        print(empty.negate().test("") ? 1 : 0);
+        print(empty.or(s -> s.length() > 3).test("long") ? 1 : 0);
+        print(empty.and(s -> s.length() > 3).test("long") ? 1 : 0);
+        print(Predicate.isEqual("a").test("a") ? 1 : 0);
+        print(Predicate.not(empty).test("a") ? 1 : 0);
+
+        ToIntFunction<String> length = String::length;
+        print(length.applyAsInt("four"));
+        IntUnaryOperator negate = x -> -x;
+        print(negate.andThen(x -> x * 10).applyAsInt(4));
+        IntBinaryOperator max = (a, b) -> a > b ? a : b;
+        print(max.applyAsInt(-3, 2));
+        IntPredicate even = x -> x % 2 == 0;
+        print(even.or(x -> x > 10).test(11) ? 1 : 0);
+
+        Runnable count = () -> counter++;
+        count.run();
+        count.run();
+        print(counter);
+
+        StringBuilder builder = new StringBuilder();
+        Consumer<String> append = builder::append;
+        append.andThen(s -> builder.append(s.length())).accept("abc");
+        builder.append(',').append(true).append(' ').append(-12L).append(1.5f).append((Object) null);
+        print(builder.toString());
+        print(builder.length());
+        builder.insert(0, "<").append('>').deleteCharAt(1).setCharAt(0, '[');
+        print(builder.toString());
+        print(new StringBuilder("stressed").reverse().toString());
+        print(new StringBuilder("a😀b").reverse().toString());
+        builder.setLength(3);
+        print(builder.toString());
+        try {
+            builder.charAt(3);
+        } catch (StringIndexOutOfBoundsException e) {
+            print("out of bounds");
+        }
+    }
+}
@@ -0,0 +1,82 @@
+package ru.khlevnov;
+
+public class GarbageCollection {
+    static class Node {
+        final int value;
+        final Node next;
+
+        Node(int value, Node next) {
+            this.value = value;
+            this.next = next;
+        }
+    }
+
+    interface Supplier {
+        String get();
+    }
+
+    static Node list;
+    static int[] retained;
+
+    static int sum(Node node) {
+        int sum = 0;
+        for (; node != null; node = node.next) {
+            sum += node.value;
+        }
+        return sum;
+    }
+
+    static int[] garbage(int round) {
+        int[] array = new int[1 << 20];
+        array[round % array.length] = round;
+        array[array.length - 1] = -round;
+        return array;
+    }
+
+    public static void main(String[] args) {
+        for (int i = 0; i < 100; i++) {
+            list = new Node(i, list);
+        }
+        Node local = new Node(-1, new Node(-2, null));
+        retained = new int[] {4, 5, 6};
+        String literal = "literal";
+        Object lock = new Object();
+        String name = "count " + retained.length;
+        int tag = 7;
+        Supplier supplier = () -> name + " and " + tag;
+
+        // far more than the maximum heap size in total, but only the last
+        // arrays are reachable at any time
+        int[][] recent = new int[4][];
+        long checksum = 0;
+        for (int round = 0; round < 64; round++) {
+            int[] array = garbage(round);
+            recent[round % recent.length] = array;
+            checksum += array[round % array.length] - array[array.length - 1];
+            String text = "round " + round;
+            checksum += text.length();
+        }
+        PrintStream.print(checksum);
+        for (int[] array : recent) {
+            PrintStream.print(array[array.length - 1]);
+        }
+
+        PrintStream.print(sum(list));
+        PrintStream.print(sum(local));
+        PrintStream.print(retained[0] + retained[1] + retained[2]);
+        PrintStream.print(literal == "literal" ? 1 : 0);
+        PrintStream.print(literal == "liter" + "al".intern() ? 1 : 0);
+        PrintStream.print(lock.getClass() == Object.class ? 1 : 0);
+        PrintStream.print(name);
+        PrintStream.print(supplier.get());
+
+        try {
+            long[][] huge = new long[1 << 16][1 << 16];
+            PrintStream.print(huge.length);
+        } catch (OutOfMemoryError e) {
+            PrintStream.print("caught " + e.getMessage());
+        }
+        int[] after = new int[1 << 20];
+        PrintStream.print(after.length);
+    }
+}
@@ -0,0 +1,178 @@
+package ru.khlevnov;
+
+import java.lang.invoke.MethodHandle;
+import java.lang.invoke.MethodHandles;
+import java.lang.invoke.MethodType;
+import java.lang.invoke.WrongMethodTypeException;
+
+public class Handles {
+    interface Shape {
+        int area();
+    }
+
+    static class Rectangle implements Shape {
+        int width;
+        int height;
+
+        Rectangle(int width, int height) {
+            this.width = width;
+            this.height = height;
+        }
+
+        public int area() {
+            return width * height;
+        }
+
+        long scaled(long factor) {
+            return area() * factor;
+        }
+
+        public String toString() {
+            return "Rectangle(" + width + "x" + height + ")";
+        }
+    }
+
+    static class Square extends Rectangle {
+        Square(int side) {
+            super(side, side);
+        }
+
+        public String toString() {
+            return "Square(" + width + ")";
+        }
+    }
+
+    static int counter = 5;
+
+    static int add(int a, int b) {
+        return a + b;
+    }
+
+    static double mix(long a, float b, double c) {
+        return a + b + c;
+    }
+
+    static String describe(Object o) {
+        return "object " + o;
+    }
+
+    static void bump() {
+        counter++;
+    }
+
+    public static void main(String[] args) throws Throwable {
+        MethodHandles.Lookup lookup = MethodHandles.lookup();
+
+        MethodType intBinary = MethodType.methodType(int.class, int.class, int.class);
+        PrintStream.print(intBinary.toString());
+        PrintStream.print(intBinary.equals(MethodType.methodType(int.class, int.class, int.class)) ? 1 : 0);
+        PrintStream.print(intBinary.changeReturnType(long.class).toString());
+        PrintStream.print(intBinary.dropParameterTypes(0, 1).parameterCount());
+        PrintStream.print(MethodType.methodType(void.class, String[].class).toMethodDescriptorString());
+
+        MethodHandle add = lookup.findStatic(Handles.class, "add", intBinary);
+        PrintStream.print(add.toString());
+        PrintStream.print((int) add.invokeExact(2, 3));
+        PrintStream.print((long) add.invoke(40, 2));
+        Object boxed = add.invoke(Integer.valueOf(7), (short) 8);
+        PrintStream.print(boxed.toString());
+
+        MethodHandle mix = lookup.findStatic(Handles.class, "mix",
+                MethodType.methodType(double.class, long.class, float.class, double.class));
+        PrintStream.print((double) mix.invoke(1, 'a', 0.5f));
+
+        MethodHandle describe = lookup.findStatic(Handles.class, "describe",
+                MethodType.methodType(String.class, Object.class));
+        PrintStream.print((String) describe.invoke(42));
+        PrintStream.print((String) describe.invokeExact((Object) "text"));
+
+        MethodHandle bump = lookup.findStatic(Handles.class, "bump", MethodType.methodType(void.class));
+        bump.invokeExact();
+        bump.invoke();
+        PrintStream.print(counter);
+
+        MethodHandle constructor = lookup.findConstructor(Rectangle.class,
+                MethodType.methodType(void.class, int.class, int.class));
+        PrintStream.print(constructor.type().toString());
+        Rectangle rectangle = (Rectangle) constructor.invokeExact(3, 4);
+        PrintStream.print(rectangle.toString());
+
+        MethodHandle area = lookup.findVirtual(Shape.class, "area", MethodType.methodType(int.class));
+        PrintStream.print(area.type().toString());
+        PrintStream.print((int) area.invokeExact((Shape) rectangle));
+        PrintStream.print((int) area.invoke(new Square(5)));
+
+        MethodHandle scaled = lookup.findVirtual(Rectangle.class, "scaled",
+                MethodType.methodType(long.class, long.class));
+        PrintStream.print((long) scaled.invoke(new Square(2), 10));
+        MethodHandle bound = scaled.bindTo(rectangle);
+        PrintStream.print(bound.type().toString());
+        PrintStream.print((long) bound.invokeExact(3L));
+        MethodHandle toString = lookup.bind(rectangle, "toString", MethodType.methodType(String.class));
+        PrintStream.print((String) toString.invokeExact());
+
+        MethodHandle width = lookup.findGetter(Rectangle.class, "width", int.class);
+        MethodHandle setWidth = lookup.findSetter(Rectangle.class, "width", int.class);
+        setWidth.invokeExact(rectangle, 10);
+        PrintStream.print((int) width.invokeExact(rectangle));
+        PrintStream.print(rectangle.area());
+
+        MethodHandle getCounter = lookup.findStaticGetter(Handles.class, "counter", int.class);
+        MethodHandle setCounter = lookup.findStaticSetter(Handles.class, "counter", int.class);
+        setCounter.invoke((byte) 100);
+        PrintStream.print((int) getCounter.invokeExact());
+
+        MethodHandle generic = add.asType(MethodType.methodType(Object.class, Object.class, Object.class));
+        PrintStream.print(generic.type().toString());
+        PrintStream.print(generic.invoke(20, 22).toString());
+        PrintStream.print(add.asType(intBinary) == add ? 1 : 0);
+
+        try {
+            long wrong = (long) add.invokeExact(1, 2);
+            PrintStream.print(wrong);
+        } catch (WrongMethodTypeException e) {
+            PrintStream.print("caught " + e.getMessage());
+        }
+        try {
+            add.asType(MethodType.methodType(int.class, String.class, int.class));
+        } catch (WrongMethodTypeException e) {
+            PrintStream.print("caught " + e.getMessage());
+        }
+        try {
+            generic.invoke("one", 2);
+        } catch (ClassCastException e) {
+            PrintStream.print("caught ClassCastException");
+        }
+        try {
+            area.invoke((Shape) null);
+        } catch (NullPointerException e) {
+            PrintStream.print("caught NullPointerException");
+        }
+        try {
+            lookup.findStatic(Handles.class, "add", MethodType.methodType(long.class, int.class, int.class));
+        } catch (NoSuchMethodException e) {
+            PrintStream.print("caught NoSuchMethodException");
+        }
+        try {
+            lookup.findGetter(Rectangle.class, "depth", int.class);
+        } catch (NoSuchFieldException e) {
+            PrintStream.print("caught NoSuchFieldException");
+        }
+        try {
+            MethodType.methodType(int.class, void.class);
+        } catch (IllegalArgumentException e) {
+            PrintStream.print("caught IllegalArgumentException");
+        }
+
+        PrintStream.print(int.class.getName());
+        PrintStream.print(String.class.getName());
+        PrintStream.print(Rectangle.class.getName());
+        PrintStream.print(Rectangle.class.getSimpleName());
+        PrintStream.print(int[][].class.getName());
+        PrintStream.print(Object[].class.getSimpleName());
+        PrintStream.print(rectangle.getClass() == Rectangle.class ? 1 : 0);
+        PrintStream.print(Shape.class.isInstance(new Square(1)) ? 1 : 0);
+        PrintStream.print(Shape.class.isInterface() ? 1 : 0);
+        PrintStream.print(int.class.isPrimitive() ? 1 : 0);
+    }
+}
@@ -0,0 +1,168 @@
+package ru.khlevnov;
+
+public class Lambdas {
+    interface IntOp {
+        int apply(int a, int b);
+    }
+
+    interface Wide {
+        double apply(long a, double b, int c);
+    }
+
+    interface IntToLong {
+        long apply(int x);
+    }
+
+    interface Action {
+        void run();
+    }
+
+    interface Function<T, R> {
+        R apply(T t);
+    }
+
+    // the lambdas need a bridge for the erased apply(Object)
+    interface StringOp extends Function<String, String> {
+        String apply(String s);
+    }
+
+    interface ToInt<T> {
+        int apply(T t);
+    }
+
+    interface Factory {
+        Counter create(int start);
+    }
+
+    interface Marker {
+    }
+
+    interface Ops {
+        static int sub(int a, int b) {
+            return a - b;
+        }
+
+        int base();
+
+        default IntOp adder() {
+            return (a, b) -> a + b + base();
+        }
+    }
+
+    static class Counter implements Ops {
+        private int value;
+
+        Counter(int value) {
+            this.value = value;
+        }
+
+        int add(int delta) {
+            value += delta;
+            return value;
+        }
+
+        int value() {
+            return value;
+        }
+
+        public int base() {
+            return value * 100;
+        }
+
+        public String toString() {
+            return "Counter(" + value + ")";
+        }
+    }
+
+    private int offset = 7;
+
+    static int max(int a, int b) {
+        return a > b ? a : b;
+    }
+
+    static long twice(long x) {
+        return 2 * x;
+    }
+
+    IntOp withOffset() {
+        return (a, b) -> a * b + offset;
+    }
+
+    static int fold(int[] values, int initial, IntOp op) {
+        int result = initial;
+        for (int value : values) {
+            result = op.apply(result, value);
+        }
+        return result;
+    }
+
+    static String concat(int i, long l, char c, boolean z, float f, double d, String s, Object o) {
+        return "i=" + i + ", l=" + l + ", c=" + c + ", z=" + z + ", f=" + f + ", d=" + d
+                + ", s=" + s + ", o=" + o;
+    }
+
+    public static void main(String[] args) {
+        int[] values = {3, 1, 4, 1, 5, 9, 2, 6};
+        PrintStream.print(fold(values, 0, (a, b) -> a + b));
+        PrintStream.print(fold(values, 1, (a, b) -> a * b % 1000));
+        PrintStream.print(fold(values, Integer.MIN_VALUE, Lambdas::max));
+        PrintStream.print(fold(values, 100, Ops::sub));
+
+        int factor = 3;
+        PrintStream.print(fold(values, 0, (a, b) -> a + b * factor));
+        PrintStream.print(new Lambdas().withOffset().apply(6, 7));
+
+        Wide wide = (a, b, c) -> a + b * c;
+        PrintStream.print(wide.apply(1L << 40, 0.5, 3));
+        IntToLong widening = Lambdas::twice;
+        PrintStream.print(widening.apply(Integer.MAX_VALUE));
+
+        Counter counter = new Counter(10);
+        IntToLong bound = counter::add;
+        bound.apply(5);
+        PrintStream.print(bound.apply(-2));
+        ToInt<Counter> unbound = Counter::value;
+        PrintStream.print(unbound.apply(counter));
+        Factory factory = Counter::new;
+        PrintStream.print(factory.create(42).value());
+        PrintStream.print(counter.adder().apply(1, 2));
+
+        int[] runs = new int[1];
+        Action action = () -> runs[0]++;
+        for (int i = 0; i < 5; i++) {
+            action.run();
+        }
+        PrintStream.print(runs[0]);
+
+        String suffix = "!";
+        StringOp shout = s -> s + suffix;
+        PrintStream.print(shout.apply("hello"));
+        Function<String, String> erased = shout;
+        PrintStream.print(erased.apply("bridged"));
+
+        IntOp marked = (IntOp & Marker) (a, b) -> a - b;
+        PrintStream.print(marked instanceof Marker ? marked.apply(10, 4) : -1);
+        IntOp serializable = (IntOp & java.io.Serializable) (a, b) -> a << b;
+        PrintStream.print(serializable instanceof java.io.Serializable ? serializable.apply(1, 10) : -1);
+
+        IntOp first = (a, b) -> a;
+        IntOp second = (a, b) -> b;
+        PrintStream.print(first.apply(1, 2) + second.apply(1, 2));
+
+        PrintStream.print(concat(-5, 1L << 40, 'é', true, 1.5f, 0.1, "str", counter));
+        PrintStream.print(concat(0, 0, '\u0001', false, -0.0f, 1e10, null, null));
+        PrintStream.print("tags \u0001 and \u0002 are kept: " + factor);
+        String empty = "";
+        for (int i = 0; i < 3; i++) {
+            empty = empty + i + ",";
+        }
+        PrintStream.print(empty);
+
+        try {
+            IntOp divide = (a, b) -> a / b;
+            divide.apply(1, 0);
+        } catch (ArithmeticException e) {
+            PrintStream.print("caught " + e.getMessage());
+        }
+    }
+}
@@ -0,0 +1,10 @@
+package ru.khlevnov;
+
+public class Launcher {
+    public static void main(String[] args) {
+        PrintStream.print(args.length);
+        for (String arg : args) {
+            PrintStream.print(arg);
+        }
+    }
+}
@@ -0,0 +1,143 @@
+package ru.khlevnov;
+
+public class Loops {
+    static int sum(int n) {
+        int sum = 0;
+        for (int i = 1; i <= n; i++) {
+            sum += i;
+        }
+        return sum;
+    }
+
+    static long countDown(long n) {
+        long steps = 0;
+        while (n > 0) {
+            n -= 3;
+            steps++;
+        }
+        return steps * 100 + n;
+    }
+
+    static int collatz(int n) {
+        int steps = 0;
+        do {
+            n = (n & 1) == 0 ? n / 2 : 3 * n + 1;
+            steps++;
+        } while (n != 1);
+        return steps;
+    }
+
+    static int primes(int limit) {
+        int count = 0;
+        outer:
+        for (int n = 2; n < limit; n++) {
+            for (int d = 2; d * d <= n; d++) {
+                if (n % d == 0) {
+                    continue outer;
+                }
+            }
+            count++;
+        }
+        return count;
+    }
+
+    static int firstSquareAbove(int x) {
+        int i = 0;
+        while (true) {
+            if (i * i > x) {
+                break;
+            }
+            i++;
+        }
+        return i;
+    }
+
+    static int matrix(int n) {
+        int[][] cells = new int[n][n];
+        for (int i = 0; i < n; i++) {
+            for (int j = 0; j < n; j++) {
+                cells[i][j] = i * n + j;
+            }
+        }
+        int trace = 0;
+        for (int i = n - 1; i >= 0; i--) {
+            trace += cells[i][i] - cells[i][n - 1 - i];
+        }
+        return trace;
+    }
+
+    static int switchInLoop(int n) {
+        int result = 0;
+        for (int i = 0; i < n; i++) {
+            switch (i % 5) {
+                case 0:
+                    result += 1;
+                    break;
+                case 1:
+                    result *= 2;
+                    break;
+                case 3:
+                    continue;
+                default:
+                    result -= 3;
+            }
+            result ^= i;
+        }
+        return result;
+    }
+
+    static int divisions(int n) {
+        int failures = 0;
+        int total = 0;
+        for (int i = -n; i <= n; i++) {
+            try {
+                total += 100 / i;
+            } catch (ArithmeticException e) {
+                failures++;
+            }
+        }
+        return total * 10 + failures;
+    }
+
+    static int synchronizedSum(Object lock, int n) {
+        int sum = 0;
+        for (int i = 0; i < n; i++) {
+            synchronized (lock) {
+                sum += i;
+            }
+        }
+        return sum;
+    }
+
+    static int synchronizedDivision(Object lock, int x) {
+        try {
+            synchronized (lock) {
+                return 100 / x;
+            }
+        } catch (ArithmeticException e) {
+            return -1;
+        }
+    }
+
+    public static void main(String[] args) {
+        PrintStream.print(sum(0));
+        PrintStream.print(sum(100));
+        PrintStream.print(sum(100000));
+        PrintStream.print(countDown(10));
+        PrintStream.print(countDown(-1));
+        PrintStream.print(collatz(27));
+        PrintStream.print(primes(1000));
+        PrintStream.print(firstSquareAbove(1000));
+        PrintStream.print(matrix(7));
+        PrintStream.print(switchInLoop(23));
+        PrintStream.print(divisions(7));
+        PrintStream.print(synchronizedSum(new Object(), 10));
+        PrintStream.print(synchronizedDivision("lock", 7));
+        PrintStream.print(synchronizedDivision("lock", 0));
+        try {
+            synchronizedSum(null, 1);
+        } catch (NullPointerException e) {
+            PrintStream.print("null lock");
+        }
+    }
+}
@@ -0,0 +1,13 @@
+package ru.khlevnov;
+
+public class PrintStream {
+    static native void print(int x);
+
+    static native void print(long x);
+
+    static native void print(float x);
+
+    static native void print(double x);
+
+    static native void print(String x);
+}
@@ -0,0 +1,124 @@
+package ru.khlevnov;
+
+public class StackOperations {
+    int field;
+    long longField;
+    static long staticLong;
+
+    static int ignored() {
+        return 1;
+    }
+
+    static long ignoredLong() {
+        return 2L;
+    }
+
+    static void dups() {
+        ignored();
+        ignoredLong();
+
+        StackOperations operations = new StackOperations();
+        int x = operations.field = 3;
+        PrintStream.print(x);
+        long y = operations.longField = 4L;
+        PrintStream.print(y);
+        operations.field++;
+        operations.longField += 10;
+        PrintStream.print(operations.field);
+        PrintStream.print(operations.longField);
+        long z = staticLong = 5L;
+        PrintStream.print(z);
+
+        int[] ints = new int[2];
+        int a = ints[0] = 6;
+        ints[1]++;
+        PrintStream.print(a + ints[1]);
+        long[] longs = new long[2];
+        long b = longs[0] = 7L;
+        longs[1] += 8;
+        PrintStream.print(b + longs[1]);
+        double[] doubles = new double[1];
+        double c = doubles[0] = 0.5;
+        doubles[0] *= 3;
+        PrintStream.print(c + doubles[0]);
+    }
+
+    /** Has more than 256 local variables, which need wide loads and stores. */
+    static void wide(int seed) {
+        int v0 = seed; int v1 = v0 + 1; int v2 = v1 + 1; int v3 = v2 + 1; int v4 = v3 + 1;
+        int v5 = v4 + 1; int v6 = v5 + 1; int v7 = v6 + 1; int v8 = v7 + 1; int v9 = v8 + 1;
+        int v10 = v9 + 1; int v11 = v10 + 1; int v12 = v11 + 1; int v13 = v12 + 1; int v14 = v13 + 1;
+        int v15 = v14 + 1; int v16 = v15 + 1; int v17 = v16 + 1; int v18 = v17 + 1; int v19 = v18 + 1;
+        int v20 = v19 + 1; int v21 = v20 + 1; int v22 = v21 + 1; int v23 = v22 + 1; int v24 = v23 + 1;
+        int v25 = v24 + 1; int v26 = v25 + 1; int v27 = v26 + 1; int v28 = v27 + 1; int v29 = v28 + 1;
+        int v30 = v29 + 1; int v31 = v30 + 1; int v32 = v31 + 1; int v33 = v32 + 1; int v34 = v33 + 1;
+        int v35 = v34 + 1; int v36 = v35 + 1; int v37 = v36 + 1; int v38 = v37 + 1; int v39 = v38 + 1;
+        int v40 = v39 + 1; int v41 = v40 + 1; int v42 = v41 + 1; int v43 = v42 + 1; int v44 = v43 + 1;
+        int v45 = v44 + 1; int v46 = v45 + 1; int v47 = v46 + 1; int v48 = v47 + 1; int v49 = v48 + 1;
+        int v50 = v49 + 1; int v51 = v50 + 1; int v52 = v51 + 1; int v53 = v52 + 1; int v54 = v53 + 1;
+        int v55 = v54 + 1; int v56 = v55 + 1; int v57 = v56 + 1; int v58 = v57 + 1; int v59 = v58 + 1;
+        int v60 = v59 + 1; int v61 = v60 + 1; int v62 = v61 + 1; int v63 = v62 + 1; int v64 = v63 + 1;
+        int v65 = v64 + 1; int v66 = v65 + 1; int v67 = v66 + 1; int v68 = v67 + 1; int v69 = v68 + 1;
+        int v70 = v69 + 1; int v71 = v70 + 1; int v72 = v71 + 1; int v73 = v72 + 1; int v74 = v73 + 1;
+        int v75 = v74 + 1; int v76 = v75 + 1; int v77 = v76 + 1; int v78 = v77 + 1; int v79 = v78 + 1;
+        int v80 = v79 + 1; int v81 = v80 + 1; int v82 = v81 + 1; int v83 = v82 + 1; int v84 = v83 + 1;
+        int v85 = v84 + 1; int v86 = v85 + 1; int v87 = v86 + 1; int v88 = v87 + 1; int v89 = v88 + 1;
+        int v90 = v89 + 1; int v91 = v90 + 1; int v92 = v91 + 1; int v93 = v92 + 1; int v94 = v93 + 1;
+        int v95 = v94 + 1; int v96 = v95 + 1; int v97 = v96 + 1; int v98 = v97 + 1; int v99 = v98 + 1;
+        int v100 = v99 + 1; int v101 = v100 + 1; int v102 = v101 + 1; int v103 = v102 + 1; int v104 = v103 + 1;
+        int v105 = v104 + 1; int v106 = v105 + 1; int v107 = v106 + 1; int v108 = v107 + 1; int v109 = v108 + 1;
+        int v110 = v109 + 1; int v111 = v110 + 1; int v112 = v111 + 1; int v113 = v112 + 1; int v114 = v113 + 1;
+        int v115 = v114 + 1; int v116 = v115 + 1; int v117 = v116 + 1; int v118 = v117 + 1; int v119 = v118 + 1;
+        int v120 = v119 + 1; int v121 = v120 + 1; int v122 = v121 + 1; int v123 = v122 + 1; int v124 = v123 + 1;
+        int v125 = v124 + 1; int v126 = v125 + 1; int v127 = v126 + 1; int v128 = v127 + 1; int v129 = v128 + 1;
+        int v130 = v129 + 1; int v131 = v130 + 1; int v132 = v131 + 1; int v133 = v132 + 1; int v134 = v133 + 1;
+        int v135 = v134 + 1; int v136 = v135 + 1; int v137 = v136 + 1; int v138 = v137 + 1; int v139 = v138 + 1;
+        int v140 = v139 + 1; int v141 = v140 + 1; int v142 = v141 + 1; int v143 = v142 + 1; int v144 = v143 + 1;
+        int v145 = v144 + 1; int v146 = v145 + 1; int v147 = v146 + 1; int v148 = v147 + 1; int v149 = v148 + 1;
+        int v150 = v149 + 1; int v151 = v150 + 1; int v152 = v151 + 1; int v153 = v152 + 1; int v154 = v153 + 1;
+        int v155 = v154 + 1; int v156 = v155 + 1; int v157 = v156 + 1; int v158 = v157 + 1; int v159 = v158 + 1;
+        int v160 = v159 + 1; int v161 = v160 + 1; int v162 = v161 + 1; int v163 = v162 + 1; int v164 = v163 + 1;
+        int v165 = v164 + 1; int v166 = v165 + 1; int v167 = v166 + 1; int v168 = v167 + 1; int v169 = v168 + 1;
+        int v170 = v169 + 1; int v171 = v170 + 1; int v172 = v171 + 1; int v173 = v172 + 1; int v174 = v173 + 1;
+        int v175 = v174 + 1; int v176 = v175 + 1; int v177 = v176 + 1; int v178 = v177 + 1; int v179 = v178 + 1;
+        int v180 = v179 + 1; int v181 = v180 + 1; int v182 = v181 + 1; int v183 = v182 + 1; int v184 = v183 + 1;
+        int v185 = v184 + 1; int v186 = v185 + 1; int v187 = v186 + 1; int v188 = v187 + 1; int v189 = v188 + 1;
+        int v190 = v189 + 1; int v191 = v190 + 1; int v192 = v191 + 1; int v193 = v192 + 1; int v194 = v193 + 1;
+        int v195 = v194 + 1; int v196 = v195 + 1; int v197 = v196 + 1; int v198 = v197 + 1; int v199 = v198 + 1;
+        int v200 = v199 + 1; int v201 = v200 + 1; int v202 = v201 + 1; int v203 = v202 + 1; int v204 = v203 + 1;
+        int v205 = v204 + 1; int v206 = v205 + 1; int v207 = v206 + 1; int v208 = v207 + 1; int v209 = v208 + 1;
+        int v210 = v209 + 1; int v211 = v210 + 1; int v212 = v211 + 1; int v213 = v212 + 1; int v214 = v213 + 1;
+        int v215 = v214 + 1; int v216 = v215 + 1; int v217 = v216 + 1; int v218 = v217 + 1; int v219 = v218 + 1;
+        int v220 = v219 + 1; int v221 = v220 + 1; int v222 = v221 + 1; int v223 = v222 + 1; int v224 = v223 + 1;
+        int v225 = v224 + 1; int v226 = v225 + 1; int v227 = v226 + 1; int v228 = v227 + 1; int v229 = v228 + 1;
+        int v230 = v229 + 1; int v231 = v230 + 1; int v232 = v231 + 1; int v233 = v232 + 1; int v234 = v233 + 1;
+        int v235 = v234 + 1; int v236 = v235 + 1; int v237 = v236 + 1; int v238 = v237 + 1; int v239 = v238 + 1;
+        int v240 = v239 + 1; int v241 = v240 + 1; int v242 = v241 + 1; int v243 = v242 + 1; int v244 = v243 + 1;
+        int v245 = v244 + 1; int v246 = v245 + 1; int v247 = v246 + 1; int v248 = v247 + 1; int v249 = v248 + 1;
+        int v250 = v249 + 1; int v251 = v250 + 1; int v252 = v251 + 1; int v253 = v252 + 1; int v254 = v253 + 1;
+        int v255 = v254 + 1; int v256 = v255 + 1; int v257 = v256 + 1; int v258 = v257 + 1; int v259 = v258 + 1;
+        long l = v259;
+        float f = v258;
+        double d = v257;
+        int[] array = {v256};
+        v259 += 1000;
+        v258 -= 30000;
+        v257++;
+        l += v0;
+        f /= 4;
+        d *= 2;
+        PrintStream.print(v259);
+        PrintStream.print(v258);
+        PrintStream.print(v257);
+        PrintStream.print(l);
+        PrintStream.print(f);
+        PrintStream.print(d);
+        PrintStream.print(array[0]);
+    }
+
+    public static void main(String[] args) {
+        dups();
+        wide(1);
+        wide(-500);
+    }
+}
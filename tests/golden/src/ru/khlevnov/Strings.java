@@ -0,0 +1,78 @@
+package ru.khlevnov;
+
+public class Strings {
+    static final String GREETING = "hello";
+
+    static String literal() {
+        return "hello";
+    }
+
+    static String fromChars(char... chars) {
+        return new String(chars);
+    }
+
+    static int same(Object a, Object b) {
+        return a == b ? 1 : 0;
+    }
+
+    static int equal(String a, Object b) {
+        return a.equals(b) ? 1 : 0;
+    }
+
+    public static void main(String[] args) {
+        PrintStream.print(args.length);
+        PrintStream.print("hello");
+        PrintStream.print("");
+        PrintStream.print("Привет, мир");
+        PrintStream.print("été ÿ € 😀");
+        PrintStream.print((String) null);
+
+        PrintStream.print(same("hello", literal()));
+        PrintStream.print(same(GREETING, literal()));
+
+        String built = fromChars('h', 'e', 'l', 'l', 'o');
+        PrintStream.print(built);
+        PrintStream.print(same(built, "hello"));
+        PrintStream.print(equal(built, "hello"));
+        PrintStream.print(same(built.intern(), "hello"));
+        PrintStream.print(equal(built, "hell"));
+        PrintStream.print(equal(built, null));
+
+        String unicode = fromChars('м', 'x', '€');
+        PrintStream.print(unicode);
+        PrintStream.print(unicode.length());
+        PrintStream.print(unicode.charAt(2));
+        PrintStream.print(same(unicode.intern(), unicode));
+        PrintStream.print(same(fromChars('м', 'x', '€').intern(), unicode));
+
+        PrintStream.print("hello".hashCode());
+        PrintStream.print(unicode.hashCode());
+        PrintStream.print("".hashCode());
+        PrintStream.print("".isEmpty() ? 1 : 0);
+        PrintStream.print("Привет".length());
+        PrintStream.print("Привет".charAt(1));
+
+        try {
+            "abc".charAt(3);
+        } catch (StringIndexOutOfBoundsException e) {
+            PrintStream.print(3);
+        }
+
+        try {
+            throw new RuntimeException("thrown with a message");
+        } catch (RuntimeException e) {
+            PrintStream.print(e.getMessage());
+        }
+        try {
+            PrintStream.print(1 / (args.length));
+        } catch (ArithmeticException e) {
+            PrintStream.print(e.getMessage());
+        }
+        try {
+            int[] array = new int[3];
+            PrintStream.print(array[args.length + 5]);
+        } catch (ArrayIndexOutOfBoundsException e) {
+            PrintStream.print(e.getMessage());
+        }
+    }
+}
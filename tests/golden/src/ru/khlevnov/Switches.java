@@ -0,0 +1,211 @@
+package ru.khlevnov;
+
+public class Switches {
+    enum Color {
+        RED,
+        GREEN,
+        BLUE {
+            public String toString() {
+                return "blue";
+            }
+        };
+    }
+
+    static int table(int x) {
+        switch (x) {
+            case 0:
+                return 10;
+            case 1:
+                return 11;
+            case 2:
+            case 3:
+                return 12;
+            case 5:
+                return 15;
+            default:
+                return -1;
+        }
+    }
+
+    static int negativeTable(long y, int x) {
+        switch (x) {
+            case -2:
+                return 20;
+            case -1:
+                return 21;
+            case 0:
+                return 22;
+            case 1:
+                return 23;
+            default:
+                return (int) y;
+        }
+    }
+
+    static int lookup(int x) {
+        switch (x) {
+            case -1000000:
+                return 30;
+            case -5:
+                return 31;
+            case 100:
+                return 32;
+            case 100000:
+                return 33;
+            case Integer.MAX_VALUE:
+                return 34;
+            default:
+                return -3;
+        }
+    }
+
+    static int fallThrough(int x) {
+        int result = 0;
+        switch (x) {
+            case 1:
+                result += 1;
+            case 2:
+                result += 10;
+                break;
+            case 3:
+                result += 100;
+            default:
+                result += 1000;
+        }
+        return result;
+    }
+
+    static int extremes(int x) {
+        switch (x) {
+            case Integer.MIN_VALUE:
+                return 40;
+            case Integer.MIN_VALUE + 1:
+                return 41;
+            case Integer.MIN_VALUE + 2:
+                return 42;
+            default:
+                return -4;
+        }
+    }
+
+    static int padded(int x) {
+        switch (-x) {
+            case 1:
+                return 50;
+            case 2:
+                return 51;
+            case 3:
+                return 52;
+            default:
+                return -5;
+        }
+    }
+
+    static int paddedLookup(int x) {
+        switch (x * 7) {
+            case -7:
+                return 60;
+            case 7000:
+                return 61;
+            default:
+                return -6;
+        }
+    }
+
+    static int color(Color color) {
+        switch (color) {
+            case RED:
+                return 70;
+            case BLUE:
+                return 72;
+            default:
+                return -7;
+        }
+    }
+
+    static int string(String s) {
+        switch (s) {
+            case "one":
+                return 1;
+            case "two":
+                return 2;
+            // the same hash code as "Aa"
+            case "BB":
+                return 3;
+            case "Aa":
+                return 4;
+            default:
+                return -8;
+        }
+    }
+
+    public static void main(String[] args) {
+        PrintStream.print(table(-1));
+        PrintStream.print(table(0));
+        PrintStream.print(table(1));
+        PrintStream.print(table(3));
+        PrintStream.print(table(4));
+        PrintStream.print(table(5));
+        PrintStream.print(table(6));
+        PrintStream.print(table(Integer.MIN_VALUE));
+        PrintStream.print(table(Integer.MAX_VALUE));
+
+        PrintStream.print(negativeTable(-9L, -3));
+        PrintStream.print(negativeTable(-9L, -2));
+        PrintStream.print(negativeTable(-9L, 0));
+        PrintStream.print(negativeTable(-9L, 1));
+        PrintStream.print(negativeTable(-9L, 2));
+
+        PrintStream.print(lookup(-1000000));
+        PrintStream.print(lookup(-5));
+        PrintStream.print(lookup(0));
+        PrintStream.print(lookup(100));
+        PrintStream.print(lookup(100000));
+        PrintStream.print(lookup(Integer.MAX_VALUE));
+        PrintStream.print(lookup(Integer.MIN_VALUE));
+
+        PrintStream.print(fallThrough(1));
+        PrintStream.print(fallThrough(2));
+        PrintStream.print(fallThrough(3));
+        PrintStream.print(fallThrough(4));
+
+        PrintStream.print(extremes(Integer.MIN_VALUE));
+        PrintStream.print(extremes(Integer.MIN_VALUE + 2));
+        PrintStream.print(extremes(Integer.MAX_VALUE));
+        PrintStream.print(extremes(0));
+
+        PrintStream.print(padded(-1));
+        PrintStream.print(padded(-3));
+        PrintStream.print(padded(3));
+        PrintStream.print(paddedLookup(-1));
+        PrintStream.print(paddedLookup(1000));
+        PrintStream.print(paddedLookup(1));
+
+        for (Color color : Color.values()) {
+            PrintStream.print(color.ordinal() + " " + color.name() + " " + color + " " + color(color));
+        }
+        Color[] values = Color.values();
+        values[0] = null;
+        PrintStream.print(Color.values()[0].name());
+        PrintStream.print(Color.valueOf("GREEN") == Color.GREEN ? 1 : 0);
+        PrintStream.print(Color.BLUE.getDeclaringClass().getName());
+        PrintStream.print(Color.RED.compareTo(Color.BLUE));
+        try {
+            Color.valueOf("PURPLE");
+        } catch (IllegalArgumentException e) {
+            PrintStream.print(e.getMessage());
+        }
+        try {
+            color(null);
+        } catch (NullPointerException e) {
+            PrintStream.print("null color");
+        }
+
+        PrintStream.print(string("one"));
+        PrintStream.print(string("two"));
+        PrintStream.print(string("BB"));
+        PrintStream.print(string("Aa"));
+        PrintStream.print(string("three"));
+        PrintStream.print(string(""));
+    }
+}
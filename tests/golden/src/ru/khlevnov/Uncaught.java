@@ -0,0 +1,16 @@
+package ru.khlevnov;
+
+public class Uncaught {
+    static void fail(int depth) {
+        if (depth == 0) {
+            throw new IllegalStateException("uncaught");
+        }
+        fail(depth - 1);
+    }
+
+    public static void main(String[] args) {
+        PrintStream.print("failing");
+        fail(3);
+        PrintStream.print("not reached");
+    }
+}
@@ -0,0 +1,20 @@
+#!/bin/sh
+# Records the output of javap -v -p for the golden and playground classes,
+# which the disassembler tests compare against, in <set>/<class>.txt. The
+# Classfile, Last modified and checksum lines depend on the checkout and
+# are left out.
+set -e
+cd "$(dirname "$0")"
+
+record() {
+    rm -rf "$1"
+    for class in $(cd "$2" && find . -name '*.class'); do
+        output="$1/${class%.class}.txt"
+        mkdir -p "$(dirname "$output")"
+        javap -J-Dfile.encoding=UTF-8 -J-Dsun.stdout.encoding=UTF-8 -v -p \
+            "$2/$class" | tail -n +4 > "$output"
+    done
+}
+
+record golden ../golden/classes
+record playground ../../playground/classes